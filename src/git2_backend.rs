@@ -0,0 +1,510 @@
+// Copyright 2022 Anthony Mugendi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::{
+    decorations, git_date, identity_matches, parse_body, parse_date, parse_trailers, BranchStatus,
+    ChangeKind, Commit, CommitStats, FileChange, GitBackend, LogQuery, SignatureStatus, Status,
+    StatusEntry,
+};
+use anyhow::{bail, Result};
+use git2::{
+    BranchType, Delta, DescribeFormatOptions, DescribeOptions, DiffOptions, Repository, Sort,
+    Status as S, StatusOptions,
+};
+use std::path::Path;
+
+/// A [GitBackend] that reads repos with libgit2 through the ```git2``` crate, so no ```git``` binary is needed.
+/// Enabled by the ```backend-git2``` feature.
+///
+/// It differs from [GitCli](super::GitCli) in a few ways: commit signatures are not verified,
+/// so [Commit::signed_by] stays None, as does [Commit::signature]
+/// unless the commit is unsigned, and the default branch is only read from
+/// ```refs/remotes/origin/HEAD```, the remote itself is never asked
+/// ## Example
+/// ```
+///  # use commit_info::{Git2Backend, Info};
+///  # use std::env;
+///  # fn main() -> anyhow::Result<()> {
+///  # let mut path = env::current_dir().unwrap();
+///  # path.push("test_project");
+///  # let dir = path.to_string_lossy().to_string();
+///  // let dir = "/path/to/repo"; <- Point to the location of t=your repo
+///  let info = Info::new(&dir).with_backend(Git2Backend).gather()?;
+///  println!("{:#?}", info);
+///  # Ok(())
+///  # }
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Git2Backend;
+
+impl GitBackend for Git2Backend {
+    fn status(&self, dir: &Path, pathspecs: &[&str]) -> Result<Status> {
+        let mut status = Status::default();
+        read_status(dir, pathspecs, &mut status)?;
+        Ok(status)
+    }
+
+    fn log(&self, dir: &Path, query: &LogQuery) -> Result<Vec<Commit>> {
+        let repo = Repository::open(dir)?;
+        let mailmap = match query.use_mailmap {
+            true => Some(repo.mailmap()?),
+            false => None,
+        };
+
+        let mut walk = repo.revwalk()?;
+        walk.set_sorting(Sort::TOPOLOGICAL | Sort::TIME)?;
+        if query.first_parent {
+            walk.simplify_first_parent()?;
+        }
+        if query.revision.contains("..") {
+            walk.push_range(&query.revision)?;
+        } else {
+            walk.push(
+                repo.revparse_single(&query.revision)?
+                    .peel_to_commit()?
+                    .id(),
+            )?;
+        }
+
+        let decorations = decorations(repo.references()?.filter_map(|reference| {
+            let reference = reference.ok()?;
+            let commit = reference.peel_to_commit().ok()?;
+            Some((reference.name()?.to_string(), oid_string(&commit.id())))
+        }));
+
+        let author_regex = query.author_regex()?;
+        let committer_regex = query.committer_regex()?;
+        let grep_regex = query.grep_regex()?;
+        let mut commits = vec![];
+        let mut skip = query.skip;
+        for oid in walk {
+            if query.max_count.is_some_and(|max| commits.len() >= max) {
+                break;
+            }
+            let commit = repo.find_commit(oid?)?;
+            if !query.include_merges && commit.parent_count() > 1 {
+                continue;
+            }
+            if !query.in_date_range(commit.time().seconds()) {
+                continue;
+            }
+            let people = [
+                (author_regex.as_ref(), commit.author()),
+                (committer_regex.as_ref(), commit.committer()),
+            ];
+            if !people.iter().all(|(regex, person)| {
+                identity_matches(
+                    *regex,
+                    &String::from_utf8_lossy(person.name_bytes()),
+                    &String::from_utf8_lossy(person.email_bytes()),
+                )
+            }) {
+                continue;
+            }
+            if let Some(regex) = &grep_regex {
+                if !regex.is_match(&String::from_utf8_lossy(commit.message_bytes())) {
+                    continue;
+                }
+            }
+            if !query.pathspecs.is_empty() && !touches(&repo, &commit, &query.pathspecs)? {
+                continue;
+            }
+            if skip > 0 {
+                skip -= 1;
+                continue;
+            }
+
+            let (author, committer) = match &mailmap {
+                Some(mailmap) => (
+                    commit.author_with_mailmap(mailmap)?,
+                    commit.committer_with_mailmap(mailmap)?,
+                ),
+                None => (commit.author(), commit.committer()),
+            };
+            let message = String::from_utf8_lossy(commit.message_bytes());
+            let committed = commit.committer().when();
+            let tree = commit.tree()?;
+            let short_tree = tree.as_object().short_id()?;
+            let hash = oid_string(&commit.id());
+            let (tags, branches) = decorations.get(&hash).cloned().unwrap_or_default();
+
+            commits.push(
+                Commit {
+                    commit_date: parse_date(&git_date(
+                        committed.seconds(),
+                        committed.offset_minutes(),
+                    )),
+                    author_date: parse_date(&git_date(
+                        commit.author().when().seconds(),
+                        commit.author().when().offset_minutes(),
+                    )),
+                    commit_message: commit.summary().map(String::from),
+                    commit_subject: commit.summary().map(String::from),
+                    commit_body: parse_body(&message),
+                    author_name: author.name().map(String::from),
+                    author_email: author.email().map(String::from),
+                    committer_name: committer.name().map(String::from),
+                    committer_email: committer.email().map(String::from),
+                    tree_hash: short_tree.as_str().map(String::from),
+                    hash: Some(hash),
+                    short_hash: commit.as_object().short_id()?.as_str().map(String::from),
+                    parent_hashes: commit.parent_ids().map(|id| oid_string(&id)).collect(),
+                    tags,
+                    branches,
+                    stats: match query.stats {
+                        true => Some(diff_stats(&repo, &commit, query)?),
+                        false => None,
+                    },
+                    files: match query.files {
+                        true => Some(diff_files(&repo, &commit, query)?),
+                        false => None,
+                    },
+                    author_tz_offset_minutes: Some(commit.author().when().offset_minutes()),
+                    committer_tz_offset_minutes: Some(committed.offset_minutes()),
+                    signed_off_by: vec![],
+                    trailers: parse_trailers(&message),
+                    conventional: None,
+                    issue_refs: vec![],
+                    notes: match query.notes {
+                        true => repo
+                            .find_note(query.notes_ref.as_deref(), commit.id())
+                            .ok()
+                            .and_then(|note| note.message().map(|m| m.trim_end().to_string()))
+                            .filter(|note| !note.is_empty()),
+                        false => None,
+                    },
+                    // signatures aren't checked, but it is known when there is none
+                    signature: match commit.header_field_bytes("gpgsig") {
+                        Err(_) if query.signatures => Some(SignatureStatus::Unsigned),
+                        _ => None,
+                    },
+                    signed_by: None,
+                }
+                .with_message_fields(),
+            );
+        }
+
+        Ok(commits)
+    }
+
+    fn verify_ref(&self, dir: &Path, reference: &str) -> Result<()> {
+        let repo = Repository::open(dir)?;
+        let found = repo
+            .revparse_single(reference)
+            .and_then(|object| object.peel_to_commit())
+            .is_ok();
+        if !found {
+            bail!("Unknown git reference: {}", reference);
+        }
+        Ok(())
+    }
+
+    fn head_hash(&self, dir: &Path) -> Option<String> {
+        let repo = Repository::open(dir).ok()?;
+        let head = repo.head().ok()?.peel_to_commit().ok()?;
+        Some(oid_string(&head.id()))
+    }
+
+    fn head_short_hash(&self, dir: &Path) -> Option<String> {
+        let repo = Repository::open(dir).ok()?;
+        let head = repo.head().ok()?.peel_to_commit().ok()?;
+        let short = head.as_object().short_id().ok()?;
+        short.as_str().map(String::from)
+    }
+
+    fn default_branch(&self, dir: &Path) -> Option<String> {
+        let repo = Repository::open(dir).ok()?;
+        let head = repo.find_reference("refs/remotes/origin/HEAD").ok()?;
+        let target = head.symbolic_target()?;
+        target
+            .strip_prefix("refs/remotes/origin/")
+            .map(String::from)
+    }
+
+    fn remote_branch(&self, dir: &Path) -> Option<String> {
+        let repo = Repository::open(dir).ok()?;
+        let mut names: Vec<String> = repo
+            .branches(Some(BranchType::Remote))
+            .ok()?
+            .filter_map(|branch| branch.ok()?.0.name().ok().flatten().map(String::from))
+            .filter(|name| !name.ends_with("/HEAD"))
+            .collect();
+        names.sort();
+        names.into_iter().next()
+    }
+
+    fn head_name(&self, dir: &Path) -> Option<String> {
+        let repo = Repository::open(dir).ok()?;
+        let head = repo.head().ok()?;
+        if head.is_branch() {
+            return head.shorthand().map(String::from);
+        }
+
+        let mut tags = DescribeOptions::new();
+        tags.describe_tags();
+        let described = repo
+            .describe(&tags)
+            .and_then(|describe| describe.format(Some(&DescribeFormatOptions::new())));
+        match described {
+            Ok(name) => Some(name),
+            Err(_) => {
+                let short = head.peel_to_commit().ok()?.as_object().short_id().ok()?;
+                Some(format!("(detached: {})", short.as_str()?))
+            }
+        }
+    }
+}
+
+// Fills in status the way GitCli does from `git status` and `git diff --stat`
+fn read_status(dir: &Path, pathspecs: &[&str], status: &mut Status) -> Result<()> {
+    let repo = Repository::open(dir)?;
+
+    let mut options = StatusOptions::new();
+    options
+        .include_untracked(true)
+        .include_ignored(true)
+        .renames_head_to_index(true);
+    for pathspec in pathspecs {
+        options.pathspec(pathspec);
+    }
+
+    let staged = S::INDEX_NEW
+        | S::INDEX_MODIFIED
+        | S::INDEX_DELETED
+        | S::INDEX_RENAMED
+        | S::INDEX_TYPECHANGE
+        | S::CONFLICTED;
+    let unstaged =
+        S::WT_MODIFIED | S::WT_DELETED | S::WT_RENAMED | S::WT_TYPECHANGE | S::CONFLICTED;
+
+    for entry in repo.statuses(Some(&mut options))?.iter() {
+        let flags = entry.status();
+        // renamed entries are listed under their new path
+        let path = entry
+            .head_to_index()
+            .and_then(|delta| delta.new_file().path())
+            .map(|path| path.to_string_lossy().to_string())
+            .or_else(|| entry.path().map(String::from))
+            .unwrap_or_default();
+
+        if flags.contains(S::IGNORED) {
+            status.ignored.push(path);
+            continue;
+        }
+        if flags.contains(S::WT_NEW) && !flags.intersects(staged) {
+            status.entries.push(StatusEntry {
+                path: path.clone(),
+                index_status: '?',
+                worktree_status: '?',
+                renamed_from: None,
+            });
+            status.untracked.push(path);
+            continue;
+        }
+        if flags.intersects(staged) {
+            status.staged.push(path.clone());
+        }
+        if flags.intersects(unstaged) {
+            status.unstaged.push(path.clone());
+        }
+        let renamed_from = entry
+            .head_to_index()
+            .filter(|_| flags.contains(S::INDEX_RENAMED))
+            .and_then(|delta| delta.old_file().path())
+            .map(|path| path.to_string_lossy().to_string());
+        let (index_status, worktree_status) = status_letters(flags);
+        status.entries.push(StatusEntry {
+            path,
+            index_status,
+            worktree_status,
+            renamed_from,
+        });
+    }
+
+    status.entries.sort_by(|a, b| a.path.cmp(&b.path));
+    status.tally();
+
+    let branch = read_branch(&repo);
+    status.branch = Some(branch);
+
+    Ok(())
+}
+
+// The XY columns `git status --porcelain` prints for flags
+fn status_letters(flags: S) -> (char, char) {
+    if flags.contains(S::CONFLICTED) {
+        return ('U', 'U');
+    }
+    let index = [
+        (S::INDEX_NEW, 'A'),
+        (S::INDEX_MODIFIED, 'M'),
+        (S::INDEX_DELETED, 'D'),
+        (S::INDEX_RENAMED, 'R'),
+        (S::INDEX_TYPECHANGE, 'T'),
+    ];
+    let worktree = [
+        (S::WT_MODIFIED, 'M'),
+        (S::WT_DELETED, 'D'),
+        (S::WT_RENAMED, 'R'),
+        (S::WT_TYPECHANGE, 'T'),
+    ];
+    let letter = |letters: &[(S, char)]| {
+        letters
+            .iter()
+            .find(|(flag, _)| flags.contains(*flag))
+            .map_or(' ', |(_, letter)| *letter)
+    };
+    (letter(&index), letter(&worktree))
+}
+
+// The same details `git status --porcelain=v2 --branch` prints in its header
+fn read_branch(repo: &Repository) -> BranchStatus {
+    let mut branch = BranchStatus::default();
+
+    let head = match repo.head() {
+        Ok(head) => head,
+        // an unborn branch still has a name
+        Err(_) => {
+            branch.head = repo
+                .find_reference("HEAD")
+                .ok()
+                .and_then(|head| head.symbolic_target().map(String::from))
+                .and_then(|target| target.strip_prefix("refs/heads/").map(String::from));
+            return branch;
+        }
+    };
+    branch.oid = head.target().map(|oid| oid_string(&oid));
+    if !head.is_branch() {
+        return branch;
+    }
+    branch.head = head.shorthand().map(String::from);
+
+    let upstream = head
+        .shorthand()
+        .and_then(|name| repo.find_branch(name, BranchType::Local).ok())
+        .and_then(|local| local.upstream().ok());
+    if let Some(upstream) = upstream {
+        branch.upstream = upstream.name().ok().flatten().map(String::from);
+        if let (Some(local), Some(remote)) = (head.target(), upstream.get().target()) {
+            if let Ok((ahead, behind)) = repo.graph_ahead_behind(local, remote) {
+                branch.ahead = Some(ahead as u32);
+                branch.behind = Some(behind as u32);
+            }
+        }
+    }
+
+    branch
+}
+
+// Whether commit changes anything matching pathspecs. Merges must differ from every parent, as with `git log -- paths`
+fn touches(repo: &Repository, commit: &git2::Commit, pathspecs: &[String]) -> Result<bool> {
+    let mut options = DiffOptions::new();
+    for pathspec in pathspecs {
+        options.pathspec(pathspec);
+    }
+
+    let tree = commit.tree()?;
+    if commit.parent_count() == 0 {
+        let diff = repo.diff_tree_to_tree(None, Some(&tree), Some(&mut options))?;
+        return Ok(diff.deltas().len() > 0);
+    }
+    for parent in commit.parents() {
+        let diff =
+            repo.diff_tree_to_tree(Some(&parent.tree()?), Some(&tree), Some(&mut options))?;
+        if diff.deltas().len() == 0 {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+// The files and lines commit changed, counted like `git log --numstat --no-renames`
+fn diff_stats(repo: &Repository, commit: &git2::Commit, query: &LogQuery) -> Result<CommitStats> {
+    let diff = match commit_diff(repo, commit, query)? {
+        Some(diff) => diff,
+        None => return Ok(CommitStats::default()),
+    };
+    let stats = diff.stats()?;
+    Ok(CommitStats {
+        files_changed: stats.files_changed() as u32,
+        insertions: stats.insertions() as u64,
+        deletions: stats.deletions() as u64,
+    })
+}
+
+// The files commit changed, listed like `git log --name-status -M`
+fn diff_files(
+    repo: &Repository,
+    commit: &git2::Commit,
+    query: &LogQuery,
+) -> Result<Vec<FileChange>> {
+    let mut diff = match commit_diff(repo, commit, query)? {
+        Some(diff) => diff,
+        None => return Ok(vec![]),
+    };
+    diff.find_similar(None)?;
+
+    let path = |file: git2::DiffFile| file.path().map(|path| path.to_string_lossy().to_string());
+    let mut files = vec![];
+    for delta in diff.deltas() {
+        let change = match delta.status() {
+            Delta::Added => ChangeKind::Added,
+            Delta::Deleted => ChangeKind::Deleted,
+            Delta::Renamed => ChangeKind::Renamed,
+            Delta::Copied => ChangeKind::Copied,
+            _ => ChangeKind::Modified,
+        };
+        let (old_path, new_path) = (path(delta.old_file()), path(delta.new_file()));
+        files.push(FileChange {
+            path: match change {
+                ChangeKind::Deleted => old_path.clone(),
+                _ => new_path,
+            }
+            .unwrap_or_default(),
+            change,
+            old_path: match change {
+                ChangeKind::Renamed | ChangeKind::Copied => old_path,
+                _ => None,
+            },
+        });
+    }
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(files)
+}
+
+// The diff of commit with its parent, limited to the pathspecs of query. None for merges git log doesn't diff
+fn commit_diff<'r>(
+    repo: &'r Repository,
+    commit: &git2::Commit,
+    query: &LogQuery,
+) -> Result<Option<git2::Diff<'r>>> {
+    if !query.diffs_commit(commit.parent_count()) {
+        return Ok(None);
+    }
+    let parent = match commit.parent_count() {
+        0 => None,
+        _ => Some(commit.parent(0)?.tree()?),
+    };
+    let mut options = DiffOptions::new();
+    for pathspec in &query.pathspecs {
+        options.pathspec(pathspec);
+    }
+    let diff =
+        repo.diff_tree_to_tree(parent.as_ref(), Some(&commit.tree()?), Some(&mut options))?;
+    Ok(Some(diff))
+}
+
+fn oid_string(oid: &git2::Oid) -> String {
+    oid.to_string()
+}
@@ -0,0 +1,591 @@
+// Copyright 2022 Anthony Mugendi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::{
+    decorations, git_date, identity_matches, parse_body, parse_date, parse_trailers, BranchStatus,
+    ChangeKind, Commit, CommitStats, FileChange, GitBackend, LogQuery, SignatureStatus, Status,
+    StatusEntry,
+};
+use anyhow::{bail, Result};
+use gix::{
+    bstr::{BString, ByteSlice},
+    commit::describe::SelectRef,
+    diff::index::ChangeRef,
+    dir::{entry, walk::EmissionMode},
+    object::tree::diff::ChangeDetached,
+    prelude::TreeDiffChangeExt,
+    progress::Discard,
+    refs::TargetRef,
+    remote::Direction,
+    revision::walk::Sorting,
+    status::{
+        self,
+        index_worktree::{self, iter::Summary},
+    },
+    traverse::commit::simple::CommitTimeOrder,
+    ObjectId, Repository,
+};
+use std::{collections::BTreeMap, path::Path};
+
+/// A [GitBackend] that reads repos with gitoxide through the ```gix``` crate. It is pure Rust,
+/// so neither a ```git``` binary nor libgit2 is needed, e.g. for static musl builds or scratch containers.
+/// Enabled by the ```backend-gix``` feature.
+///
+/// It differs from [GitCli](super::GitCli) in a few ways: ```.mailmap``` is not applied, commit signatures are not verified,
+/// so [Commit::signed_by] stays None, as does [Commit::signature]
+/// unless the commit is unsigned, and the default branch is only read from
+/// ```refs/remotes/origin/HEAD```, the remote itself is never asked
+/// ## Example
+/// ```
+///  # use commit_info::{GixBackend, Info};
+///  # use std::env;
+///  # fn main() -> anyhow::Result<()> {
+///  # let mut path = env::current_dir().unwrap();
+///  # path.push("test_project");
+///  # let dir = path.to_string_lossy().to_string();
+///  // let dir = "/path/to/repo"; <- Point to the location of t=your repo
+///  let info = Info::new(&dir).with_backend(GixBackend).gather()?;
+///  println!("{:#?}", info);
+///  # Ok(())
+///  # }
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct GixBackend;
+
+impl GitBackend for GixBackend {
+    fn status(&self, dir: &Path, pathspecs: &[&str]) -> Result<Status> {
+        let mut status = Status::default();
+        read_status(dir, pathspecs, &mut status)?;
+        Ok(status)
+    }
+
+    fn log(&self, dir: &Path, query: &LogQuery) -> Result<Vec<Commit>> {
+        let repo = gix::open(dir)?;
+
+        let (tips, hidden) = match query.revision.split_once("..") {
+            Some((from, to)) => (
+                resolve(&repo, if to.is_empty() { "HEAD" } else { to })?,
+                Some(resolve(&repo, if from.is_empty() { "HEAD" } else { from })?),
+            ),
+            None => (resolve(&repo, &query.revision)?, None),
+        };
+        let mut walk = repo
+            .rev_walk([tips])
+            .with_hidden(hidden)
+            .sorting(Sorting::ByCommitTime(CommitTimeOrder::NewestFirst));
+        if query.first_parent {
+            walk = walk.first_parent_only();
+        }
+
+        let mut pathspec = match query.pathspecs.is_empty() {
+            true => None,
+            false => Some(repo.pathspec(
+                true,
+                query.pathspecs.iter().map(|p| p.as_bytes().as_bstr()),
+                false,
+                &gix::index::State::new(repo.object_hash()),
+                gix::worktree::stack::state::attributes::Source::IdMapping,
+            )?),
+        };
+
+        let decorations = decorations(repo.references()?.all()?.filter_map(|reference| {
+            let mut reference = reference.ok()?;
+            // peeling follows symbolic refs such as origin/HEAD in place, renaming the reference
+            let name = reference.name().as_bstr().to_string();
+            Some((name, reference.peel_to_commit().ok()?.id.to_string()))
+        }));
+
+        let author_regex = query.author_regex()?;
+        let committer_regex = query.committer_regex()?;
+        let grep_regex = query.grep_regex()?;
+        let mut commits = vec![];
+        let mut skip = query.skip;
+        for info in walk.all()? {
+            if query.max_count.is_some_and(|max| commits.len() >= max) {
+                break;
+            }
+            let info = info?;
+            if !query.include_merges && info.parent_ids.len() > 1 {
+                continue;
+            }
+            let commit = info.object()?;
+            if !query.in_date_range(commit.committer()?.time()?.seconds) {
+                continue;
+            }
+            let people = [
+                (author_regex.as_ref(), commit.author()?),
+                (committer_regex.as_ref(), commit.committer()?),
+            ];
+            if !people.iter().all(|(regex, person)| {
+                identity_matches(
+                    *regex,
+                    &person.name.to_str_lossy(),
+                    &person.email.to_str_lossy(),
+                )
+            }) {
+                continue;
+            }
+            if let Some(regex) = &grep_regex {
+                if !regex.is_match(&commit.message_raw_sloppy().to_str_lossy()) {
+                    continue;
+                }
+            }
+            if let Some(pathspec) = pathspec.as_mut() {
+                if !touches(&repo, &commit, pathspec)? {
+                    continue;
+                }
+            }
+            if skip > 0 {
+                skip -= 1;
+                continue;
+            }
+
+            let author = commit.author()?;
+            let committer = commit.committer()?;
+            let (authored, committed) = (author.time()?, committer.time()?);
+            let message = commit.message_raw_sloppy().to_str_lossy();
+            let (tags, branches) = decorations
+                .get(&commit.id.to_string())
+                .cloned()
+                .unwrap_or_default();
+
+            commits.push(
+                Commit {
+                    commit_date: parse_date(&git_date(committed.seconds, committed.offset / 60)),
+                    author_date: parse_date(&git_date(authored.seconds, authored.offset / 60)),
+                    commit_message: Some(commit.message()?.summary().to_string()),
+                    commit_subject: Some(commit.message()?.summary().to_string()),
+                    commit_body: parse_body(&message),
+                    author_name: Some(author.name.to_string()),
+                    author_email: Some(author.email.to_string()),
+                    committer_name: Some(committer.name.to_string()),
+                    committer_email: Some(committer.email.to_string()),
+                    tree_hash: Some(commit.tree_id()?.shorten_or_id().to_string()),
+                    hash: Some(commit.id.to_string()),
+                    short_hash: Some(commit.id().shorten_or_id().to_string()),
+                    parent_hashes: commit.parent_ids().map(|id| id.to_string()).collect(),
+                    tags,
+                    branches,
+                    stats: match query.stats {
+                        true => Some(diff_stats(&repo, &commit, query, pathspec.as_mut())?),
+                        false => None,
+                    },
+                    files: match query.files {
+                        true => Some(diff_files(&repo, &commit, query, pathspec.as_mut())?),
+                        false => None,
+                    },
+                    author_tz_offset_minutes: Some(authored.offset / 60),
+                    committer_tz_offset_minutes: Some(committed.offset / 60),
+                    signed_off_by: vec![],
+                    trailers: parse_trailers(&message),
+                    conventional: None,
+                    issue_refs: vec![],
+                    notes: match query.notes {
+                        true => read_note(&repo, query.notes_ref.as_deref(), commit.id),
+                        false => None,
+                    },
+                    // signatures aren't checked, but it is known when there is none
+                    signature: match commit.decode()?.extra_headers().pgp_signature() {
+                        None if query.signatures => Some(SignatureStatus::Unsigned),
+                        _ => None,
+                    },
+                    signed_by: None,
+                }
+                .with_message_fields(),
+            );
+        }
+
+        Ok(commits)
+    }
+
+    fn verify_ref(&self, dir: &Path, reference: &str) -> Result<()> {
+        let repo = gix::open(dir)?;
+        if resolve(&repo, reference).is_err() {
+            bail!("Unknown git reference: {}", reference);
+        }
+        Ok(())
+    }
+
+    fn head_hash(&self, dir: &Path) -> Option<String> {
+        let repo = gix::open(dir).ok()?;
+        let head = repo.head_commit().ok()?;
+        Some(head.id.to_string())
+    }
+
+    fn head_short_hash(&self, dir: &Path) -> Option<String> {
+        let repo = gix::open(dir).ok()?;
+        let head = repo.head_commit().ok()?;
+        Some(head.short_id().ok()?.to_string())
+    }
+
+    fn default_branch(&self, dir: &Path) -> Option<String> {
+        let repo = gix::open(dir).ok()?;
+        let head = repo.find_reference("refs/remotes/origin/HEAD").ok()?;
+        match head.target() {
+            TargetRef::Symbolic(target) => target
+                .as_bstr()
+                .to_str()
+                .ok()?
+                .strip_prefix("refs/remotes/origin/")
+                .map(String::from),
+            TargetRef::Object(_) => None,
+        }
+    }
+
+    fn remote_branch(&self, dir: &Path) -> Option<String> {
+        let repo = gix::open(dir).ok()?;
+        let references = repo.references().ok()?;
+        let mut names: Vec<String> = references
+            .remote_branches()
+            .ok()?
+            .filter_map(|reference| Some(reference.ok()?.name().shorten().to_string()))
+            .filter(|name| !name.ends_with("/HEAD"))
+            .collect();
+        names.sort();
+        names.into_iter().next()
+    }
+
+    fn head_name(&self, dir: &Path) -> Option<String> {
+        let repo = gix::open(dir).ok()?;
+        let head = repo.head().ok()?;
+        if head.is_unborn() {
+            return None;
+        }
+        if let Some(name) = head.referent_name() {
+            return Some(name.shorten().to_string());
+        }
+
+        let commit = repo.head_commit().ok()?;
+        let described = commit
+            .describe()
+            .names(SelectRef::AllTags)
+            .try_format()
+            .ok()
+            .flatten();
+        match described {
+            Some(name) => Some(name.to_string()),
+            None => Some(format!("(detached: {})", commit.short_id().ok()?)),
+        }
+    }
+}
+
+// Fills in status the way GitCli does from `git status` and `git diff --stat`
+fn read_status(dir: &Path, pathspecs: &[&str], status: &mut Status) -> Result<()> {
+    let repo = gix::open(dir)?;
+
+    let items = repo
+        .status(Discard)?
+        .untracked_files(status::UntrackedFiles::Collapsed)
+        .dirwalk_options(|options| options.emit_ignored(Some(EmissionMode::CollapseDirectory)))
+        .into_iter(pathspecs.iter().map(|p| BString::from(*p)))?;
+
+    // the index and the working tree are compared separately, so both halves of an entry are merged by path
+    let mut entries = BTreeMap::new();
+
+    for item in items {
+        match item? {
+            status::Item::TreeIndex(change) => {
+                let path = change.location().to_string();
+                let (letter, renamed_from) = match &change {
+                    ChangeRef::Addition { .. } => ('A', None),
+                    ChangeRef::Deletion { .. } => ('D', None),
+                    ChangeRef::Modification { .. } => ('M', None),
+                    ChangeRef::Rewrite {
+                        source_location,
+                        copy,
+                        ..
+                    } => (
+                        if *copy { 'C' } else { 'R' },
+                        Some(source_location.to_string()),
+                    ),
+                };
+                let entry = status_entry(&mut entries, &path);
+                entry.index_status = letter;
+                entry.renamed_from = renamed_from;
+                status.staged.push(path);
+            }
+            status::Item::IndexWorktree(index_worktree::Item::DirectoryContents {
+                entry, ..
+            }) => {
+                // collapsed directories are listed with a trailing slash, as git does
+                let mut path = entry.rela_path.to_string();
+                if entry.disk_kind == Some(entry::Kind::Directory) {
+                    path.push('/');
+                }
+                match entry.status {
+                    entry::Status::Untracked => {
+                        let entry = status_entry(&mut entries, &path);
+                        entry.index_status = '?';
+                        entry.worktree_status = '?';
+                        status.untracked.push(path);
+                    }
+                    entry::Status::Ignored(_) => status.ignored.push(path),
+                    _ => {}
+                }
+            }
+            status::Item::IndexWorktree(item) => {
+                let letter = match item.summary() {
+                    Some(Summary::Removed) => 'D',
+                    Some(Summary::Added) => '?',
+                    Some(Summary::Modified) => 'M',
+                    Some(Summary::TypeChange) => 'T',
+                    Some(Summary::Renamed) => 'R',
+                    Some(Summary::Copied) => 'C',
+                    Some(Summary::IntentToAdd) => 'A',
+                    Some(Summary::Conflict) => 'U',
+                    None => continue,
+                };
+                let path = item.rela_path().to_string();
+                let entry = status_entry(&mut entries, &path);
+                entry.worktree_status = letter;
+                if letter == 'U' {
+                    entry.index_status = 'U';
+                }
+                status.unstaged.push(path);
+            }
+        }
+    }
+    status.entries = entries.into_values().collect();
+
+    // the status is gathered in parallel, so the order isn't stable
+    for paths in [
+        &mut status.staged,
+        &mut status.unstaged,
+        &mut status.untracked,
+        &mut status.ignored,
+    ] {
+        paths.sort();
+        paths.dedup();
+    }
+    status.tally();
+
+    let branch = read_branch(&repo);
+    status.branch = Some(branch);
+
+    Ok(())
+}
+
+// The entry of path in entries, added unchanged on both sides if it isn't there yet
+fn status_entry<'a>(
+    entries: &'a mut BTreeMap<String, StatusEntry>,
+    path: &str,
+) -> &'a mut StatusEntry {
+    entries.entry(path.into()).or_insert_with(|| StatusEntry {
+        path: path.into(),
+        index_status: ' ',
+        worktree_status: ' ',
+        renamed_from: None,
+    })
+}
+
+// The same details `git status --porcelain=v2 --branch` prints in its header
+fn read_branch(repo: &Repository) -> BranchStatus {
+    let mut branch = BranchStatus::default();
+
+    let head = match repo.head() {
+        Ok(head) => head,
+        Err(_) => return branch,
+    };
+    let name = head.referent_name().map(|name| name.to_owned());
+    branch.head = name.as_ref().map(|name| name.shorten().to_string());
+    branch.oid = head.id().map(|id| id.to_string());
+
+    let (Some(name), Some(local)) = (name, head.id()) else {
+        return branch;
+    };
+    let upstream = match repo.branch_remote_tracking_ref_name(name.as_ref(), Direction::Fetch) {
+        Some(Ok(upstream)) => upstream.into_owned(),
+        _ => return branch,
+    };
+    branch.upstream = Some(upstream.shorten().to_string());
+
+    let remote = repo
+        .find_reference(&upstream)
+        .ok()
+        .and_then(|mut reference| reference.peel_to_id().ok())
+        .map(|id| id.detach());
+    if let Some(remote) = remote {
+        branch.ahead = count_unique(repo, local.detach(), remote);
+        branch.behind = count_unique(repo, remote, local.detach());
+    }
+
+    branch
+}
+
+// Counts the commits reachable from `tip` but not from `other`
+fn count_unique(repo: &Repository, tip: ObjectId, other: ObjectId) -> Option<u32> {
+    let walk = repo.rev_walk([tip]).with_hidden([other]).all().ok()?;
+    Some(walk.filter(|info| info.is_ok()).count() as u32)
+}
+
+// Resolves a revision like `HEAD~2` or `v1.0` to the commit it names
+fn resolve(repo: &Repository, revision: &str) -> Result<ObjectId> {
+    let object = repo.rev_parse_single(revision)?.object()?;
+    Ok(object.peel_to_commit()?.id)
+}
+
+// The note git would show for the commit, read from notes_ref or else the default notes ref
+fn read_note(repo: &Repository, notes_ref: Option<&str>, id: ObjectId) -> Option<String> {
+    let notes_ref = notes_ref.unwrap_or("refs/notes/commits");
+    let hex = id.to_string();
+    // large notes trees are fanned out into directories named after the first bytes of the hash
+    let note = [hex.clone(), format!("{}/{}", &hex[..2], &hex[2..])]
+        .iter()
+        .find_map(|path| {
+            repo.rev_parse_single(format!("{}:{}", notes_ref, path).as_str())
+                .ok()
+        })?;
+    let blob = note.object().ok()?;
+    let text = blob.data.to_str_lossy().trim_end().to_string();
+    if text.is_empty() {
+        None
+    } else {
+        Some(text)
+    }
+}
+
+// Whether commit changes anything matching pathspec. Merges must differ from every parent, as with `git log -- paths`
+fn touches(repo: &Repository, commit: &gix::Commit, pathspec: &mut gix::Pathspec) -> Result<bool> {
+    let tree = commit.tree()?;
+    let parents: Vec<ObjectId> = commit.parent_ids().map(|id| id.detach()).collect();
+    if parents.is_empty() {
+        return changes_match(repo, None, &tree, pathspec);
+    }
+    for parent in parents {
+        let parent = repo.find_commit(parent)?.tree()?;
+        if !changes_match(repo, Some(&parent), &tree, pathspec)? {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+// The files and lines commit changed, counted like `git log --numstat --no-renames`
+fn diff_stats(
+    repo: &Repository,
+    commit: &gix::Commit,
+    query: &LogQuery,
+    mut pathspec: Option<&mut gix::Pathspec>,
+) -> Result<CommitStats> {
+    let options = gix::diff::Options::default().with_rewrites(None);
+    let changes = match commit_changes(repo, commit, query, Some(options))? {
+        Some(changes) => changes,
+        None => return Ok(CommitStats::default()),
+    };
+    let mut cache = repo.diff_resource_cache_for_tree_diff()?;
+    let mut stats = CommitStats::default();
+    for change in &changes {
+        if !change.entry_mode().is_blob_or_symlink() {
+            continue;
+        }
+        if let Some(pathspec) = pathspec.as_deref_mut() {
+            if !pathspec.is_included(change.location(), Some(false)) {
+                continue;
+            }
+        }
+        stats.files_changed += 1;
+        // binary files have no lines to count
+        if let Some(counts) = change.attach(repo, repo).diff(&mut cache)?.line_counts()? {
+            stats.insertions += u64::from(counts.insertions);
+            stats.deletions += u64::from(counts.removals);
+        }
+        cache.clear_resource_cache_keep_allocation();
+    }
+    Ok(stats)
+}
+
+// The files commit changed, listed like `git log --name-status -M`
+fn diff_files(
+    repo: &Repository,
+    commit: &gix::Commit,
+    query: &LogQuery,
+    mut pathspec: Option<&mut gix::Pathspec>,
+) -> Result<Vec<FileChange>> {
+    // without options renames are tracked as configured, which git does by default
+    let changes = match commit_changes(repo, commit, query, None)? {
+        Some(changes) => changes,
+        None => return Ok(vec![]),
+    };
+
+    let mut files = vec![];
+    for entry in &changes {
+        if !entry.entry_mode().is_blob_or_symlink() {
+            continue;
+        }
+        if let Some(pathspec) = pathspec.as_deref_mut() {
+            if !pathspec.is_included(entry.location(), Some(false)) {
+                continue;
+            }
+        }
+        let (change, old_path) = match entry {
+            ChangeDetached::Addition { .. } => (ChangeKind::Added, None),
+            ChangeDetached::Deletion { .. } => (ChangeKind::Deleted, None),
+            ChangeDetached::Modification { .. } => (ChangeKind::Modified, None),
+            ChangeDetached::Rewrite {
+                source_location,
+                copy,
+                ..
+            } => (
+                if *copy {
+                    ChangeKind::Copied
+                } else {
+                    ChangeKind::Renamed
+                },
+                Some(source_location.to_string()),
+            ),
+        };
+        files.push(FileChange {
+            path: entry.location().to_string(),
+            change,
+            old_path,
+        });
+    }
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(files)
+}
+
+// The changes between commit and its parent. None for merges git log doesn't diff
+fn commit_changes(
+    repo: &Repository,
+    commit: &gix::Commit,
+    query: &LogQuery,
+    options: Option<gix::diff::Options>,
+) -> Result<Option<Vec<ChangeDetached>>> {
+    let parents: Vec<ObjectId> = commit.parent_ids().map(|id| id.detach()).collect();
+    if !query.diffs_commit(parents.len()) {
+        return Ok(None);
+    }
+    let parent = match parents.first() {
+        Some(parent) => Some(repo.find_commit(*parent)?.tree()?),
+        None => None,
+    };
+    Ok(Some(repo.diff_tree_to_tree(
+        parent.as_ref(),
+        Some(&commit.tree()?),
+        options,
+    )?))
+}
+
+fn changes_match(
+    repo: &Repository,
+    old: Option<&gix::Tree>,
+    new: &gix::Tree,
+    pathspec: &mut gix::Pathspec,
+) -> Result<bool> {
+    let options = gix::diff::Options::default().with_rewrites(None);
+    let changes = repo.diff_tree_to_tree(old, Some(new), Some(options))?;
+    Ok(changes
+        .iter()
+        .any(|change| pathspec.is_included(change.location(), Some(false))))
+}
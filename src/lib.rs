@@ -3,16 +3,27 @@
 
 //! This crate gathers relevant git info from any Repo. Some of the info returned includes:
 //! - **Git status info**: Checks if a repo is dirty, has been modified and so on.
-//! - **Commits**: Gathers and shows information for the last 10 commits
-//! 
+//! - **Commits**: Gathers and shows information for the latest commits, 5 by default
+//!
+//! Every method of [Info] that reads a repo, from [Info::commit_info] to queries such as [Info::path_status] or
+//! [Info::is_shallow], fails with [CommitInfoError::NotARepository] when its directory isn't a git repo.
+//! A repo without commits is not an error: queries about its history return nothing, and those given a
+//! reference fail because it doesn't resolve
+//!
 //! ## Example
 //! ```rust
+//!  # use commit_info::Info;
+//!  # use std::env;
+//!  # fn main() -> anyhow::Result<()> {
 //!  # let mut path = env::current_dir().unwrap();
 //!  # path.push("test_project");
 //!  # let dir = path.to_string_lossy().to_string();
 //!  // let dir = "/path/to/repo"; <- Point to the location of t=your repo
 //!  let info = Info::new(&dir).status_info()?.commit_info()?;
-//!  println("{:#?}", info);
+//!  println!("{:#?}", info);
+//!  # Ok(())
+//!  # }
+//! ```
 
 // Copyright 2022 Anthony Mugendi
 //
@@ -28,33 +39,330 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use anyhow::Result;
-use chrono::{DateTime, Utc};
-use cmd_lib::run_fun;
+use anyhow::{anyhow, bail, Result};
+#[cfg(feature = "dates")]
+use chrono::{DateTime, FixedOffset, TimeZone, Utc};
+use regex::Regex;
 use serde::{Deserialize, Serialize};
-use serde_json::{from_str, json};
-use std::{collections::HashMap, path::PathBuf};
+use serde_json::Value;
+use std::{
+    collections::{BTreeMap, HashMap},
+    io::{BufRead, BufReader, Read},
+    path::{Path, PathBuf},
+    process::{Child, ChildStdout, Command, Stdio},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex, OnceLock,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+#[cfg(feature = "backend-git2")]
+mod git2_backend;
+#[cfg(feature = "backend-git2")]
+pub use git2_backend::Git2Backend;
+#[cfg(feature = "backend-gix")]
+mod gix_backend;
+#[cfg(feature = "backend-gix")]
+pub use gix_backend::GixBackend;
+#[cfg(feature = "tokio")]
+mod tokio_api;
+
+/// The type used for commit dates.
+/// With the default `dates` feature this is a parsed timestamp that keeps the UTC offset it was recorded with,
+/// without it the date is kept exactly as git reports it, e.g. ```2014-08-29 16:09:40 -0600```
+#[cfg(feature = "dates")]
+pub type CommitDate = DateTime<FixedOffset>;
+/// The type used for commit dates.
+/// With the default `dates` feature this is a parsed timestamp that keeps the UTC offset it was recorded with,
+/// without it the date is kept exactly as git reports it, e.g. ```2014-08-29 16:09:40 -0600```
+#[cfg(not(feature = "dates"))]
+pub type CommitDate = String;
+
+/// The timezone commit dates are rendered in by [Info::to_json], see [Info::with_display_timezone]
+#[cfg(feature = "dates")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DisplayTimezone {
+    /// A fixed offset from UTC
+    Fixed(FixedOffset),
+    /// A named IANA timezone, e.g. ```America/Denver```, which follows daylight saving time
+    #[cfg(feature = "tz")]
+    Named(chrono_tz::Tz),
+}
+
+#[cfg(feature = "dates")]
+impl From<FixedOffset> for DisplayTimezone {
+    fn from(offset: FixedOffset) -> Self {
+        DisplayTimezone::Fixed(offset)
+    }
+}
+
+#[cfg(feature = "tz")]
+impl From<chrono_tz::Tz> for DisplayTimezone {
+    fn from(tz: chrono_tz::Tz) -> Self {
+        DisplayTimezone::Named(tz)
+    }
+}
+
+/// Errors specific to this crate. [Info::commit_info] and [Info::status_info] return them directly,
+/// other methods return them wrapped in an [anyhow::Error], use ```downcast_ref::<CommitInfoError>()``` to tell them apart
+/// ## Example
+/// ```
+///  # use commit_info::{CommitInfoError, Info};
+///  # use std::env;
+///  # let dir = env::temp_dir().to_string_lossy().to_string();
+///  match Info::new(&dir).status_info() {
+///      Ok(info) => println!("{:#?}", info.status),
+///      Err(CommitInfoError::NotARepository(dir)) => println!("run git init in {}", dir.display()),
+///      Err(e) => println!("{}", e),
+///  }
+/// ```
+#[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
+pub enum CommitInfoError {
+    /// The ```git``` binary could not be run, most likely because it isn't installed or isn't on PATH
+    #[error("git was not found, is it installed and on PATH?")]
+    GitBinaryMissing,
+    /// The directory is not a git repository
+    #[error("{} is not a git repository", .0.display())]
+    NotARepository(PathBuf),
+    /// The directory passed to [Info::try_new] or [Info::discover] does not exist
+    #[error("directory {} does not exist", .0.display())]
+    DirectoryNotFound(PathBuf),
+    /// The path passed to [Info::try_new] exists but is not a directory
+    #[error("{} is not a directory", .0.display())]
+    NotADirectory(PathBuf),
+    /// A git command exited with an error
+    #[error("{command} failed: {stderr}")]
+    CommandFailed {
+        /// The command that was run, e.g. ```git log --no-merges```
+        command: String,
+        /// What the command printed to stderr
+        stderr: String,
+    },
+    /// The output of git could not be understood
+    #[error("could not parse git output: {0}")]
+    ParseError(String),
+    /// The repository, or the requested ref, has no commits yet
+    #[error("no commits yet")]
+    NoCommits,
+    /// Git did not finish within the time set with [Info::with_timeout], so it was stopped
+    #[error("git did not finish within {0:?}")]
+    Timeout(Duration),
+    /// The [CancellationToken] set with [Info::with_cancellation] was cancelled, so git was stopped
+    #[error("cancelled")]
+    Cancelled,
+    /// Any other failure, e.g. of a custom [GitBackend]
+    #[error(transparent)]
+    Other(anyhow::Error),
+}
+
+impl From<anyhow::Error> for CommitInfoError {
+    fn from(error: anyhow::Error) -> Self {
+        // unwrap errors of this crate that were passed along as an anyhow::Error
+        match error.downcast::<CommitInfoError>() {
+            Ok(error) => error,
+            Err(error) => CommitInfoError::Other(error),
+        }
+    }
+}
+
+/// Stops the git commands an [Info] runs once cancelled, see [Info::with_cancellation].
+/// Clones share the same state, so one clone can be kept to cancel the work done with another
+/// ## Example
+/// ```
+///  # use commit_info::CancellationToken;
+///  let token = CancellationToken::new();
+///  let handle = token.clone();
+///  handle.cancel();
+///  assert!(token.is_cancelled());
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    /// A token that hasn't been cancelled yet
+    pub fn new() -> CancellationToken {
+        CancellationToken::default()
+    }
+
+    /// Cancels the work of every [Info] holding this token or one of its clones
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+
+    /// Whether [CancellationToken::cancel] has been called
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+}
 
 /// The Status Struct:
 /// Holds information about the status of the repo
-#[derive(Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
 pub struct Status {
-    /// Holds any error thrown by ```git status```
+    /// Holds the error of ```git diff --stat``` when it failed although the status could be read,
+    /// in which case ```git_dirty``` is left unknown
     pub error: Option<String>,
-    /// Indicates if repo is dirty or not. For this, we check both ```git status -s``` and ```git diff -stat```
+    /// Indicates if repo is dirty or not. For this, we check both ```git status --porcelain``` and ```git diff --stat```
     pub git_dirty: Option<bool>,
+    /// Whether any change is staged in the index, i.e. the X column of ```git status --porcelain``` is set. None if git status failed
+    pub has_staged: Option<bool>,
+    /// Whether any tracked file has changes that are not staged, i.e. the Y column of ```git status --porcelain``` is set.
+    /// Untracked files are not counted. None if git status failed
+    pub has_unstaged: Option<bool>,
     /// A HashMap describing the state of the repo
     pub summary: HashMap<String, bool>,
+    /// Paths with changes staged in the index
+    pub staged: Vec<String>,
+    /// Paths with changes in the working tree that are not staged
+    pub unstaged: Vec<String>,
+    /// Paths that are not tracked by git
+    pub untracked: Vec<String>,
+    /// Paths that exist in the working tree but are ignored by git. These never make the repo dirty
+    pub ignored: Vec<String>,
+    /// The branch header reported by ```git status --porcelain=v2 --branch```. None if git status failed
+    pub branch: Option<BranchStatus>,
+    /// Every changed and untracked path with what changed about it, sorted by path. Ignored paths are not listed
+    #[serde(default)]
+    pub entries: Vec<StatusEntry>,
+}
+
+/// A path listed by ```git status --porcelain```, with the state of the index and of the working tree
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct StatusEntry {
+    /// The path, relative to the repo root. Renamed and copied entries are listed under their new path
+    pub path: String,
+    /// The X column of ```git status --porcelain```: what is staged, e.g. ```'M'```, ```'A'```, ```'D'``` or ```'R'```,
+    /// and ```' '``` if nothing is. Both columns are ```'?'``` for untracked paths and ```'U'``` marks conflicts
+    pub index_status: char,
+    /// The Y column of ```git status --porcelain```: what changed in the working tree since the path was staged
+    pub worktree_status: char,
+    /// The path a renamed or copied entry came from
+    pub renamed_from: Option<String>,
+}
+
+impl StatusEntry {
+    // Unmerged paths, which `git status --porcelain` shows as DD, AU, UD, UA, DU, AA or UU
+    fn is_conflicted(&self) -> bool {
+        let xy = (self.index_status, self.worktree_status);
+        xy.0 == 'U' || xy.1 == 'U' || xy == ('A', 'A') || xy == ('D', 'D')
+    }
+}
+
+/// How many paths of a [Status] are in each state, e.g. for a prompt showing "3 staged, 1 untracked"
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StatusCounts {
+    /// Paths with changes staged in the index. Conflicts are only counted as conflicted
+    pub staged: usize,
+    /// Paths with changes in the working tree that are not staged. Conflicts are only counted as conflicted
+    pub unstaged: usize,
+    /// Paths that are not tracked by git
+    pub untracked: usize,
+    /// Paths with merge conflicts that are not resolved yet
+    pub conflicted: usize,
+    /// Paths renamed in the index or in the working tree
+    pub renamed: usize,
+    /// Paths deleted from the index or from the working tree
+    pub deleted: usize,
+}
+
+/// A linked worktree, created with ```git worktree add```, and the repo it belongs to
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct WorktreeInfo {
+    /// The worktree's name, which ```git worktree``` commands accept
+    pub name: String,
+    /// The worktree's own git directory, e.g. ```/path/to/repo/.git/worktrees/feature```
+    #[serde(serialize_with = "lossy_path")]
+    pub git_dir: PathBuf,
+    /// The repo the worktree belongs to: its main working tree, or its git directory if the repo is bare
+    #[serde(serialize_with = "lossy_path")]
+    pub main_dir: PathBuf,
+}
+
+impl WorktreeInfo {
+    // Reads the `.git` file of a linked worktree. None for normal repos and for submodules, whose
+    // `.git` file points at a git directory of their own
+    fn read(dir: &Path) -> Option<WorktreeInfo> {
+        let link = std::fs::read_to_string(dir.join(".git")).ok()?;
+        let git_dir = dir.join(link.trim().strip_prefix("gitdir:")?.trim());
+        let common_dir = std::fs::read_to_string(git_dir.join("commondir")).ok()?;
+        let common_dir = git_dir.join(common_dir.trim());
+        let common_dir = common_dir.canonicalize().unwrap_or(common_dir);
+
+        let main_dir = match common_dir.file_name() {
+            Some(name) if name == ".git" => common_dir.parent()?.into(),
+            _ => common_dir,
+        };
+        Some(WorktreeInfo {
+            name: git_dir.file_name()?.to_string_lossy().into(),
+            git_dir,
+            main_dir,
+        })
+    }
+}
+
+/// Where HEAD stands relative to its upstream, as reported by ```git status --porcelain=v2 --branch```
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct BranchStatus {
+    /// The full hash of the HEAD commit. None for a branch without commits yet
+    pub oid: Option<String>,
+    /// The current branch. None when HEAD is detached
+    pub head: Option<String>,
+    /// The upstream the current branch tracks, e.g. ```origin/main```
+    pub upstream: Option<String>,
+    /// How many commits HEAD has that the upstream doesn't. None without an upstream, or if it is gone
+    pub ahead: Option<u32>,
+    /// How many commits the upstream has that HEAD doesn't. None without an upstream, or if it is gone
+    pub behind: Option<u32>,
+}
+
+impl BranchStatus {
+    // Reads a `# branch.<key> <value>` header line
+    fn read_header(&mut self, line: &str) {
+        let (key, value) = match line
+            .strip_prefix("# branch.")
+            .and_then(|header| header.split_once(' '))
+        {
+            Some(header) => header,
+            None => return,
+        };
+        match key {
+            "oid" if value != "(initial)" => self.oid = Some(value.into()),
+            "head" if value != "(detached)" => self.head = Some(value.into()),
+            "upstream" => self.upstream = Some(value.into()),
+            "ab" => {
+                let (ahead, behind) = value.split_once(' ').unwrap_or_default();
+                self.ahead = ahead.trim_start_matches('+').parse().ok();
+                self.behind = behind.trim_start_matches('-').parse().ok();
+            }
+            _ => {}
+        }
+    }
 }
 
 /// Struct holding info of each commit
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
 pub struct Commit {
-    /// The repo commit date
-    #[serde(with = "my_date_format")]
-    pub commit_date: Option<DateTime<Utc>>,
-    /// The repo commit message
+    /// The repo commit date, in the committer's timezone, as shown by ```%ci```
+    #[cfg_attr(feature = "dates", serde(with = "my_date_format"))]
+    #[serde(default)]
+    pub commit_date: Option<CommitDate>,
+    /// The date the commit was authored, in the author's timezone, as shown by ```%ai```.
+    /// It differs from the commit date when a commit is amended, rebased or applied from a patch
+    #[cfg_attr(feature = "dates", serde(with = "my_date_format"))]
+    #[serde(default)]
+    pub author_date: Option<CommitDate>,
+    /// The repo commit message. Only its subject is kept, the same as [Commit::commit_subject]
     pub commit_message: Option<String>,
+    /// The subject of the commit message, as shown by ```%s```
+    #[serde(default)]
+    pub commit_subject: Option<String>,
+    /// The rest of the commit message after the subject and the blank line following it, as shown by ```%b```,
+    /// trailers included. None when the message is only a subject
+    #[serde(default)]
+    pub commit_body: Option<String>,
     /// The repo author name
     pub author_name: Option<String>,
     /// The repo author email
@@ -65,287 +373,8559 @@ pub struct Commit {
     pub committer_email: Option<String>,
     /// tree hash
     pub tree_hash: Option<String>,
+    /// The full commit hash
+    pub hash: Option<String>,
+    /// The abbreviated commit hash, as shown by ```%h```, e.g. ```3f2a9c1```
+    #[serde(default)]
+    pub short_hash: Option<String>,
+    /// The full hashes of the commit's parents, as shown by ```%P```. Empty for root commits, two or more for merges
+    #[serde(default)]
+    pub parent_hashes: Vec<String>,
+    /// The tags pointing at the commit, e.g. ```v1.2.0```, sorted by name
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// The local and remote branches pointing at the commit, e.g. ```main``` or ```origin/main```, sorted by name
+    #[serde(default)]
+    pub branches: Vec<String>,
+    /// How many files and lines the commit changed. Only read when asked for with [Info::with_stats]
+    #[serde(default)]
+    pub stats: Option<CommitStats>,
+    /// The files the commit added, modified, deleted or renamed, sorted by path. Only read when asked for with [Info::with_files]
+    #[serde(default)]
+    pub files: Option<Vec<FileChange>>,
+    /// The UTC offset of the author date in minutes, e.g. ```330``` for ```+0530```
+    #[serde(default)]
+    pub author_tz_offset_minutes: Option<i32>,
+    /// The UTC offset of the committer date in minutes, e.g. ```-360``` for ```-0600```
+    #[serde(default)]
+    pub committer_tz_offset_minutes: Option<i32>,
+    /// The name and email of every ```Signed-off-by:``` trailer in the message, the same as [Commit::signed_off_by()]
+    #[serde(default)]
+    pub signed_off_by: Vec<(String, String)>,
+    /// The key and value of every trailer in the last paragraph of the message, e.g. ```Co-authored-by: Ann <ann@example.com>```,
+    /// in order of appearance. Values folded over several lines are joined with a space
+    #[serde(default)]
+    pub trailers: Vec<(String, String)>,
+    /// The subject parsed as a Conventional Commit, see [Commit::conventional]. None when it doesn't follow the format
+    #[serde(default)]
+    pub conventional: Option<ConventionalCommit>,
+    /// Each reference of [Commit::references] once, e.g. ```#12```, ```GH-34``` or ```JIRA-56```, in order of appearance
+    #[serde(default)]
+    pub issue_refs: Vec<String>,
+    /// The note attached to the commit with ```git notes```, from the default notes ref or the one set with [Info::with_notes_ref].
+    /// Only read with [Info::with_notes]
+    #[serde(default)]
+    pub notes: Option<String>,
+    /// Whether the commit is signed and whether its signature could be verified, as reported by ```%G?```.
+    /// Only read when asked for with [Info::with_signatures]. The library backends don't check signatures,
+    /// so they only report unsigned commits and leave signed ones None
+    #[serde(default)]
+    pub signature: Option<SignatureStatus>,
+    /// The signer and key of a signed commit, for audits. Only read when asked for with [Info::with_signatures].
+    /// None for unsigned commits and with the library backends
+    #[serde(default)]
+    pub signed_by: Option<Signer>,
 }
 
-/// The main struct that returns combined Status and Commits info
-#[derive(Debug, Clone)]
-pub struct Info {
-    /// Repo directory
-    pub dir: String,
-    /// Boolean indicating id the directory above is indeed a repo
-    pub is_git: bool,
-    /// Repo branch inspected
-    pub branch: Option<String>,
-    /// Status information for the repo
-    pub status: Option<Status>,
-    /// Information on the repo commits
-    pub commits: Option<Vec<Commit>>,
+/// Who signed a commit and with which key
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct Signer {
+    /// The signer as reported by ```%GS```: the key's user id for gpg or the allowed principal for ssh.
+    /// None when the key isn't known, e.g. an ssh key missing from the allowed signers
+    pub name: Option<String>,
+    /// The id of the signing key, as reported by ```%GK```
+    pub key_id: Option<String>,
+    /// The fingerprint of the signing key, as reported by ```%GF```. For ssh keys it is the same as the key id
+    pub fingerprint: Option<String>,
+    /// How much the signing key is trusted, as reported by ```%GT```
+    pub trust_level: Option<TrustLevel>,
 }
 
+/// How much a signing key is trusted, one of the levels ```%GT``` reports
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum TrustLevel {
+    /// ```undefined```: nothing is known about the key, e.g. an ssh key missing from the allowed signers
+    Undefined,
+    /// ```never```: the key must never be trusted
+    Never,
+    /// ```marginal```
+    Marginal,
+    /// ```fully```: the key is trusted, e.g. an ssh key listed in the allowed signers
+    Fully,
+    /// ```ultimate```: usually one of your own keys
+    Ultimate,
+}
 
-impl Commit {
-    /// To initialize a blank Commit Struct
-    pub fn new() -> Commit {
-        Commit {
-            // branch: "".into(),
-            commit_date: None,
-            commit_message: None,
-            author_name: None,
-            author_email: None,
-            committer_name: None,
-            committer_email: None,
-            tree_hash: None,
-        }
+impl TrustLevel {
+    // Reads a %GT level
+    fn from_name(name: &str) -> Option<TrustLevel> {
+        Some(match name.trim() {
+            "undefined" => TrustLevel::Undefined,
+            "never" => TrustLevel::Never,
+            "marginal" => TrustLevel::Marginal,
+            "fully" => TrustLevel::Fully,
+            "ultimate" => TrustLevel::Ultimate,
+            _ => return None,
+        })
     }
 }
 
-impl Info {
-    /// To initialize the Info Struct. A &str pointing to the repo directory is expected
-    /// This implementation method checks that the directory does indeed exist and that the repo is a git repo
-    /// It returns a new Info Struct with the "dir" and "is_git" fields set
-    /// 
-    /// ## Example
-    /// ```
-    ///  # let mut path = env::current_dir().unwrap();
-    ///  # path.push("test_project");
-    ///  # let dir = path.to_string_lossy().to_string();
-    ///  // let dir = "/path/to/repo"; <- Point to the location of t=your repo
-    ///  let info = Info::new(&dir);
-    ///  println("{:#?}", info);
-    /// ```
-    pub fn new(dir: &str) -> Info {
-        // check if dir is_git
-        let mut project_path = PathBuf::from(dir);
-        project_path.push(".git");
+/// What checking the signature of a commit found, one of the codes ```%G?``` reports
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SignatureStatus {
+    /// ```G```: a good and valid signature
+    Good,
+    /// ```U```: a good signature made with a key of unknown validity, e.g. a gpg key that isn't trusted yet
+    GoodUnknownValidity,
+    /// ```X```: a good signature that has expired
+    Expired,
+    /// ```Y```: a good signature made with a key that has expired
+    ExpiredKey,
+    /// ```R```: a good signature made with a key that has been revoked
+    RevokedKey,
+    /// ```B```: a bad signature
+    Bad,
+    /// ```E```: the signature can't be checked, e.g. because the key is missing
+    Unverifiable,
+    /// ```N```: the commit isn't signed
+    Unsigned,
+}
 
-        let is_git = project_path.exists();
+impl SignatureStatus {
+    // Reads a %G? code
+    fn from_code(code: &str) -> Option<SignatureStatus> {
+        Some(match code.trim() {
+            "G" => SignatureStatus::Good,
+            "U" => SignatureStatus::GoodUnknownValidity,
+            "X" => SignatureStatus::Expired,
+            "Y" => SignatureStatus::ExpiredKey,
+            "R" => SignatureStatus::RevokedKey,
+            "B" => SignatureStatus::Bad,
+            "E" => SignatureStatus::Unverifiable,
+            "N" => SignatureStatus::Unsigned,
+            _ => return None,
+        })
+    }
 
-        Info {
-            dir: dir.into(),
-            is_git: is_git,
-            status: None,
-            commits: None,
-            branch: None,
-        }
+    /// Whether the commit carries a signature at all, whatever checking it found
+    pub fn is_signed(&self) -> bool {
+        *self != SignatureStatus::Unsigned
     }
 
-    /// Get information of all the commits.
-    /// This Method returns Info in its result.
-    /// If there are no commits, the returned value is None
-    /// ## Example
-    /// ```
-    ///  # let mut path = env::current_dir().unwrap();
-    ///  # path.push("test_project");
-    ///  # let dir = path.to_string_lossy().to_string();
-    ///  // let dir = "/path/to/repo"; <- Point to the location of t=your repo
-    ///  let commits_info = Info::new(&dir).commit_info()?;
-    ///  println("{:#?}", commits_info);
-    /// ```
-    pub fn commit_info(&self) -> Result<Info> {
-        let mut git_info = self.clone();
+    /// Whether the signature is good, made with a key git could verify it with.
+    /// Signatures made with keys of unknown validity count, since trusting the signer is up to the caller
+    pub fn is_good(&self) -> bool {
+        matches!(
+            self,
+            SignatureStatus::Good | SignatureStatus::GoodUnknownValidity
+        )
+    }
+}
 
-        if git_info.is_git {
-            let dir = &git_info.dir;
+/// The parts of a commit message that follows the Conventional Commits format, ```type(scope)!: description```
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ConventionalCommit {
+    /// The commit type, e.g. ```feat``` or ```fix```
+    pub kind: String,
+    /// The optional scope given in parentheses after the type
+    pub scope: Option<String>,
+    /// Whether the commit is marked as a breaking change, with ```!``` or a ```BREAKING CHANGE:``` footer
+    pub breaking: bool,
+    /// The description following the colon
+    pub description: String,
+}
 
-            //check diff
-            let branch = match run_fun!(
-                cd ${dir};
-                git branch -r |  grep -v HEAD | head -n 1 ;
-            ) {
-                Ok(resp) => {
-                    let r = resp.clone();
-                    r
-                }
-                _ => "".into(),
-            };
+/// An issue or pull request a commit message refers to, see [Commit::references]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct IssueRef {
+    /// The reference as written, e.g. ```#123```, ```GH-123``` or ```JIRA-456```
+    pub reference: String,
+    /// Which kind of reference it is
+    pub kind: IssueRefKind,
+    /// The keyword directly before the reference, as written, e.g. ```Fixes``` in ```Fixes: #123``` or ```closes #123```
+    pub keyword: Option<String>,
+    /// The byte offset of the reference in the message, the subject and body separated by a blank line
+    pub position: usize,
+}
 
-            let branch = &branch[..];
-            let branch = branch.trim();
-            // println!("BBB >> {:?}", branch);
-            git_info.branch = Some(branch.into());
+/// The kinds of references [Commit::references] finds
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IssueRefKind {
+    /// An issue or pull request number, e.g. ```#123```
+    Number,
+    /// A GitHub style reference, e.g. ```GH-123```
+    GitHub,
+    /// An issue key of trackers such as Jira, a project key followed by a number, e.g. ```JIRA-456```
+    Tracker,
+}
 
-            let format = format!("{{\"commit_date\":\"%ci\", \"commit_message\":\"%s\", \"author_name\":\"%an\", \"author_email\":\"%ae\", \"committer_name\":\"%cn\", \"committer_email\":\"%ce\",  \"tree_hash\":\"%t\"}}");
+/// Struct holding the aggregated commits of a single contributor
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Contributor {
+    /// The contributor name, as used in their most recent commit
+    pub name: String,
+    /// The contributor email
+    pub email: String,
+    /// Number of commits authored by the contributor
+    pub commit_count: u32,
+}
 
-            // let format = "%ci";
+/// A single movement of HEAD, as recorded in the reflog
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ReflogEntry {
+    /// The commit HEAD pointed at before the movement. All zeros if HEAD didn't exist yet
+    pub old_hash: String,
+    /// The commit HEAD pointed at after the movement
+    pub new_hash: String,
+    /// What moved HEAD, e.g. ```commit```, ```checkout```, ```reset``` or ```commit (amend)```
+    pub action: String,
+    /// The details recorded with the action, e.g. ```moving from main to feature```
+    pub message: String,
+    /// When the movement happened, in UTC
+    #[cfg(feature = "dates")]
+    pub date: DateTime<Utc>,
+    /// When the movement happened. Without the ```dates``` feature it is kept as git reports it
+    #[cfg(not(feature = "dates"))]
+    pub date: CommitDate,
+}
 
-            let empty_commit = json!(Commit::new());
+/// Line statistics summed over a range of commits
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct Churn {
+    /// Number of commits in the range
+    pub commits: u32,
+    /// Lines added across all commits
+    pub insertions: u64,
+    /// Lines removed across all commits
+    pub deletions: u64,
+    /// Number of distinct files changed, binary files included
+    pub files_touched: u32,
+}
 
-            let commits = match run_fun!(
-                cd ${dir};
-                git log --format="$format" $branch
-                // git status
-            ) {
-                Ok(resp) => resp,
-                Err(_) => {
-                    // println!("{:#?}", e);
-                    // Commit::new()
-                    empty_commit.to_string()
-                }
-            };
+/// The files and lines a single commit changed, see [Info::with_stats]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CommitStats {
+    /// Number of files changed, binary files included
+    pub files_changed: u32,
+    /// Lines added
+    pub insertions: u64,
+    /// Lines removed
+    pub deletions: u64,
+}
 
-            // println!("{:#?}", commits);
+/// When a repo was started and last worked on, see [Info::activity]
+#[cfg(feature = "dates")]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Activity {
+    /// The commit date of the oldest root commit reachable from HEAD
+    pub first_commit_date: Option<DateTime<Utc>>,
+    /// The commit date of HEAD
+    pub last_commit_date: Option<DateTime<Utc>>,
+    /// Whole days between the HEAD commit date and now
+    pub days_since_last_commit: Option<i64>,
+}
 
-            let commits = commits.split("\n").collect::<Vec<&str>>();
-            let len: usize = if commits.len() > 5 { 5 } else { commits.len() };
+/// A page of commits returned by [Info::commits_page]
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct CommitPage {
+    /// The commits on this page, newest first
+    pub commits: Vec<Commit>,
+    /// Whether more commits follow this page
+    pub has_more: bool,
+    /// How many commits the whole history has, when it could be told
+    pub total_hint: Option<usize>,
+}
 
-            // pick top
-            let top_commits: Vec<Commit> = commits[0..len]
-                .to_vec()
-                .iter()
-                .map(|s| {
-                    let commit: Commit = match from_str(s) {
-                        Ok(c) => c,
-                        _ => Commit::new(),
-                    };
-                    commit
-                })
-                .filter(|e: &Commit| {
-                    // let b:&Commit = e;
-                    e.commit_date != None
-                })
-                .collect();
+/// HEAD relative to the most recent tag, the parts of ```git describe --tags --dirty```, see [Info::describe].
+/// Displays the way git prints it
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Describe {
+    /// The most recent tag reachable from HEAD. None when there are no tags
+    pub tag: Option<String>,
+    /// How many commits HEAD is past the tag, 0 on the tag itself or without a tag
+    pub commits_since_tag: u32,
+    /// The abbreviated hash of HEAD
+    pub short_hash: String,
+    /// Whether the working tree has changes
+    pub dirty: bool,
+}
 
-            git_info.commits = if top_commits.len() > 0 {
-                Some(top_commits)
-            } else {
-                None
-            };
+impl std::fmt::Display for Describe {
+    // Like git, the count and hash are only spelled out past the tag
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.tag {
+            Some(tag) if self.commits_since_tag == 0 => write!(f, "{}", tag)?,
+            Some(tag) => write!(f, "{}-{}-g{}", tag, self.commits_since_tag, self.short_hash)?,
+            None => write!(f, "{}", self.short_hash)?,
+        }
+        if self.dirty {
+            write!(f, "-dirty")?;
+        }
+        Ok(())
+    }
+}
+
+/// What ```git fsck --connectivity-only``` found wrong with a repo, see [Info::verify]
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct IntegrityReport {
+    /// The hashes of objects nothing points at, e.g. commits of deleted branches. Harmless, ```git gc``` prunes them
+    pub dangling: Vec<String>,
+    /// The hashes of objects that are pointed at but missing from the object database
+    pub missing: Vec<String>,
+    /// Any other error git reported, e.g. about a corrupt object, as printed
+    pub errors: Vec<String>,
+}
+
+impl IntegrityReport {
+    /// Whether no object is missing and git reported no errors. Dangling objects are fine
+    pub fn is_ok(&self) -> bool {
+        self.missing.is_empty() && self.errors.is_empty()
+    }
+}
+
+/// Where a running ```git bisect``` session stands, see [Info::bisect_status]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct BisectStatus {
+    /// The full hash of the commit currently checked out for testing
+    pub current: String,
+    /// Roughly how many more steps the bisect needs. None until both a good and a bad commit are known
+    pub remaining_estimate: Option<u32>,
+}
+
+/// How git sees a path in the working tree
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PathStatus {
+    /// The path is in the index
+    Tracked,
+    /// The path exists but is neither tracked nor ignored
+    Untracked,
+    /// The path exists and matches a gitignore rule
+    Ignored,
+    /// The path neither exists nor is tracked
+    NotFound,
+}
+
+/// The kind of change made to a file between two trees
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangeKind {
+    /// The file only exists in the newer tree
+    Added,
+    /// The file content or mode changed
+    Modified,
+    /// The file only exists in the older tree
+    Deleted,
+    /// The file was moved, possibly with changes
+    Renamed,
+    /// The file was copied from another file, possibly with changes
+    Copied,
+}
+
+/// A single file changed between two refs, see [Info::diff_refs], or by a commit, see [Info::with_files]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct FileChange {
+    /// The path in the newer tree, or the removed path for deletions
+    pub path: String,
+    /// What happened to the file
+    pub change: ChangeKind,
+    /// The source path of a rename or copy
+    pub old_path: Option<String>,
+}
+
+/// What [Info::search_commits] looks for in commit messages. A plain `&str` is searched for as a regular expression
+/// ## Example
+/// ```
+///  # use commit_info::CommitSearch;
+///  let search = CommitSearch::new("CVE-2024-")
+///      .with_fixed_string(true)
+///      .with_ignore_case(true);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CommitSearch {
+    /// The text looked for, an extended regular expression unless `fixed_string` is set
+    pub pattern: String,
+    /// Whether the pattern is matched literally, like ```git log --fixed-strings```
+    pub fixed_string: bool,
+    /// Whether case is ignored, like ```git log --regexp-ignore-case```
+    pub ignore_case: bool,
+}
 
-            // println!("{:#?}",);
-            // git_info
+impl CommitSearch {
+    /// Searches for the regular expression `pattern`, minding case
+    pub fn new(pattern: impl Into<String>) -> CommitSearch {
+        CommitSearch {
+            pattern: pattern.into(),
+            ..CommitSearch::default()
         }
-        Ok(git_info)
     }
 
-    /// This method returns status information for the repo
-    /// ## Example
-    /// ```
-    ///  # let mut path = env::current_dir().unwrap();
-    ///  # path.push("test_project");
-    ///  # let dir = path.to_string_lossy().to_string();
-    ///  // let dir = "/path/to/repo"; <- Point to the location of t=your repo
-    ///  let status_info = Info::new(&dir).status_info()?;
-    ///  println("{:#?}", status_info);
-    /// ```
-    pub fn status_info(&self) -> Result<Info> {
-        let mut git_info = self.clone();
-        let mut status = Status {
-            error: None,
-            git_dirty: None,
-            summary: HashMap::new(),
-        };
-
-        if git_info.is_git {
-            let dir = &git_info.dir;
-
-            match run_fun!( cd ${dir}; git status -s; ) {
-                // if we can run git status then it is a git directory
-                Ok(resp) => {
-                    //
-                    let is_modified = resp.len() > 0;
-
-                    //check diff
-                    let resp = match run_fun!( cd ${dir}; git diff --stat; ) {
-                        Ok(r) => r,
-                        _ => "ERR".into(),
-                    };
-                    let is_dirty = resp.len() > 0;
-
-                    status.summary.insert("is_modified".into(), is_modified);
-                    status.summary.insert("is_dirty".into(), is_dirty);
-                    status.git_dirty = Some(is_dirty || is_modified);
-                }
-                Err(e) => {
-                    status.error = Some(format!("{:?}", e));
-                }
-            };
+    /// Sets whether the pattern is matched literally rather than as a regular expression
+    pub fn with_fixed_string(mut self, fixed_string: bool) -> CommitSearch {
+        self.fixed_string = fixed_string;
+        self
+    }
+
+    /// Sets whether case is ignored. Like git, this also ignores the case of [Info::with_author] and [Info::with_committer]
+    pub fn with_ignore_case(mut self, ignore_case: bool) -> CommitSearch {
+        self.ignore_case = ignore_case;
+        self
+    }
+
+    // The pattern as an extended regular expression, which both git and the regex crate read the same way
+    fn regex(&self) -> String {
+        if !self.fixed_string {
+            return self.pattern.clone();
+        }
+        let mut escaped = String::new();
+        for c in self.pattern.chars() {
+            if r"\.[]{}()*+?^$|".contains(c) {
+                escaped.push('\\');
+            }
+            escaped.push(c);
         }
+        escaped
+    }
+}
 
-        git_info.status = Some(status);
+impl From<&str> for CommitSearch {
+    fn from(pattern: &str) -> CommitSearch {
+        CommitSearch::new(pattern)
+    }
+}
 
-        Ok(git_info)
+impl From<String> for CommitSearch {
+    fn from(pattern: String) -> CommitSearch {
+        CommitSearch::new(pattern)
     }
 }
 
-mod my_date_format {
-    use chrono::{DateTime, TimeZone, Utc};
-    use serde::{self, Deserialize, Deserializer, Serializer};
+/// Selects the commits [GitBackend::log] lists
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LogQuery {
+    /// The revision or range to list, e.g. ```HEAD```, ```origin/main``` or ```v1.0..HEAD```
+    pub revision: String,
+    /// Number of the newest commits to leave out
+    pub skip: usize,
+    /// The most commits to list. All of them when None
+    pub max_count: Option<usize>,
+    /// Only list commits changing paths matching these, relative to the repo root. Empty for every commit
+    pub pathspecs: Vec<String>,
+    /// Whether merge commits are listed, see [Info::with_merges]
+    pub include_merges: bool,
+    /// Whether only the first parent of merges is followed, see [Info::first_parent]
+    pub first_parent: bool,
+    /// Whether identities are normalized with ```.mailmap```, see [Info::with_mailmap]
+    pub use_mailmap: bool,
+    /// Only list commits committed at or after this unix timestamp, see [Info::with_since]
+    pub since: Option<i64>,
+    /// Only list commits committed at or before this unix timestamp, see [Info::with_until]
+    pub until: Option<i64>,
+    /// Only list commits whose author, as ```Name <email>```, matches this regular expression, see [Info::with_author]
+    pub author: Option<String>,
+    /// Only list commits whose committer, as ```Name <email>```, matches this regular expression, see [Info::with_committer]
+    pub committer: Option<String>,
+    /// Only list commits whose message matches this, see [Info::search_commits]
+    pub grep: Option<CommitSearch>,
+    /// Whether [Commit::notes] is filled in, see [Info::with_notes]
+    pub notes: bool,
+    /// The full name of the ref [Commit::notes] are read from, e.g. ```refs/notes/qa```. The default notes ref when None
+    pub notes_ref: Option<String>,
+    /// Whether [Commit::stats] is filled in, see [Info::with_stats]
+    pub stats: bool,
+    /// Whether [Commit::files] is filled in, see [Info::with_files]
+    pub files: bool,
+    /// Whether [Commit::signature] and the signer of each commit are read, see [Info::with_signatures]
+    pub signatures: bool,
+}
+
+#[cfg(any(feature = "backend-git2", feature = "backend-gix"))]
+impl LogQuery {
+    // Whether the changes of a commit with this many parents are listed. Like `git log`, merges are only
+    // compared with their first parent when following it
+    fn diffs_commit(&self, parents: usize) -> bool {
+        parents <= 1 || self.first_parent
+    }
 
-    // 2014-08-29 16:09:40 -0600
+    // Whether a commit dated seconds lies between since and until, for backends that filter the commits themselves
+    fn in_date_range(&self, seconds: i64) -> bool {
+        self.since.is_none_or(|since| seconds >= since)
+            && self.until.is_none_or(|until| seconds <= until)
+    }
 
-    const FORMAT: &'static str = "%Y-%m-%d %H:%M:%S %Z";
+    // The author pattern compiled, or None when commits of every author are listed
+    fn author_regex(&self) -> Result<Option<Regex>> {
+        self.compile(self.author.as_deref())
+    }
 
-    // The signature of a serialize_with function must follow the pattern:
-    //
-    //    fn serialize<S>(&T, S) -> Result<S::Ok, S::Error>
-    //    where
-    //        S: Serializer
-    //
-    // although it may also be generic over the input types T.
-    pub fn serialize<S>(date: &Option<DateTime<Utc>>, serializer: S) -> Result<S::Ok, S::Error>
-    where
-        S: Serializer,
-    {
-        let s = match date {
-            Some(dt) => {
-                let s = format!("{}", dt.format(FORMAT));
-                s
-            }
-            _ => "null".into(),
-        };
+    // The committer pattern compiled, or None when commits of every committer are listed
+    fn committer_regex(&self) -> Result<Option<Regex>> {
+        self.compile(self.committer.as_deref())
+    }
 
-        serializer.serialize_str(&s)
+    // The message pattern compiled, or None when commits with any message are listed
+    fn grep_regex(&self) -> Result<Option<Regex>> {
+        self.compile(self.grep.as_ref().map(CommitSearch::regex).as_deref())
     }
 
-    // The signature of a deserialize_with function must follow the pattern:
-    //
-    //    fn deserialize<'de, D>(D) -> Result<T, D::Error>
-    //    where
-    //        D: Deserializer<'de>
-    //
-    // although it may also be generic over the output types T.
-    pub fn deserialize<'de, D>(deserializer: D) -> Result<Option<DateTime<Utc>>, D::Error>
-    where
-        D: Deserializer<'de>,
-    {
-        let s = String::deserialize(deserializer)?;
+    // Compiles pattern the way git compiles all the patterns of a log: git matches each line of a message on its own,
+    // and ignoring case applies to all of them
+    fn compile(&self, pattern: Option<&str>) -> Result<Option<Regex>> {
+        let ignore_case = self.grep.as_ref().is_some_and(|grep| grep.ignore_case);
+        let regex = pattern.map(|pattern| {
+            regex::RegexBuilder::new(pattern)
+                .case_insensitive(ignore_case)
+                .multi_line(true)
+                .build()
+        });
+        Ok(regex.transpose()?)
+    }
+}
 
-        let dt = Utc
-            .datetime_from_str(&s, FORMAT)
-            .map_err(serde::de::Error::custom)?;
+// Whether the `Name <email>` of an identity matches regex, which every identity does when there is none
+#[cfg(any(feature = "backend-git2", feature = "backend-gix"))]
+fn identity_matches(regex: Option<&Regex>, name: &str, email: &str) -> bool {
+    regex.is_none_or(|regex| regex.is_match(&format!("{} <{}>", name, email)))
+}
 
-        Ok(Some(dt))
+/// How [Info::commit_info] and [Info::status_info] read a repo.
+/// By default [Backend::Auto] picks one of the built in backends. Implement this trait to read repos some other way,
+/// e.g. through a git library, or to hand [Info] canned data in tests, and set it with [Info::with_backend]
+pub trait GitBackend: std::fmt::Debug + Send + Sync {
+    /// Fails if the backend can't be used at all, e.g. because git isn't installed. Checked before any other call
+    fn check(&self) -> Result<()> {
+        Ok(())
     }
+
+    /// Gathers the working tree status of the repo in `dir`, limited to `pathspecs` unless empty
+    fn status(&self, dir: &Path, pathspecs: &[&str]) -> Result<Status>;
+
+    /// Lists the commits selected by `query`, newest first
+    fn log(&self, dir: &Path, query: &LogQuery) -> Result<Vec<Commit>>;
+
+    /// Fails unless `reference` resolves to a commit
+    fn verify_ref(&self, dir: &Path, reference: &str) -> Result<()>;
+
+    /// The full hash of the commit HEAD points to. None for empty repos
+    fn head_hash(&self, dir: &Path) -> Option<String>;
+
+    /// The abbreviated hash of the commit HEAD points to. None for empty repos
+    fn head_short_hash(&self, dir: &Path) -> Option<String>;
+
+    /// The default branch of the origin remote, e.g. ```main```. None when there is no such remote
+    fn default_branch(&self, dir: &Path) -> Option<String>;
+
+    /// Any remote tracking branch, e.g. ```origin/main```, for when origin has no default branch. None without remotes
+    fn remote_branch(&self, dir: &Path) -> Option<String>;
+
+    /// Names what HEAD points at, see [Info::branch]. None for empty repos
+    fn head_name(&self, dir: &Path) -> Option<String>;
 }
 
+/// The [GitBackend] that runs the ```git``` binary found on PATH
+#[derive(Debug, Clone, Copy, Default)]
+pub struct GitCli;
 
-// To successfully run tests, first create a "test_project" directory at the home of this crate
-// Do so by running cargo new test_project
-// It is not included so you will need to create it yourself
-#[cfg(test)]
-mod tests {
+/// Picks one of the built in [GitBackend]s, see [Info::with_backend].
+/// The library backends are only available with the ```backend-git2``` and ```backend-gix``` features
+/// ## Example
+/// ```
+///  # use commit_info::{Backend, Info};
+///  # use std::env;
+///  # fn main() -> anyhow::Result<()> {
+///  # let mut path = env::current_dir().unwrap();
+///  # path.push("test_project");
+///  # let dir = path.to_string_lossy().to_string();
+///  // let dir = "/path/to/repo"; <- Point to the location of t=your repo
+///  let info = Info::new(&dir).with_backend(Backend::Cli).gather()?;
+///  println!("{:#?}", info);
+///  # Ok(())
+///  # }
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Backend {
+    /// Runs the ```git``` binary when it is installed, otherwise falls back to libgit2 and then gitoxide,
+    /// whichever is compiled in. Detected once per process
+    #[default]
+    Auto,
+    /// Always runs the ```git``` binary, see [GitCli]
+    Cli,
+    /// Reads repos with libgit2, see ```Git2Backend```
+    #[cfg(feature = "backend-git2")]
+    Git2,
+    /// Reads repos with gitoxide, see ```GixBackend```
+    #[cfg(feature = "backend-gix")]
+    Gix,
+}
 
-    use super::Info;
-    use std::env;
+/// The main struct that returns combined Status and Commits info.
+/// Its public fields are serialized with serde, the options set with the ```with_``` methods are not,
+/// so a deserialized Info gets the default ones. Fields missing from the input are left empty.
+/// Two Infos are equal when their fields and options are, whatever their backend
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct Info {
+    /// Repo directory. Serialized lossily, any bytes that aren't valid UTF-8 become the replacement character
+    #[serde(serialize_with = "lossy_path")]
+    pub dir: PathBuf,
+    /// Where the path passed to [Info::discover] lies inside [Info::dir], e.g. ```src/bin```.
+    /// Empty for the repo root itself and when created with [Info::new]
+    #[serde(serialize_with = "lossy_path")]
+    pub subpath: PathBuf,
+    /// Boolean indicating id the directory above is indeed a repo
+    pub is_git: bool,
+    /// Whether the repo is bare, e.g. ```/srv/git/project.git```, so it has no working tree.
+    /// Commits are read as usual, but [Info::status_info] leaves every [Status] field empty
+    pub is_bare: bool,
+    /// Set when the directory is a linked worktree, created with ```git worktree add```. None for the main working tree
+    pub worktree: Option<WorktreeInfo>,
+    /// Repo branch inspected. Without a remote this is the current branch, or for a detached HEAD
+    /// the nearest tag or ```(detached: <short hash>)```. None for empty repos
+    pub branch: Option<String>,
+    /// The default branch of the origin remote, e.g. ```main``` or ```develop```. None when there is no such remote
+    pub default_branch: Option<String>,
+    /// The full hash of the commit HEAD points to. None for empty repos or until [Info::commit_info] has been run
+    pub head_hash: Option<String>,
+    /// The abbreviated hash of the commit HEAD points to, as shown by ```git rev-parse --short HEAD```
+    pub head_short_hash: Option<String>,
+    /// Whether the repo is a shallow clone, whose history is truncated. Set by [Info::gather], see [Info::is_shallow]
+    pub shallow: Option<bool>,
+    /// Status information for the repo
+    pub status: Option<Status>,
+    /// Information on the repo commits
+    pub commits: Option<Vec<Commit>>,
+    // whether merge commits are listed, see with_merges
+    #[serde(skip)]
+    include_merges: bool,
+    // whether only the first parent of merges is followed, see first_parent
+    #[serde(skip)]
+    first_parent: bool,
+    // whether author and committer identities are normalized with .mailmap, see with_mailmap
+    #[serde(skip)]
+    use_mailmap: bool,
+    // how many commits commit_info lists, all of them when None, see with_commit_limit
+    #[serde(skip)]
+    commit_limit: Option<usize>,
+    // the ref commit_info reads instead of the detected branch, see with_ref
+    #[serde(skip)]
+    reference: Option<String>,
+    // the oldest commit date listed, see with_since
+    #[serde(skip)]
+    since: Option<i64>,
+    // the newest commit date listed, see with_until
+    #[serde(skip)]
+    until: Option<i64>,
+    // the pattern commit authors must match, see with_author
+    #[serde(skip)]
+    author: Option<String>,
+    // the pattern commit committers must match, see with_committer
+    #[serde(skip)]
+    committer: Option<String>,
+    // whether the notes of each commit are read, see with_notes
+    #[serde(skip)]
+    notes: bool,
+    // the full name of the ref notes are read from, see with_notes_ref
+    #[serde(skip)]
+    notes_ref: Option<String>,
+    // whether the files and lines each commit changed are read, see with_stats
+    #[serde(skip)]
+    stats: bool,
+    // whether the files each commit changed are listed, see with_files
+    #[serde(skip)]
+    files: bool,
+    // whether the signature of each commit is checked, see with_signatures
+    #[serde(skip)]
+    signatures: bool,
+    // how long commit_info and status_info may take, see with_timeout
+    #[serde(skip)]
+    timeout: Option<Duration>,
+    // stops commit_info and status_info early, see with_cancellation
+    #[serde(skip)]
+    cancellation: Option<CancellationToken>,
+    // the git binary that is run, see with_git_binary
+    #[serde(skip)]
+    git_binary: Option<PathBuf>,
+    // arguments passed to git before every subcommand, see with_git_args
+    #[serde(skip)]
+    git_args: Vec<String>,
+    // whether git sees the GIT_DIR and similar variables of this process, see with_inherited_git_env
+    #[serde(skip)]
+    inherit_git_env: bool,
+    // recent results of commit_info and status_info, see with_cache_ttl
+    #[serde(skip)]
+    cache: Option<ResultCache>,
+    // how the repo is read, see with_backend
+    #[serde(skip)]
+    backend: Arc<dyn GitBackend>,
+    // the timezone to_json renders commit dates in, see with_display_timezone
+    #[cfg(feature = "dates")]
+    #[serde(skip)]
+    display_timezone: Option<DisplayTimezone>,
+    // the strftime format to_json renders commit dates in, see with_date_format
+    #[cfg(feature = "dates")]
+    #[serde(skip)]
+    date_format: Option<String>,
+}
 
-    fn test_dir() -> String {
-        let mut path = env::current_dir().unwrap();
-        path.push("test_project");
+impl Commit {
+    /// To initialize a blank Commit Struct
+    #[deprecated(since = "0.1.2", note = "use Commit::default() instead")]
+    pub fn new() -> Commit {
+        Commit::default()
+    }
+}
 
-        path.to_string_lossy().to_string()
+impl Commit {
+    /// Returns the first line of the commit message
+    /// ## Example
+    /// ```
+    ///  # use commit_info::Commit;
+    ///  let mut commit = Commit::default();
+    ///  commit.commit_message = Some("Fix parser\n\nIt choked on quotes".into());
+    ///  assert_eq!(Some("Fix parser"), commit.short_message());
+    /// ```
+    pub fn short_message(&self) -> Option<&str> {
+        self.commit_message.as_deref()?.lines().next()
     }
 
-    #[test]
-    fn it_works() {
-        let dir = test_dir();
+    /// Whether the commit is a merge, i.e. it has more than one parent
+    /// ## Example
+    /// ```
+    ///  # use commit_info::Commit;
+    ///  let commit = Commit {
+    ///      parent_hashes: vec!["1a2b3c".into(), "4d5e6f".into()],
+    ///      ..Commit::default()
+    ///  };
+    ///  assert!(commit.is_merge());
+    /// ```
+    pub fn is_merge(&self) -> bool {
+        self.parent_hashes.len() > 1
+    }
 
-        let info = Info::new(&dir)
-            .status_info()
-            .expect("Unable to get status info")
-            .commit_info()
-            .expect("Unable to get commit info");
+    /// The name and email of everyone credited in a ```Co-authored-by:``` trailer
+    /// ## Example
+    /// ```
+    ///  # use commit_info::Commit;
+    ///  let commit = Commit {
+    ///      trailers: vec![("Co-authored-by".into(), "Ann <ann@example.com>".into())],
+    ///      ..Commit::default()
+    ///  };
+    ///  assert_eq!(vec![("Ann".to_string(), "ann@example.com".to_string())], commit.co_authors());
+    /// ```
+    pub fn co_authors(&self) -> Vec<(String, String)> {
+        self.trailer_identities("Co-authored-by")
+    }
 
-        assert_eq!(None, info.commits);
-        assert_eq!(Some(true), info.status.expect("err").git_dirty);
+    /// The name and email of everyone who signed off the commit in a ```Signed-off-by:``` trailer.
+    /// Commits read from a repo already carry the result in the [Commit::signed_off_by] field
+    /// ## Example
+    /// ```
+    ///  # use commit_info::Commit;
+    ///  let commit = Commit {
+    ///      trailers: vec![("Signed-off-by".into(), "Ann <ann@example.com>".into())],
+    ///      ..Commit::default()
+    ///  };
+    ///  assert_eq!(vec![("Ann".to_string(), "ann@example.com".to_string())], commit.signed_off_by());
+    /// ```
+    pub fn signed_off_by(&self) -> Vec<(String, String)> {
+        self.trailer_identities("Signed-off-by")
+    }
+
+    // The `Name <email>` values of the trailers named key, which git compares ignoring case
+    fn trailer_identities(&self, key: &str) -> Vec<(String, String)> {
+        self.trailers
+            .iter()
+            .filter(|(name, _)| name.eq_ignore_ascii_case(key))
+            .filter_map(|(_, value)| parse_identity(value))
+            .collect()
+    }
+
+    /// Parses the commit message as a Conventional Commit, e.g. ```feat(api)!: drop v1 endpoints```.
+    /// Returns None for messages that don't follow the format.
+    /// Commits read from a repo already carry the result in the [Commit::conventional] field
+    /// ## Example
+    /// ```
+    ///  # use commit_info::Commit;
+    ///  let mut commit = Commit::default();
+    ///  commit.commit_message = Some("feat(api): add pagination".into());
+    ///  let conventional = commit.conventional().unwrap();
+    ///  assert_eq!("feat", conventional.kind);
+    ///  assert_eq!(Some("api".to_string()), conventional.scope);
+    /// ```
+    pub fn conventional(&self) -> Option<ConventionalCommit> {
+        let subject = self.short_message()?;
+        let (head, description) = subject.split_once(": ")?;
+        let description = description.trim();
+
+        let (head, bang) = match head.strip_suffix('!') {
+            Some(head) => (head, true),
+            None => (head, false),
+        };
+        let (kind, scope) = match head.split_once('(') {
+            Some((kind, scope)) => {
+                let scope = scope.strip_suffix(')')?;
+                if scope.is_empty() || scope.contains(['(', ')']) {
+                    return None;
+                }
+                (kind, Some(scope.to_string()))
+            }
+            None => (head, None),
+        };
+
+        let valid_kind =
+            !kind.is_empty() && kind.chars().all(|c| c.is_ascii_alphanumeric() || c == '-');
+        if !valid_kind || description.is_empty() {
+            return None;
+        }
+
+        // breaking changes can also be flagged in a footer
+        let message = self.commit_message.as_deref().unwrap_or_default();
+        let body = self.commit_body.as_deref().unwrap_or_default();
+        let footer = message.lines().skip(1).chain(body.lines()).any(|line| {
+            line.starts_with("BREAKING CHANGE:") || line.starts_with("BREAKING-CHANGE:")
+        });
+
+        Some(ConventionalCommit {
+            kind: kind.into(),
+            scope,
+            breaking: bang || footer,
+            description: description.into(),
+        })
+    }
+
+    /// Finds the issues and pull requests the message refers to, in order of appearance: ```#123```, ```GH-123```
+    /// and tracker keys such as ```JIRA-456```, along with keywords such as ```Fixes:``` or ```closes``` right before them.
+    /// Anything shaped like a tracker key counts, so ```UTF-8``` is listed too
+    /// ## Example
+    /// ```
+    ///  # use commit_info::{Commit, IssueRefKind};
+    ///  let commit = Commit {
+    ///      commit_message: Some("Fix login redirect, see JIRA-456".into()),
+    ///      commit_body: Some("Fixes: #123".into()),
+    ///      ..Commit::default()
+    ///  };
+    ///  let references = commit.references();
+    ///  assert_eq!("JIRA-456", references[0].reference);
+    ///  assert_eq!(IssueRefKind::Tracker, references[0].kind);
+    ///  assert_eq!(Some("Fixes".to_string()), references[1].keyword);
+    /// ```
+    pub fn references(&self) -> Vec<IssueRef> {
+        let subject = self.commit_message.as_deref().unwrap_or_default();
+        let message = match &self.commit_body {
+            Some(body) => format!("{}\n\n{}", subject, body),
+            None => subject.to_string(),
+        };
+        parse_references(&message)
+    }
+
+    /// How long ago the commit was committed, or None without a commit date. Enabled by the ```dates``` feature
+    /// ## Example
+    /// ```
+    ///  # use commit_info::Commit;
+    ///  let commit = Commit {
+    ///      commit_date: chrono::DateTime::parse_from_rfc3339("2014-08-29T16:09:40-06:00").ok(),
+    ///      ..Commit::default()
+    ///  };
+    ///  assert!(commit.age().unwrap().num_days() > 365);
+    /// ```
+    #[cfg(feature = "dates")]
+    pub fn age(&self) -> Option<chrono::Duration> {
+        Some(Utc::now().signed_duration_since(self.commit_date?))
+    }
+
+    /// How long ago the commit was committed in words, like git's relative dates, e.g. ```3 days ago```
+    /// or ```1 year, 2 months ago```. None without a commit date. Enabled by the ```dates``` feature
+    /// ## Example
+    /// ```
+    ///  # use commit_info::Commit;
+    ///  let commit = Commit {
+    ///      commit_date: Some((chrono::Utc::now() - chrono::Duration::days(3)).into()),
+    ///      ..Commit::default()
+    ///  };
+    ///  assert_eq!(Some("3 days ago".to_string()), commit.relative_time());
+    /// ```
+    #[cfg(feature = "dates")]
+    pub fn relative_time(&self) -> Option<String> {
+        Some(relative_time(self.age()?.num_seconds()))
+    }
+
+    // Fills in the fields derived from the message once the rest of the commit is read
+    fn with_message_fields(mut self) -> Commit {
+        self.conventional = self.conventional();
+        self.signed_off_by = self.signed_off_by();
+        self.issue_refs = vec![];
+        for found in self.references() {
+            if !self.issue_refs.contains(&found.reference) {
+                self.issue_refs.push(found.reference);
+            }
+        }
+        self
+    }
+}
+
+impl Status {
+    /// Returns true only when git reported the repo as not dirty.
+    /// If the dirty state is unknown (`git_dirty` is None, e.g. because git failed) the repo is not considered clean
+    /// ## Example
+    /// ```
+    ///  # use commit_info::Info;
+    ///  # use std::env;
+    ///  # fn main() -> anyhow::Result<()> {
+    ///  # let mut path = env::current_dir().unwrap();
+    ///  # path.push("test_project");
+    ///  # let dir = path.to_string_lossy().to_string();
+    ///  // let dir = "/path/to/repo"; <- Point to the location of t=your repo
+    ///  let info = Info::new(&dir).status_info()?;
+    ///  if let Some(status) = info.status {
+    ///      println!("clean: {}", status.is_clean());
+    ///  }
+    ///  # Ok(())
+    ///  # }
+    /// ```
+    pub fn is_clean(&self) -> bool {
+        self.git_dirty == Some(false)
+    }
+
+    /// The ```is_modified``` entry of [Status::summary]: whether ```git status --porcelain``` listed any change,
+    /// untracked files included. None if git status failed
+    /// ## Example
+    /// ```
+    ///  # use commit_info::Info;
+    ///  # use std::env;
+    ///  # fn main() -> anyhow::Result<()> {
+    ///  # let mut path = env::current_dir().unwrap();
+    ///  # path.push("test_project");
+    ///  # let dir = path.to_string_lossy().to_string();
+    ///  // let dir = "/path/to/repo"; <- Point to the location of t=your repo
+    ///  if let Some(status) = Info::new(&dir).status_info()?.status {
+    ///      println!("modified: {:?}", status.is_modified());
+    ///  }
+    ///  # Ok(())
+    ///  # }
+    /// ```
+    pub fn is_modified(&self) -> Option<bool> {
+        self.summary.get("is_modified").copied()
+    }
+
+    /// The ```is_dirty``` entry of [Status::summary]: whether ```git diff --stat``` reported unstaged changes
+    /// to tracked files. None if it could not be run. See [Status::git_dirty] for the overall dirty state
+    /// ## Example
+    /// ```
+    ///  # use commit_info::Info;
+    ///  # use std::env;
+    ///  # fn main() -> anyhow::Result<()> {
+    ///  # let mut path = env::current_dir().unwrap();
+    ///  # path.push("test_project");
+    ///  # let dir = path.to_string_lossy().to_string();
+    ///  // let dir = "/path/to/repo"; <- Point to the location of t=your repo
+    ///  if let Some(status) = Info::new(&dir).status_info()?.status {
+    ///      println!("dirty: {:?}", status.is_dirty_flag());
+    ///  }
+    ///  # Ok(())
+    ///  # }
+    /// ```
+    pub fn is_dirty_flag(&self) -> Option<bool> {
+        self.summary.get("is_dirty").copied()
+    }
+
+    /// The ```ahead``` count of [Status::branch]: how many commits HEAD has that its upstream doesn't, the same as
+    /// ```git rev-list --left-right --count @{upstream}...HEAD``` reports. None without an upstream, or if it is gone
+    /// ## Example
+    /// ```
+    ///  # use commit_info::Info;
+    ///  # use std::env;
+    ///  # fn main() -> anyhow::Result<()> {
+    ///  # let mut path = env::current_dir().unwrap();
+    ///  # path.push("test_project");
+    ///  # let dir = path.to_string_lossy().to_string();
+    ///  // let dir = "/path/to/repo"; <- Point to the location of t=your repo
+    ///  if let Some(status) = Info::new(&dir).status_info()?.status {
+    ///      println!("↑{:?} ↓{:?}", status.ahead(), status.behind());
+    ///  }
+    ///  # Ok(())
+    ///  # }
+    /// ```
+    pub fn ahead(&self) -> Option<u32> {
+        self.branch.as_ref().and_then(|branch| branch.ahead)
+    }
+
+    /// The ```behind``` count of [Status::branch]: how many commits the upstream has that HEAD doesn't.
+    /// None without an upstream, or if it is gone
+    /// ## Example
+    /// ```
+    ///  # use commit_info::Info;
+    ///  # use std::env;
+    ///  # fn main() -> anyhow::Result<()> {
+    ///  # let mut path = env::current_dir().unwrap();
+    ///  # path.push("test_project");
+    ///  # let dir = path.to_string_lossy().to_string();
+    ///  // let dir = "/path/to/repo"; <- Point to the location of t=your repo
+    ///  if let Some(status) = Info::new(&dir).status_info()?.status {
+    ///      println!("behind by {:?}", status.behind());
+    ///  }
+    ///  # Ok(())
+    ///  # }
+    /// ```
+    pub fn behind(&self) -> Option<u32> {
+        self.branch.as_ref().and_then(|branch| branch.behind)
+    }
+
+    /// The ```upstream``` of [Status::branch]: the branch the current one tracks, e.g. ```origin/main```.
+    /// None if it doesn't track one
+    /// ## Example
+    /// ```
+    ///  # use commit_info::Info;
+    ///  # use std::env;
+    ///  # fn main() -> anyhow::Result<()> {
+    ///  # let mut path = env::current_dir().unwrap();
+    ///  # path.push("test_project");
+    ///  # let dir = path.to_string_lossy().to_string();
+    ///  // let dir = "/path/to/repo"; <- Point to the location of t=your repo
+    ///  if let Some(status) = Info::new(&dir).status_info()?.status {
+    ///      println!("tracking {:?}", status.upstream());
+    ///  }
+    ///  # Ok(())
+    ///  # }
+    /// ```
+    pub fn upstream(&self) -> Option<&str> {
+        self.branch.as_ref()?.upstream.as_deref()
+    }
+
+    /// Returns every staged, unstaged and untracked path, i.e. exactly the files that make the repo dirty.
+    /// The paths are sorted and each appears once
+    /// ## Example
+    /// ```
+    ///  # use commit_info::Info;
+    ///  # use std::env;
+    ///  # fn main() -> anyhow::Result<()> {
+    ///  # let mut path = env::current_dir().unwrap();
+    ///  # path.push("test_project");
+    ///  # let dir = path.to_string_lossy().to_string();
+    ///  // let dir = "/path/to/repo"; <- Point to the location of t=your repo
+    ///  let info = Info::new(&dir).status_info()?;
+    ///  if let Some(status) = info.status {
+    ///      println!("these files are dirty: {:?}", status.dirty_paths());
+    ///  }
+    ///  # Ok(())
+    ///  # }
+    /// ```
+    pub fn dirty_paths(&self) -> Vec<String> {
+        let mut paths: Vec<String> = self
+            .staged
+            .iter()
+            .chain(&self.unstaged)
+            .chain(&self.untracked)
+            .cloned()
+            .collect();
+        paths.sort();
+        paths.dedup();
+        paths
+    }
+
+    /// Counts the paths of [Status::entries] in each state. A path can be counted more than once,
+    /// e.g. a renamed file that was changed again after being staged is staged, unstaged and renamed
+    /// ## Example
+    /// ```
+    ///  # use commit_info::Info;
+    ///  # use std::env;
+    ///  # fn main() -> anyhow::Result<()> {
+    ///  # let mut path = env::current_dir().unwrap();
+    ///  # path.push("test_project");
+    ///  # let dir = path.to_string_lossy().to_string();
+    ///  // let dir = "/path/to/repo"; <- Point to the location of t=your repo
+    ///  if let Some(status) = Info::new(&dir).status_info()?.status {
+    ///      let counts = status.counts();
+    ///      println!("{} staged, {} untracked", counts.staged, counts.untracked);
+    ///  }
+    ///  # Ok(())
+    ///  # }
+    /// ```
+    pub fn counts(&self) -> StatusCounts {
+        let mut counts = StatusCounts::default();
+        for entry in &self.entries {
+            let (x, y) = (entry.index_status, entry.worktree_status);
+            if x == '?' {
+                counts.untracked += 1;
+                continue;
+            }
+            if entry.is_conflicted() {
+                counts.conflicted += 1;
+                continue;
+            }
+            counts.staged += usize::from(x != ' ');
+            counts.unstaged += usize::from(y != ' ');
+            counts.renamed += usize::from(x == 'R' || y == 'R');
+            counts.deleted += usize::from(x == 'D' || y == 'D');
+        }
+        counts
+    }
+
+    // Fills in the summary and branch from the output of `git status --porcelain=v2 --branch --ignored -z`
+    // and `git diff --stat`. If the diff could not be run we cannot tell whether the repo is dirty, so git_dirty stays None
+    fn summarize(&mut self, porcelain: &str, diff: Result<String, CommitInfoError>) {
+        // ignored entries are reported separately and do not count as modifications
+        let mut is_modified = false;
+        let mut branch = BranchStatus::default();
+        // entries are NUL terminated and paths are never quoted
+        let mut entries = porcelain.split('\0');
+        while let Some(entry) = entries.next() {
+            // changed entries are a kind, the XY status and other space separated fields, followed by the path
+            // which may itself contain spaces
+            let (fields, renamed_from) = match entry.chars().next() {
+                Some('#') => {
+                    branch.read_header(entry);
+                    continue;
+                }
+                Some('!') => {
+                    self.ignored.push(entry.get(2..).unwrap_or_default().into());
+                    continue;
+                }
+                Some('?') => {
+                    let path: String = entry.get(2..).unwrap_or_default().into();
+                    self.entries.push(StatusEntry {
+                        path: path.clone(),
+                        index_status: '?',
+                        worktree_status: '?',
+                        renamed_from: None,
+                    });
+                    self.untracked.push(path);
+                    is_modified = true;
+                    continue;
+                }
+                Some('1') => (entry.splitn(9, ' '), None),
+                // renames and copies are followed by the path they came from
+                Some('2') => (entry.splitn(10, ' '), entries.next()),
+                Some('u') => (entry.splitn(11, ' '), None),
+                _ => continue,
+            };
+
+            let fields: Vec<&str> = fields.collect();
+            let (xy, path) = match (fields.get(1), fields.last()) {
+                (Some(xy), Some(path)) if fields.len() > 2 => (*xy, *path),
+                _ => continue,
+            };
+            // unchanged sides are shown as a dot
+            let mut xy = xy.chars().map(|c| if c == '.' { ' ' } else { c });
+            let (x, y) = (xy.next().unwrap_or(' '), xy.next().unwrap_or(' '));
+            if x != ' ' {
+                self.staged.push(path.into());
+            }
+            if y != ' ' {
+                self.unstaged.push(path.into());
+            }
+            self.entries.push(StatusEntry {
+                path: path.into(),
+                index_status: x,
+                worktree_status: y,
+                renamed_from: renamed_from.map(String::from),
+            });
+            is_modified = true;
+        }
+        // untracked paths are listed after the changed ones
+        self.entries.sort_by(|a, b| a.path.cmp(&b.path));
+        self.branch = Some(branch);
+        self.summary.insert("is_modified".into(), is_modified);
+        self.has_staged = Some(!self.staged.is_empty());
+        self.has_unstaged = Some(!self.unstaged.is_empty());
+
+        match diff {
+            Ok(resp) => {
+                let is_dirty = !resp.is_empty();
+                self.summary.insert("is_dirty".into(), is_dirty);
+                self.git_dirty = Some(is_dirty || is_modified);
+            }
+            Err(e) => {
+                self.error = Some(e.to_string());
+            }
+        }
+    }
+
+    // Fills in the summary from the staged, unstaged and untracked paths, for backends that list them directly
+    #[cfg(any(feature = "backend-git2", feature = "backend-gix"))]
+    fn tally(&mut self) {
+        let is_modified =
+            !(self.staged.is_empty() && self.unstaged.is_empty() && self.untracked.is_empty());
+        // `git diff --stat` lists exactly the unstaged changes to tracked files
+        let is_dirty = !self.unstaged.is_empty();
+        self.summary.insert("is_modified".into(), is_modified);
+        self.summary.insert("is_dirty".into(), is_dirty);
+        self.git_dirty = Some(is_dirty || is_modified);
+        self.has_staged = Some(!self.staged.is_empty());
+        self.has_unstaged = Some(!self.unstaged.is_empty());
+    }
+}
+
+impl Default for Info {
+    /// An Info for no directory, with the default options
+    fn default() -> Self {
+        Info {
+            dir: PathBuf::new(),
+            subpath: PathBuf::new(),
+            is_git: false,
+            is_bare: false,
+            worktree: None,
+            status: None,
+            commits: None,
+            branch: None,
+            default_branch: None,
+            head_hash: None,
+            head_short_hash: None,
+            shallow: None,
+            include_merges: true,
+            first_parent: false,
+            use_mailmap: true,
+            commit_limit: Some(5),
+            reference: None,
+            since: None,
+            until: None,
+            author: None,
+            committer: None,
+            notes: false,
+            notes_ref: None,
+            stats: false,
+            files: false,
+            signatures: false,
+            timeout: None,
+            cancellation: None,
+            git_binary: None,
+            git_args: vec![],
+            inherit_git_env: false,
+            cache: None,
+            backend: Arc::new(Backend::Auto),
+            #[cfg(feature = "dates")]
+            display_timezone: None,
+            #[cfg(feature = "dates")]
+            date_format: None,
+        }
+    }
+}
+
+impl PartialEq for Info {
+    fn eq(&self, other: &Self) -> bool {
+        #[cfg(feature = "dates")]
+        let same_rendering = self.display_timezone == other.display_timezone
+            && self.date_format == other.date_format;
+        #[cfg(not(feature = "dates"))]
+        let same_rendering = true;
+
+        // the backend, cache and cancellation token don't change what an Info holds
+        same_rendering
+            && self.dir == other.dir
+            && self.subpath == other.subpath
+            && self.is_git == other.is_git
+            && self.is_bare == other.is_bare
+            && self.worktree == other.worktree
+            && self.branch == other.branch
+            && self.default_branch == other.default_branch
+            && self.head_hash == other.head_hash
+            && self.head_short_hash == other.head_short_hash
+            && self.shallow == other.shallow
+            && self.status == other.status
+            && self.commits == other.commits
+            && self.include_merges == other.include_merges
+            && self.first_parent == other.first_parent
+            && self.use_mailmap == other.use_mailmap
+            && self.commit_limit == other.commit_limit
+            && self.reference == other.reference
+            && self.since == other.since
+            && self.until == other.until
+            && self.author == other.author
+            && self.committer == other.committer
+            && self.notes == other.notes
+            && self.notes_ref == other.notes_ref
+            && self.stats == other.stats
+            && self.files == other.files
+            && self.signatures == other.signatures
+            && self.timeout == other.timeout
+            && self.git_binary == other.git_binary
+            && self.git_args == other.git_args
+            && self.inherit_git_env == other.inherit_git_env
+    }
+}
+
+impl Eq for Info {}
+
+impl Info {
+    /// To initialize the Info Struct. Any path to the repo directory is accepted, a &str, String, Path or PathBuf,
+    /// and paths that aren't valid UTF-8 are kept as they are.
+    /// This implementation method checks that the directory does indeed exist and that the repo is a git repo
+    /// It returns a new Info Struct with the "dir" and "is_git" fields set
+    ///
+    /// ## Example
+    /// ```
+    ///  # use commit_info::Info;
+    ///  # use std::env;
+    ///  # fn main() -> anyhow::Result<()> {
+    ///  # let mut path = env::current_dir().unwrap();
+    ///  # path.push("test_project");
+    ///  // let path = PathBuf::from("/path/to/repo"); <- Point to the location of t=your repo
+    ///  let info = Info::new(&path);
+    ///  println!("{:#?}", info);
+    ///  # Ok(())
+    ///  # }
+    /// ```
+    pub fn new(dir: impl AsRef<Path>) -> Info {
+        let dir = dir.as_ref();
+        // check if dir is_git
+        let is_bare = is_bare_repo(dir);
+        let is_git = is_bare || dir.join(".git").exists();
+
+        Info {
+            dir: dir.into(),
+            is_git,
+            is_bare,
+            worktree: WorktreeInfo::read(dir),
+            ..Info::default()
+        }
+    }
+
+    /// Tells whether the ```git``` binary can be run, by checking that ```git --version``` succeeds.
+    /// The binary named by the ```COMMIT_INFO_GIT_BIN``` environment variable is checked instead when it is set.
+    /// When it can't, every method that runs git returns [CommitInfoError::GitBinaryMissing]
+    /// ## Example
+    /// ```
+    ///  # use commit_info::Info;
+    ///  if !Info::git_available() {
+    ///      println!("please install git");
+    ///  }
+    /// ```
+    pub fn git_available() -> bool {
+        RunOptions::default().git_runs()
+    }
+
+    /// Same as [Info::new] but fails with [CommitInfoError::DirectoryNotFound] if `dir` doesn't exist and with
+    /// [CommitInfoError::NotADirectory] if it isn't a directory, so a missing directory can be told apart
+    /// from one that simply isn't a git repo
+    /// ## Example
+    /// ```
+    ///  # use commit_info::Info;
+    ///  # use std::env;
+    ///  # fn main() -> anyhow::Result<()> {
+    ///  # let mut path = env::current_dir().unwrap();
+    ///  # path.push("test_project");
+    ///  # let dir = path.to_string_lossy().to_string();
+    ///  // let dir = "/path/to/repo"; <- Point to the location of t=your repo
+    ///  let info = Info::try_new(&dir)?;
+    ///  println!("{:#?}", info);
+    ///  # Ok(())
+    ///  # }
+    /// ```
+    pub fn try_new(dir: impl AsRef<Path>) -> Result<Info, CommitInfoError> {
+        let dir = dir.as_ref();
+        if !dir.exists() {
+            return Err(CommitInfoError::DirectoryNotFound(dir.into()));
+        }
+        if !dir.is_dir() {
+            return Err(CommitInfoError::NotADirectory(dir.into()));
+        }
+
+        Ok(Info::new(dir))
+    }
+
+    /// Finds the repo containing `path`, which may be any directory inside it, by walking up its parents
+    /// until one has a ```.git```, like ```git rev-parse --show-toplevel```. [Info::dir] is set to the repo root
+    /// and [Info::subpath] to where `path` lies inside it. Fails with [CommitInfoError::DirectoryNotFound] if `path`
+    /// doesn't exist and with [CommitInfoError::NotARepository] if it isn't inside a repo
+    /// ## Example
+    /// ```
+    ///  # use commit_info::Info;
+    ///  # use std::env;
+    ///  # fn main() -> anyhow::Result<()> {
+    ///  # let mut path = env::current_dir().unwrap();
+    ///  # path.push("test_project/src");
+    ///  // let path = "/path/to/repo/src"; <- Point to a directory inside your repo
+    ///  let info = Info::discover(&path)?;
+    ///  println!("{} is in {}", info.subpath.display(), info.dir.display());
+    ///  # Ok(())
+    ///  # }
+    /// ```
+    pub fn discover(path: impl AsRef<Path>) -> Result<Info, CommitInfoError> {
+        let path = path.as_ref();
+        let start = match path.canonicalize() {
+            Ok(start) => start,
+            Err(_) => return Err(CommitInfoError::DirectoryNotFound(path.into())),
+        };
+
+        let root = start
+            .ancestors()
+            .find(|dir| dir.join(".git").exists() || is_bare_repo(dir));
+        let root = match root {
+            Some(root) => root,
+            None => return Err(CommitInfoError::NotARepository(path.into())),
+        };
+        let mut info = Info::new(root);
+        // start is one of root's descendants, so this can't fail
+        info.subpath = start.strip_prefix(root).unwrap_or(Path::new("")).into();
+        Ok(info)
+    }
+
+    /// Sets whether merge commits are included when listing commits. They are included by default.
+    /// This applies to every method that reads the commit history, e.g. [Info::commit_info] and [Info::commits_page]
+    /// ## Example
+    /// ```
+    ///  # use commit_info::Info;
+    ///  # use std::env;
+    ///  # fn main() -> anyhow::Result<()> {
+    ///  # let mut path = env::current_dir().unwrap();
+    ///  # path.push("test_project");
+    ///  # let dir = path.to_string_lossy().to_string();
+    ///  // let dir = "/path/to/repo"; <- Point to the location of t=your repo
+    ///  let changelog = Info::new(&dir).with_merges(false).commit_info()?;
+    ///  println!("{:#?}", changelog.commits);
+    ///  # Ok(())
+    ///  # }
+    /// ```
+    pub fn with_merges(mut self, include: bool) -> Info {
+        self.include_merges = include;
+        self
+    }
+
+    /// Sets whether only the first parent of each merge is followed when walking the history. Off by default.
+    /// This shows the mainline of a branch: commits brought in by merges are skipped, the merges themselves are kept.
+    /// Like [Info::with_merges] it applies to every method that reads the commit history
+    /// ## Example
+    /// ```
+    ///  # use commit_info::Info;
+    ///  # use std::env;
+    ///  # fn main() -> anyhow::Result<()> {
+    ///  # let mut path = env::current_dir().unwrap();
+    ///  # path.push("test_project");
+    ///  # let dir = path.to_string_lossy().to_string();
+    ///  // let dir = "/path/to/repo"; <- Point to the location of t=your repo
+    ///  let mainline = Info::new(&dir).first_parent(true).commit_info()?;
+    ///  println!("{:#?}", mainline.commits);
+    ///  # Ok(())
+    ///  # }
+    /// ```
+    pub fn first_parent(mut self, enabled: bool) -> Info {
+        self.first_parent = enabled;
+        self
+    }
+
+    /// Sets whether author and committer names and emails are normalized with the repo's ```.mailmap```. On by default.
+    /// Turn it off to get the identities exactly as recorded in the commits, e.g. in [Info::commit_info] and [Info::contributors]
+    /// ## Example
+    /// ```
+    ///  # use commit_info::Info;
+    ///  # use std::env;
+    ///  # fn main() -> anyhow::Result<()> {
+    ///  # let mut path = env::current_dir().unwrap();
+    ///  # path.push("test_project");
+    ///  # let dir = path.to_string_lossy().to_string();
+    ///  // let dir = "/path/to/repo"; <- Point to the location of t=your repo
+    ///  let raw = Info::new(&dir).with_mailmap(false).contributors()?;
+    ///  println!("{:#?}", raw);
+    ///  # Ok(())
+    ///  # }
+    /// ```
+    pub fn with_mailmap(mut self, enabled: bool) -> Info {
+        self.use_mailmap = enabled;
+        self
+    }
+
+    /// Sets how many of the latest commits [Info::commit_info] lists. 5 by default.
+    /// Only that many are read from git. Use [Info::commit_info_n] with None to list them all,
+    /// or [Info::commits_iter] to walk the whole history one commit at a time
+    /// ## Example
+    /// ```
+    ///  # use commit_info::Info;
+    ///  # use std::env;
+    ///  # fn main() -> anyhow::Result<()> {
+    ///  # let mut path = env::current_dir().unwrap();
+    ///  # path.push("test_project");
+    ///  # let dir = path.to_string_lossy().to_string();
+    ///  // let dir = "/path/to/repo"; <- Point to the location of t=your repo
+    ///  let info = Info::new(&dir).with_commit_limit(20).commit_info()?;
+    ///  println!("{:#?}", info.commits);
+    ///  # Ok(())
+    ///  # }
+    /// ```
+    pub fn with_commit_limit(mut self, limit: usize) -> Info {
+        self.commit_limit = Some(limit);
+        self
+    }
+
+    /// Makes [Info::commit_info] read the commits of the given branch, tag or any other ref,
+    /// as [Info::commit_info_for] does, instead of picking the remote's default branch or the current one
+    /// ## Example
+    /// ```
+    ///  # use commit_info::Info;
+    ///  # use std::env;
+    ///  # fn main() -> anyhow::Result<()> {
+    ///  # let mut path = env::current_dir().unwrap();
+    ///  # path.push("test_project");
+    ///  # let dir = path.to_string_lossy().to_string();
+    ///  // let dir = "/path/to/repo"; <- Point to the location of t=your repo
+    ///  let release_info = Info::new(&dir).with_ref("origin/release").commit_info();
+    ///  println!("{:#?}", release_info);
+    ///  # Ok(())
+    ///  # }
+    /// ```
+    pub fn with_ref(mut self, reference: impl Into<String>) -> Info {
+        self.reference = Some(reference.into());
+        self
+    }
+
+    /// Only lists commits committed at or after the given unix timestamp, like ```git log --since```.
+    /// Like [Info::with_merges] it applies to every method that reads the commit history
+    /// ## Example
+    /// ```
+    ///  # use commit_info::Info;
+    ///  # use std::env;
+    ///  # use std::time::{SystemTime, UNIX_EPOCH};
+    ///  # fn main() -> anyhow::Result<()> {
+    ///  # let mut path = env::current_dir().unwrap();
+    ///  # path.push("test_project");
+    ///  # let dir = path.to_string_lossy().to_string();
+    ///  // let dir = "/path/to/repo"; <- Point to the location of t=your repo
+    ///  let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() as i64;
+    ///  let last_week = Info::new(&dir).with_since(now - 7 * 86_400).commit_info()?;
+    ///  println!("{:#?}", last_week.commits);
+    ///  # Ok(())
+    ///  # }
+    /// ```
+    pub fn with_since(mut self, timestamp: i64) -> Info {
+        self.since = Some(timestamp);
+        self
+    }
+
+    /// Only lists commits committed at or before the given unix timestamp, like ```git log --until```.
+    /// Like [Info::with_merges] it applies to every method that reads the commit history
+    /// ## Example
+    /// ```
+    ///  # use commit_info::Info;
+    ///  # use std::env;
+    ///  # fn main() -> anyhow::Result<()> {
+    ///  # let mut path = env::current_dir().unwrap();
+    ///  # path.push("test_project");
+    ///  # let dir = path.to_string_lossy().to_string();
+    ///  // let dir = "/path/to/repo"; <- Point to the location of t=your repo
+    ///  // everything committed before 2023
+    ///  let old = Info::new(&dir).with_until(1_672_531_199).commit_info()?;
+    ///  println!("{:#?}", old.commits);
+    ///  # Ok(())
+    ///  # }
+    /// ```
+    pub fn with_until(mut self, timestamp: i64) -> Info {
+        self.until = Some(timestamp);
+        self
+    }
+
+    /// Same as [Info::with_since], but takes a ```chrono``` date in any timezone. Enabled by the ```dates``` feature
+    /// ## Example
+    /// ```
+    ///  # use commit_info::Info;
+    ///  # use std::env;
+    ///  # fn main() -> anyhow::Result<()> {
+    ///  # let mut path = env::current_dir().unwrap();
+    ///  # path.push("test_project");
+    ///  # let dir = path.to_string_lossy().to_string();
+    ///  // let dir = "/path/to/repo"; <- Point to the location of t=your repo
+    ///  let month_ago = chrono::Utc::now() - chrono::Duration::days(30);
+    ///  let last_month = Info::new(&dir).with_since_date(month_ago).commit_info()?;
+    ///  println!("{:#?}", last_month.commits);
+    ///  # Ok(())
+    ///  # }
+    /// ```
+    #[cfg(feature = "dates")]
+    pub fn with_since_date<Tz: TimeZone>(self, date: DateTime<Tz>) -> Info {
+        self.with_since(date.timestamp())
+    }
+
+    /// Same as [Info::with_until], but takes a ```chrono``` date in any timezone. Enabled by the ```dates``` feature
+    /// ## Example
+    /// ```
+    ///  # use commit_info::Info;
+    ///  # use std::env;
+    ///  # fn main() -> anyhow::Result<()> {
+    ///  # let mut path = env::current_dir().unwrap();
+    ///  # path.push("test_project");
+    ///  # let dir = path.to_string_lossy().to_string();
+    ///  // let dir = "/path/to/repo"; <- Point to the location of t=your repo
+    ///  let new_year = chrono::DateTime::parse_from_rfc3339("2024-01-01T00:00:00+03:00")?;
+    ///  let old = Info::new(&dir).with_until_date(new_year).commit_info()?;
+    ///  println!("{:#?}", old.commits);
+    ///  # Ok(())
+    ///  # }
+    /// ```
+    #[cfg(feature = "dates")]
+    pub fn with_until_date<Tz: TimeZone>(self, date: DateTime<Tz>) -> Info {
+        self.with_until(date.timestamp())
+    }
+
+    /// Only lists commits whose author matches the given regular expression, like ```git log --author```.
+    /// It is matched against ```Name <email>```, so either part may be used.
+    /// Like [Info::with_merges] it applies to every method that reads the commit history
+    /// ## Example
+    /// ```
+    ///  # use commit_info::Info;
+    ///  # use std::env;
+    ///  # fn main() -> anyhow::Result<()> {
+    ///  # let mut path = env::current_dir().unwrap();
+    ///  # path.push("test_project");
+    ///  # let dir = path.to_string_lossy().to_string();
+    ///  // let dir = "/path/to/repo"; <- Point to the location of t=your repo
+    ///  let mine = Info::new(&dir).with_author("@example.com").commit_info()?;
+    ///  println!("{:#?}", mine.commits);
+    ///  # Ok(())
+    ///  # }
+    /// ```
+    pub fn with_author(mut self, pattern: impl Into<String>) -> Info {
+        self.author = Some(pattern.into());
+        self
+    }
+
+    /// Only lists commits whose committer matches the given regular expression, like ```git log --committer```.
+    /// The committer is whoever applied the commit, e.g. the maintainer who merged or rebased someone else's work.
+    /// Like [Info::with_author] it is matched against ```Name <email>``` and applies to every method that reads the commit history
+    /// ## Example
+    /// ```
+    ///  # use commit_info::Info;
+    ///  # use std::env;
+    ///  # fn main() -> anyhow::Result<()> {
+    ///  # let mut path = env::current_dir().unwrap();
+    ///  # path.push("test_project");
+    ///  # let dir = path.to_string_lossy().to_string();
+    ///  // let dir = "/path/to/repo"; <- Point to the location of t=your repo
+    ///  let applied = Info::new(&dir).with_committer("^Alice ").commit_info()?;
+    ///  println!("{:#?}", applied.commits);
+    ///  # Ok(())
+    ///  # }
+    /// ```
+    pub fn with_committer(mut self, pattern: impl Into<String>) -> Info {
+        self.committer = Some(pattern.into());
+        self
+    }
+
+    /// Sets whether the note attached to each commit with ```git notes``` is read into [Commit::notes]. Off by default.
+    /// They come from ```refs/notes/commits``` unless another ref is set with [Info::with_notes_ref]
+    /// ## Example
+    /// ```
+    ///  # use commit_info::Info;
+    ///  # use std::env;
+    ///  # fn main() -> anyhow::Result<()> {
+    ///  # let mut path = env::current_dir().unwrap();
+    ///  # path.push("test_project");
+    ///  # let dir = path.to_string_lossy().to_string();
+    ///  // let dir = "/path/to/repo"; <- Point to the location of t=your repo
+    ///  let info = Info::new(&dir).with_notes(true).commit_info()?;
+    ///  for commit in info.commits.iter().flatten() {
+    ///      println!("{:?}: {:?}", commit.short_hash, commit.notes);
+    ///  }
+    ///  # Ok(())
+    ///  # }
+    /// ```
+    pub fn with_notes(mut self, enabled: bool) -> Info {
+        self.notes = enabled;
+        self
+    }
+
+    /// Picks the notes ref [Info::with_notes] reads from instead of the default ```refs/notes/commits```,
+    /// like ```git log --notes=<ref>```. Short names such as ```qa``` are taken to mean ```refs/notes/qa```
+    /// ## Example
+    /// ```
+    ///  # use commit_info::Info;
+    ///  # use std::env;
+    ///  # fn main() -> anyhow::Result<()> {
+    ///  # let mut path = env::current_dir().unwrap();
+    ///  # path.push("test_project");
+    ///  # let dir = path.to_string_lossy().to_string();
+    ///  // let dir = "/path/to/repo"; <- Point to the location of t=your repo
+    ///  let info = Info::new(&dir)
+    ///      .with_notes(true)
+    ///      .with_notes_ref("qa")
+    ///      .commit_info()?;
+    ///  for commit in info.commits.iter().flatten() {
+    ///      println!("{:?}: {:?}", commit.short_hash, commit.notes);
+    ///  }
+    ///  # Ok(())
+    ///  # }
+    /// ```
+    pub fn with_notes_ref(mut self, reference: impl Into<String>) -> Info {
+        let reference = reference.into();
+        // the same expansion git applies to --notes=<ref>
+        self.notes_ref = Some(if reference.starts_with("refs/notes/") {
+            reference
+        } else if reference.starts_with("notes/") {
+            format!("refs/{}", reference)
+        } else {
+            format!("refs/notes/{}", reference)
+        });
+        self
+    }
+
+    /// Sets whether the files and lines changed by each commit are read into [Commit::stats]. Off by default.
+    /// They are counted like ```git log --numstat --no-renames```: a rename is a deletion and an addition,
+    /// binary files count as changed files without lines and merges count as changing nothing, unless [Info::first_parent]
+    /// compares them with their first parent. [Info::commits_iter] doesn't read them
+    /// ## Example
+    /// ```
+    ///  # use commit_info::Info;
+    ///  # use std::env;
+    ///  # fn main() -> anyhow::Result<()> {
+    ///  # let mut path = env::current_dir().unwrap();
+    ///  # path.push("test_project");
+    ///  # let dir = path.to_string_lossy().to_string();
+    ///  // let dir = "/path/to/repo"; <- Point to the location of t=your repo
+    ///  let info = Info::new(&dir).with_stats(true).commit_info()?;
+    ///  for commit in info.commits.unwrap_or_default() {
+    ///      println!("{:?}: {:?}", commit.commit_message, commit.stats);
+    ///  }
+    ///  # Ok(())
+    ///  # }
+    /// ```
+    pub fn with_stats(mut self, enabled: bool) -> Info {
+        self.stats = enabled;
+        self
+    }
+
+    /// Sets whether the files changed by each commit are listed in [Commit::files]. Off by default, since it takes
+    /// another walk over the history. Renames are detected and carry the path they were moved from.
+    /// Like with [Info::with_stats], merges list nothing unless [Info::first_parent] compares them with their first parent
+    /// ## Example
+    /// ```
+    ///  # use commit_info::Info;
+    ///  # use std::env;
+    ///  # fn main() -> anyhow::Result<()> {
+    ///  # let mut path = env::current_dir().unwrap();
+    ///  # path.push("test_project");
+    ///  # let dir = path.to_string_lossy().to_string();
+    ///  // let dir = "/path/to/repo"; <- Point to the location of t=your repo
+    ///  let info = Info::new(&dir).with_files(true).commit_info()?;
+    ///  for commit in info.commits.unwrap_or_default() {
+    ///      for file in commit.files.unwrap_or_default() {
+    ///          println!("{:?} {}", file.change, file.path);
+    ///      }
+    ///  }
+    ///  # Ok(())
+    ///  # }
+    /// ```
+    pub fn with_files(mut self, enabled: bool) -> Info {
+        self.files = enabled;
+        self
+    }
+
+    /// Sets whether the signature of each commit is checked into [Commit::signature] and [Commit::signed_by]. Off by default,
+    /// since git runs gpg or ssh to verify every signed commit it lists. [Info::verify_commits] always checks them
+    /// ## Example
+    /// ```
+    ///  # use commit_info::Info;
+    ///  # use std::env;
+    ///  # fn main() -> anyhow::Result<()> {
+    ///  # let mut path = env::current_dir().unwrap();
+    ///  # path.push("test_project");
+    ///  # let dir = path.to_string_lossy().to_string();
+    ///  // let dir = "/path/to/repo"; <- Point to the location of t=your repo
+    ///  let info = Info::new(&dir).with_signatures(true).commit_info()?;
+    ///  for commit in info.commits.unwrap_or_default() {
+    ///      println!("{:?}: {:?}", commit.commit_message, commit.signature);
+    ///  }
+    ///  # Ok(())
+    ///  # }
+    /// ```
+    pub fn with_signatures(mut self, enabled: bool) -> Info {
+        self.signatures = enabled;
+        self
+    }
+
+    /// Bounds how long [Info::commit_info] and [Info::status_info] may take. When the time is up the git command
+    /// still running is killed and [CommitInfoError::Timeout] is returned. There is no limit by default.
+    /// The async versions of the ```tokio``` feature are bounded the same way
+    /// Only the git commands run by [GitCli] are stopped, a custom [GitBackend] has to bound its own work
+    /// ## Example
+    /// ```
+    ///  # use commit_info::Info;
+    ///  # use std::env;
+    ///  # use std::time::Duration;
+    ///  # fn main() -> anyhow::Result<()> {
+    ///  # let mut path = env::current_dir().unwrap();
+    ///  # path.push("test_project");
+    ///  # let dir = path.to_string_lossy().to_string();
+    ///  // let dir = "/path/to/repo"; <- Point to the location of t=your repo
+    ///  let info = Info::new(&dir).with_timeout(Duration::from_secs(5)).commit_info()?;
+    ///  println!("{:#?}", info.commits);
+    ///  # Ok(())
+    ///  # }
+    /// ```
+    pub fn with_timeout(mut self, timeout: Duration) -> Info {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Lets [Info::commit_info] and [Info::status_info] be stopped from another thread by cancelling `token`.
+    /// The git command still running is then killed and [CommitInfoError::Cancelled] is returned.
+    /// The async versions of the ```tokio``` feature can be stopped the same way
+    /// ## Example
+    /// ```
+    ///  # use commit_info::{CancellationToken, CommitInfoError, Info};
+    ///  # use std::env;
+    ///  # let mut path = env::current_dir().unwrap();
+    ///  # path.push("test_project");
+    ///  # let dir = path.to_string_lossy().to_string();
+    ///  // let dir = "/path/to/repo"; <- Point to the location of t=your repo
+    ///  let token = CancellationToken::new();
+    ///  let info = Info::new(&dir).with_cancellation(token.clone());
+    ///  // e.g. when the user navigates away
+    ///  token.cancel();
+    ///  assert!(matches!(info.status_info(), Err(CommitInfoError::Cancelled)));
+    /// ```
+    pub fn with_cancellation(mut self, token: CancellationToken) -> Info {
+        self.cancellation = Some(token);
+        self
+    }
+
+    /// Sets the ```git``` binary to run, e.g. one under a toolchain prefix that isn't on PATH.
+    /// Without it the ```COMMIT_INFO_GIT_BIN``` environment variable is used if set, and ```git``` from PATH otherwise.
+    /// Every git command spawned for this Info uses it
+    /// ## Example
+    /// ```
+    ///  # use commit_info::Info;
+    ///  # use std::env;
+    ///  # fn main() -> anyhow::Result<()> {
+    ///  # let mut path = env::current_dir().unwrap();
+    ///  # path.push("test_project");
+    ///  # let dir = path.to_string_lossy().to_string();
+    ///  # let git = "git";
+    ///  // let git = "/opt/toolchain/bin/git";
+    ///  let info = Info::new(&dir).with_git_binary(git).status_info()?;
+    ///  println!("{:#?}", info.status);
+    ///  # Ok(())
+    ///  # }
+    /// ```
+    pub fn with_git_binary(mut self, path: impl Into<PathBuf>) -> Info {
+        self.git_binary = Some(path.into());
+        self
+    }
+
+    /// Sets arguments passed to git before the subcommand of every git command spawned for this Info,
+    /// such as ```-c core.quotepath=off```. They replace any set before
+    /// ## Example
+    /// ```
+    ///  # use commit_info::Info;
+    ///  # use std::env;
+    ///  # fn main() -> anyhow::Result<()> {
+    ///  # let mut path = env::current_dir().unwrap();
+    ///  # path.push("test_project");
+    ///  # let dir = path.to_string_lossy().to_string();
+    ///  // let dir = "/path/to/repo"; <- Point to the location of t=your repo
+    ///  let info = Info::new(&dir)
+    ///      .with_git_args(["-c", "core.quotepath=off"])
+    ///      .status_info()?;
+    ///  println!("{:#?}", info.status);
+    ///  # Ok(())
+    ///  # }
+    /// ```
+    pub fn with_git_args<I, S>(mut self, args: I) -> Info
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.git_args = args.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Sets whether the git commands spawned for this Info inherit ```GIT_DIR```, ```GIT_WORK_TREE```, ```GIT_INDEX_FILE```
+    /// and the other variables that point git at a repo. They are cleared by default, so running inside a git hook,
+    /// where git sets them, still reads the repo this Info was made for. Pass true to keep them
+    /// ## Example
+    /// ```
+    ///  # use commit_info::Info;
+    ///  # use std::env;
+    ///  # fn main() -> anyhow::Result<()> {
+    ///  # let mut path = env::current_dir().unwrap();
+    ///  # path.push("test_project");
+    ///  # let dir = path.to_string_lossy().to_string();
+    ///  // let dir = "/path/to/repo"; <- Point to the location of t=your repo
+    ///  let info = Info::new(&dir).with_inherited_git_env(true).status_info()?;
+    ///  println!("{:#?}", info.status);
+    ///  # Ok(())
+    ///  # }
+    /// ```
+    pub fn with_inherited_git_env(mut self, inherit: bool) -> Info {
+        self.inherit_git_env = inherit;
+        self
+    }
+
+    /// Keeps what [Info::commit_info], [Info::commit_info_for] and [Info::status_info_for] gather for `ttl`,
+    /// so calling them again within it returns the same data without running git.
+    /// The cache is shared by the clones of this Info and only reused for calls with the same options.
+    /// Use [Info::refresh] to drop it early
+    /// ## Example
+    /// ```
+    ///  # use commit_info::Info;
+    ///  # use std::{env, time::Duration};
+    ///  # fn main() -> anyhow::Result<()> {
+    ///  # let mut path = env::current_dir().unwrap();
+    ///  # path.push("test_project");
+    ///  # let dir = path.to_string_lossy().to_string();
+    ///  // let dir = "/path/to/repo"; <- Point to the location of t=your repo
+    ///  let info = Info::new(&dir).with_cache_ttl(Duration::from_secs(5));
+    ///  let status = info.status_info()?.status;
+    ///  // read from the cache
+    ///  assert_eq!(format!("{:?}", status), format!("{:?}", info.status_info()?.status));
+    ///  # Ok(())
+    ///  # }
+    /// ```
+    pub fn with_cache_ttl(mut self, ttl: Duration) -> Info {
+        self.cache = Some(ResultCache {
+            ttl,
+            entries: Arc::default(),
+        });
+        self
+    }
+
+    /// Sets how [Info::commit_info] and [Info::status_info] read the repo, either one of the built in [Backend]s
+    /// or your own [GitBackend]. [Backend::Auto] is used by default
+    /// ## Example
+    /// ```
+    ///  # use commit_info::{GitCli, Info};
+    ///  # use std::env;
+    ///  # fn main() -> anyhow::Result<()> {
+    ///  # let mut path = env::current_dir().unwrap();
+    ///  # path.push("test_project");
+    ///  # let dir = path.to_string_lossy().to_string();
+    ///  // let dir = "/path/to/repo"; <- Point to the location of t=your repo
+    ///  let info = Info::new(&dir).with_backend(GitCli).commit_info()?;
+    ///  println!("{:#?}", info.commits);
+    ///  # Ok(())
+    ///  # }
+    /// ```
+    pub fn with_backend(mut self, backend: impl GitBackend + 'static) -> Info {
+        self.backend = Arc::new(backend);
+        self
+    }
+
+    /// Sets the timezone [Info::to_json] renders commit and author dates in, e.g. ```2022-03-01 13:30:00 +03:00```.
+    /// By default each date is rendered in RFC 3339 with the offset it was recorded with.
+    /// Accepts a [chrono::FixedOffset] or, with the ```tz``` feature, a ```chrono_tz::Tz```.
+    /// Only the rendering changes, the dates on [Commit] keep their own offset
+    /// ## Example
+    /// ```
+    ///  # use commit_info::Info;
+    ///  # use std::env;
+    ///  # fn main() -> anyhow::Result<()> {
+    ///  # let mut path = env::current_dir().unwrap();
+    ///  # path.push("test_project");
+    ///  # let dir = path.to_string_lossy().to_string();
+    ///  // let dir = "/path/to/repo"; <- Point to the location of t=your repo
+    ///  let nairobi = chrono::FixedOffset::east_opt(3 * 3600).unwrap();
+    ///  let info = Info::new(&dir).with_display_timezone(nairobi).commit_info()?;
+    ///  println!("{}", info.to_json_pretty());
+    ///  # Ok(())
+    ///  # }
+    /// ```
+    #[cfg(feature = "dates")]
+    pub fn with_display_timezone(mut self, tz: impl Into<DisplayTimezone>) -> Info {
+        self.display_timezone = Some(tz.into());
+        self
+    }
+
+    /// Sets the ```chrono``` strftime format [Info::to_json] renders commit and author dates in, e.g. ```%d/%m/%Y %H:%M```.
+    /// Dates are rendered in RFC 3339 by default. Combined with [Info::with_display_timezone] the dates are converted
+    /// to that timezone first. A format chrono can't render, such as one with an unknown ```%``` specifier,
+    /// falls back to RFC 3339
+    /// ## Example
+    /// ```
+    ///  # use commit_info::Info;
+    ///  # use std::env;
+    ///  # fn main() -> anyhow::Result<()> {
+    ///  # let mut path = env::current_dir().unwrap();
+    ///  # path.push("test_project");
+    ///  # let dir = path.to_string_lossy().to_string();
+    ///  // let dir = "/path/to/repo"; <- Point to the location of t=your repo
+    ///  let info = Info::new(&dir).with_date_format("%Y-%m-%d").commit_info()?;
+    ///  println!("{}", info.to_json_pretty());
+    ///  # Ok(())
+    ///  # }
+    /// ```
+    #[cfg(feature = "dates")]
+    pub fn with_date_format(mut self, format: impl Into<String>) -> Info {
+        self.date_format = Some(format.into());
+        self
+    }
+
+    /// Get information of the latest commits, 5 unless set with [Info::with_commit_limit].
+    /// This Method returns Info in its result.
+    /// If there are no commits, the returned value is None.
+    /// The commits are read from the ref set with [Info::with_ref], or else the remote's default branch.
+    /// When ```refs/remotes/origin/HEAD``` isn't set, e.g. after ```git remote add```, the ```git``` backend runs
+    /// ```git remote show origin``` to find the default branch, which contacts the remote over the network.
+    /// It never prompts for credentials: a remote that needs them is skipped as if it had no default branch.
+    /// Fails with [CommitInfoError::NotARepository] unless the directory is a repo, and with
+    /// [CommitInfoError::CommandFailed] when git can't read the history
+    /// ## Example
+    /// ```
+    ///  # use commit_info::Info;
+    ///  # use std::env;
+    ///  # fn main() -> anyhow::Result<()> {
+    ///  # let mut path = env::current_dir().unwrap();
+    ///  # path.push("test_project");
+    ///  # let dir = path.to_string_lossy().to_string();
+    ///  // let dir = "/path/to/repo"; <- Point to the location of t=your repo
+    ///  let commits_info = Info::new(&dir).commit_info()?;
+    ///  println!("{:#?}", commits_info);
+    ///  # Ok(())
+    ///  # }
+    /// ```
+    pub fn commit_info(&self) -> Result<Info, CommitInfoError> {
+        if let Some(reference) = &self.reference {
+            return self.commit_info_for(reference);
+        }
+        self.cached(&["commits"], Info::copy_commits, || {
+            self.limited(|| {
+                self.backend.check()?;
+
+                let mut git_info = self.clone();
+                git_info.require_repo()?;
+
+                {
+                    let (backend, dir) = (&git_info.backend, git_info.dir.as_path());
+
+                    git_info.default_branch = backend.default_branch(dir);
+                    git_info.head_hash = backend.head_hash(dir);
+                    git_info.head_short_hash = backend.head_short_hash(dir);
+
+                    // prefer the remote's default branch, then any remote branch
+                    let branch = match &git_info.default_branch {
+                        Some(default_branch) => Some(format!("origin/{}", default_branch)),
+                        None => backend.remote_branch(dir),
+                    }
+                    // bare clones know the remote's default branch without tracking it
+                    .filter(|branch| backend.verify_ref(dir, branch).is_ok());
+
+                    match branch {
+                        Some(branch) => {
+                            git_info.commits = git_info.top_commits(&branch)?;
+                            git_info.branch = Some(branch);
+                        }
+                        None => {
+                            // no remote, so read the local history instead
+                            git_info.branch = git_info.backend.head_name(dir);
+                            // a fresh repo has no HEAD to read yet
+                            if git_info.head_hash.is_some() {
+                                git_info.commits = git_info.top_commits("HEAD")?;
+                            }
+                        }
+                    }
+                }
+                Ok(git_info)
+            })
+        })
+    }
+
+    /// Same as [Info::commit_info], but lists up to `limit` of the latest commits, or all of them when it is None.
+    /// Only the commits listed are read from git, so small limits stay fast on huge histories
+    /// ## Example
+    /// ```
+    ///  # use commit_info::Info;
+    ///  # use std::env;
+    ///  # fn main() -> anyhow::Result<()> {
+    ///  # let mut path = env::current_dir().unwrap();
+    ///  # path.push("test_project");
+    ///  # let dir = path.to_string_lossy().to_string();
+    ///  // let dir = "/path/to/repo"; <- Point to the location of t=your repo
+    ///  let everything = Info::new(&dir).commit_info_n(None)?;
+    ///  println!("{:#?}", everything.commits);
+    ///  # Ok(())
+    ///  # }
+    /// ```
+    pub fn commit_info_n(&self, limit: Option<usize>) -> Result<Info, CommitInfoError> {
+        let mut info = self.clone();
+        info.commit_limit = limit;
+        info.commit_info()
+    }
+
+    /// Same as [Info::commit_info] but reads the commits from the given branch, tag or any other ref
+    /// instead of the auto-detected branch. An error is returned if the ref does not exist,
+    /// [CommitInfoError::NoCommits] if the repo has no commits at all
+    /// ## Example
+    /// ```
+    ///  # use commit_info::Info;
+    ///  # use std::env;
+    ///  # fn main() -> anyhow::Result<()> {
+    ///  # let mut path = env::current_dir().unwrap();
+    ///  # path.push("test_project");
+    ///  # let dir = path.to_string_lossy().to_string();
+    ///  // let dir = "/path/to/repo"; <- Point to the location of t=your repo
+    ///  let release_info = Info::new(&dir).commit_info_for("origin/release");
+    ///  println!("{:#?}", release_info);
+    ///  # Ok(())
+    ///  # }
+    /// ```
+    pub fn commit_info_for(&self, reference: &str) -> Result<Info, CommitInfoError> {
+        self.cached(&["commits", reference], Info::copy_commits, || {
+            self.limited(|| {
+                self.backend.check()?;
+
+                let mut git_info = self.clone();
+                git_info.require_repo()?;
+
+                if let Err(e) = git_info.backend.verify_ref(&git_info.dir, reference) {
+                    return Err(match git_info.backend.head_hash(&git_info.dir) {
+                        None => CommitInfoError::NoCommits,
+                        Some(_) => e.into(),
+                    });
+                }
+                git_info.branch = Some(reference.into());
+                git_info.commits = git_info.top_commits(reference)?;
+                Ok(git_info)
+            })
+        })
+    }
+
+    /// This method returns status information for the repo.
+    /// Bare repos have no working tree, so their [Status] is left empty.
+    /// Fails with [CommitInfoError::NotARepository] unless the directory is a repo, and with
+    /// [CommitInfoError::CommandFailed] when ```git status``` fails
+    /// ## Example
+    /// ```
+    ///  # use commit_info::Info;
+    ///  # use std::env;
+    ///  # fn main() -> anyhow::Result<()> {
+    ///  # let mut path = env::current_dir().unwrap();
+    ///  # path.push("test_project");
+    ///  # let dir = path.to_string_lossy().to_string();
+    ///  // let dir = "/path/to/repo"; <- Point to the location of t=your repo
+    ///  let status_info = Info::new(&dir).status_info()?;
+    ///  println!("{:#?}", status_info);
+    ///  # Ok(())
+    ///  # }
+    /// ```
+    pub fn status_info(&self) -> Result<Info, CommitInfoError> {
+        self.status_info_for(&[])
+    }
+
+    /// Same as [Info::status_info] but only looks at the paths matching `pathspecs`, relative to the repo root,
+    /// so changes elsewhere don't make the repo dirty. An empty slice covers the whole repo
+    /// ## Example
+    /// ```
+    ///  # use commit_info::Info;
+    ///  # use std::env;
+    ///  # fn main() -> anyhow::Result<()> {
+    ///  # let mut path = env::current_dir().unwrap();
+    ///  # path.push("test_project");
+    ///  # let dir = path.to_string_lossy().to_string();
+    ///  // let dir = "/path/to/repo"; <- Point to the location of t=your repo
+    ///  let docs = Info::new(&dir).status_info_for(&["docs"])?;
+    ///  println!("{:#?}", docs.status);
+    ///  # Ok(())
+    ///  # }
+    /// ```
+    pub fn status_info_for(&self, pathspecs: &[&str]) -> Result<Info, CommitInfoError> {
+        let key = [&["status"], pathspecs].concat();
+        self.cached(&key, Info::copy_status, || {
+            self.limited(|| {
+                self.backend.check()?;
+
+                let mut git_info = self.clone();
+                git_info.require_repo()?;
+                // a bare repo has no working tree to compare against
+                git_info.status = Some(if git_info.is_bare {
+                    Status::default()
+                } else {
+                    git_info.backend.status(&git_info.dir, pathspecs)?
+                });
+
+                Ok(git_info)
+            })
+        })
+    }
+
+    /// Streams the commits reachable from HEAD, or the reference set with [Info::with_ref], newest first,
+    /// without loading the whole history. `git log` is read one commit at a time and stopped as soon as the iterator
+    /// is dropped, so taking the first few commits of a huge repo is cheap. The date range and author set on this Info
+    /// apply, the commit limit doesn't
+    /// ## Example
+    /// ```
+    ///  # use commit_info::Info;
+    ///  # use std::env;
+    ///  # fn main() -> anyhow::Result<()> {
+    ///  # let mut path = env::current_dir().unwrap();
+    ///  # path.push("test_project");
+    ///  # let dir = path.to_string_lossy().to_string();
+    ///  // let dir = "/path/to/repo"; <- Point to the location of t=your repo
+    ///  for commit in Info::new(&dir).commits_iter()?.take(3) {
+    ///      println!("{:?}", commit?.commit_message);
+    ///  }
+    ///  # Ok(())
+    ///  # }
+    /// ```
+    pub fn commits_iter(&self) -> Result<impl Iterator<Item = Result<Commit>>> {
+        self.require_git()?;
+        self.require_repo()?;
+
+        if !self.has_commits() {
+            return Ok(CommitIter::empty());
+        }
+        let reference = self.reference.as_deref().unwrap_or("HEAD");
+        self.verify_ref(reference)?;
+        self.stream_log(&[reference])
+    }
+
+    /// Returns when the history of HEAD started and when it was last committed to.
+    /// Empty repos and directories that are not git repos have no dates.
+    /// In a shallow clone the first commit date is that of the oldest fetched commit, see [Info::is_shallow]
+    /// ## Example
+    /// ```
+    ///  # use commit_info::Info;
+    ///  # use std::env;
+    ///  # fn main() -> anyhow::Result<()> {
+    ///  # let mut path = env::current_dir().unwrap();
+    ///  # path.push("test_project");
+    ///  # let dir = path.to_string_lossy().to_string();
+    ///  // let dir = "/path/to/repo"; <- Point to the location of t=your repo
+    ///  let activity = Info::new(&dir).activity()?;
+    ///  if activity.days_since_last_commit.unwrap_or(0) > 365 {
+    ///      println!("this repo looks stale");
+    ///  }
+    ///  # Ok(())
+    ///  # }
+    /// ```
+    #[cfg(feature = "dates")]
+    pub fn activity(&self) -> Result<Activity> {
+        self.require_git()?;
+        let mut activity = Activity::default();
+        self.require_repo()?;
+        if !self.has_commits() {
+            return Ok(activity);
+        }
+
+        let last = self.git(&["log", "-1", "--format=%ci", "HEAD", "--"])?;
+        // a history can have several roots, e.g. after merging unrelated histories
+        let roots = self.git(&["log", "--max-parents=0", "--format=%ci", "HEAD", "--"])?;
+
+        let utc = |date: CommitDate| date.with_timezone(&Utc);
+        activity.first_commit_date = roots.lines().filter_map(parse_date).min().map(utc);
+        activity.last_commit_date = parse_date(&last).map(utc);
+        activity.days_since_last_commit = activity
+            .last_commit_date
+            .map(|last| (Utc::now() - last).num_days());
+
+        Ok(activity)
+    }
+
+    /// Sums the lines added and removed by the commits in `range`, e.g. ```v1.0.0..HEAD```.
+    /// Binary files count towards `files_touched` but not towards insertions or deletions
+    /// ## Example
+    /// ```
+    ///  # use commit_info::Info;
+    ///  # use std::env;
+    ///  # fn main() -> anyhow::Result<()> {
+    ///  # let mut path = env::current_dir().unwrap();
+    ///  # path.push("test_project");
+    ///  # let dir = path.to_string_lossy().to_string();
+    ///  // let dir = "/path/to/repo"; <- Point to the location of t=your repo
+    ///  let churn = Info::new(&dir).churn("HEAD~10..HEAD");
+    ///  println!("{:#?}", churn);
+    ///  # Ok(())
+    ///  # }
+    /// ```
+    pub fn churn(&self, range: &str) -> Result<Churn> {
+        self.require_git()?;
+
+        let mut churn = Churn::default();
+        self.require_repo()?;
+        if !self.has_commits() {
+            return Ok(churn);
+        }
+
+        let options = self.history_options();
+        let mut args = vec!["log", "--numstat", "--format=%x00%H"];
+        args.extend(options.iter().map(String::as_str));
+        args.extend([range, "--"]);
+        let output = self.git(&args)?;
+
+        let mut files: Vec<&str> = vec![];
+        for line in output.lines() {
+            // every commit starts with its NUL prefixed hash, followed by a line per changed file
+            if line.starts_with('\0') {
+                churn.commits += 1;
+                continue;
+            }
+
+            let mut fields = line.splitn(3, '\t');
+            let (added, removed, path) = match (fields.next(), fields.next(), fields.next()) {
+                (Some(added), Some(removed), Some(path)) => (added, removed, path),
+                _ => continue,
+            };
+            // binary files are reported as "-"
+            churn.insertions += added.parse::<u64>().unwrap_or(0);
+            churn.deletions += removed.parse::<u64>().unwrap_or(0);
+            files.push(path);
+        }
+
+        files.sort_unstable();
+        files.dedup();
+        churn.files_touched = files.len() as u32;
+
+        Ok(churn)
+    }
+
+    /// Returns up to `limit` of the most recent HEAD movements, newest first.
+    /// Unlike the commit history this includes checkouts, resets and amends.
+    /// Repos without a reflog return an empty Vec
+    /// ## Example
+    /// ```
+    ///  # use commit_info::Info;
+    ///  # use std::env;
+    ///  # fn main() -> anyhow::Result<()> {
+    ///  # let mut path = env::current_dir().unwrap();
+    ///  # path.push("test_project");
+    ///  # let dir = path.to_string_lossy().to_string();
+    ///  // let dir = "/path/to/repo"; <- Point to the location of t=your repo
+    ///  for entry in Info::new(&dir).reflog(10)? {
+    ///      println!("{} {}: {}", entry.new_hash, entry.action, entry.message);
+    ///  }
+    ///  # Ok(())
+    ///  # }
+    /// ```
+    pub fn reflog(&self, limit: usize) -> Result<Vec<ReflogEntry>> {
+        self.require_git()?;
+        self.require_repo()?;
+
+        if limit == 0 || !self.has_commits() {
+            return Ok(vec![]);
+        }
+
+        // one extra entry tells us where HEAD was before the oldest returned movement
+        let count = format!("--max-count={}", limit + 1);
+        let output = self.git(&["log", "-g", &count, "--format=%H%x00%gs%x00%ci"])?;
+
+        let records: Vec<Vec<&str>> = output
+            .lines()
+            .map(|line| line.split('\0').collect::<Vec<&str>>())
+            .filter(|fields| fields.len() == 3)
+            .collect();
+
+        let mut entries = vec![];
+        for (i, fields) in records.iter().enumerate().take(limit) {
+            let old_hash = match records.get(i + 1) {
+                Some(older) => older[0].to_string(),
+                None => "0".repeat(fields[0].len()),
+            };
+            let (action, message) = fields[1].split_once(": ").unwrap_or((fields[1], ""));
+            let date = match parse_date(fields[2]) {
+                Some(date) => date,
+                None => continue,
+            };
+            #[cfg(feature = "dates")]
+            let date = date.with_timezone(&Utc);
+
+            entries.push(ReflogEntry {
+                old_hash,
+                new_hash: fields[0].into(),
+                action: action.into(),
+                message: message.into(),
+                date,
+            });
+        }
+
+        Ok(entries)
+    }
+
+    /// Gathers both the status and the commits of the repo.
+    /// This returns the same as ```status_info()?.commit_info()?``` but runs the git commands concurrently,
+    /// which is safe since none of them modify the repo
+    /// ## Example
+    /// ```
+    ///  # use commit_info::Info;
+    ///  # use std::env;
+    ///  # fn main() -> anyhow::Result<()> {
+    ///  # let mut path = env::current_dir().unwrap();
+    ///  # path.push("test_project");
+    ///  # let dir = path.to_string_lossy().to_string();
+    ///  // let dir = "/path/to/repo"; <- Point to the location of t=your repo
+    ///  let info = Info::new(&dir).gather()?;
+    ///  println!("{:#?}", info);
+    ///  # Ok(())
+    ///  # }
+    /// ```
+    pub fn gather(&self) -> Result<Info, CommitInfoError> {
+        let (status_info, commit_info) = thread::scope(|scope| {
+            let status_info = scope.spawn(|| self.status_info());
+            let commit_info = self.commit_info();
+            (join(status_info), commit_info)
+        });
+
+        let mut git_info = commit_info?;
+        git_info.status = status_info?.status;
+        git_info.shallow = git_info.is_shallow().ok();
+
+        Ok(git_info)
+    }
+
+    /// Drops the results cached by [Info::with_cache_ttl], for this Info and its clones,
+    /// so the next call to [Info::commit_info] or [Info::status_info] runs git again.
+    /// Does nothing without a cache
+    /// ## Example
+    /// ```
+    ///  # use commit_info::Info;
+    ///  # use std::{env, time::Duration};
+    ///  # fn main() -> anyhow::Result<()> {
+    ///  # let mut path = env::current_dir().unwrap();
+    ///  # path.push("test_project");
+    ///  # let dir = path.to_string_lossy().to_string();
+    ///  // let dir = "/path/to/repo"; <- Point to the location of t=your repo
+    ///  let info = Info::new(&dir).with_cache_ttl(Duration::from_secs(60));
+    ///  info.status_info()?;
+    ///  // something changed the repo
+    ///  info.refresh();
+    ///  println!("{:#?}", info.status_info()?.status);
+    ///  # Ok(())
+    ///  # }
+    /// ```
+    pub fn refresh(&self) {
+        if let Some(cache) = &self.cache {
+            cache.clear();
+        }
+    }
+
+    /// Returns the state of the ```git bisect``` session in progress, or None when the repo isn't being bisected
+    /// ## Example
+    /// ```
+    ///  # use commit_info::Info;
+    ///  # use std::env;
+    ///  # fn main() -> anyhow::Result<()> {
+    ///  # let mut path = env::current_dir().unwrap();
+    ///  # path.push("test_project");
+    ///  # let dir = path.to_string_lossy().to_string();
+    ///  // let dir = "/path/to/repo"; <- Point to the location of t=your repo
+    ///  if let Some(bisect) = Info::new(&dir).bisect_status()? {
+    ///      println!("testing {}, about {:?} steps left", bisect.current, bisect.remaining_estimate);
+    ///  }
+    ///  # Ok(())
+    ///  # }
+    /// ```
+    pub fn bisect_status(&self) -> Result<Option<BisectStatus>> {
+        self.require_git()?;
+        self.require_repo()?;
+
+        let git_dir = PathBuf::from(self.git(&["rev-parse", "--absolute-git-dir"])?);
+        if !git_dir.join("BISECT_START").exists() {
+            return Ok(None);
+        }
+        let current = self.git(&["rev-parse", "HEAD"])?;
+
+        // the terms default to bad and good but can be renamed with `git bisect start --term-new`
+        let terms = std::fs::read_to_string(git_dir.join("BISECT_TERMS")).unwrap_or_default();
+        let mut terms = terms.lines();
+        let bad = format!("refs/bisect/{}", terms.next().unwrap_or("bad"));
+        let good = format!("refs/bisect/{}-*", terms.next().unwrap_or("good"));
+
+        let good: Vec<String> = self
+            .git(&["for-each-ref", "--format=%(refname)", &good])
+            .unwrap_or_default()
+            .lines()
+            .map(String::from)
+            .collect();
+
+        let mut remaining_estimate = None;
+        if !good.is_empty() {
+            let mut args = vec!["rev-list", "--bisect-vars", &bad, "--not"];
+            args.extend(good.iter().map(String::as_str));
+            if let Ok(vars) = self.git(&args) {
+                remaining_estimate = vars
+                    .lines()
+                    .find_map(|line| line.strip_prefix("bisect_steps="))
+                    .and_then(|steps| steps.parse().ok());
+            }
+        }
+
+        Ok(Some(BisectStatus {
+            current,
+            remaining_estimate,
+        }))
+    }
+
+    /// Tells whether the repo is a shallow clone, e.g. one made by CI with ```fetch-depth: 1```.
+    /// The history of a shallow clone is cut off, so anything computed from it, such as
+    /// [Info::activity], [Info::contributors] or [Info::churn], only covers the fetched commits
+    /// ## Example
+    /// ```
+    ///  # use commit_info::Info;
+    ///  # use std::env;
+    ///  # fn main() -> anyhow::Result<()> {
+    ///  # let mut path = env::current_dir().unwrap();
+    ///  # path.push("test_project");
+    ///  # let dir = path.to_string_lossy().to_string();
+    ///  // let dir = "/path/to/repo"; <- Point to the location of t=your repo
+    ///  if Info::new(&dir).is_shallow()? {
+    ///      println!("warning: the history is incomplete");
+    ///  }
+    ///  # Ok(())
+    ///  # }
+    /// ```
+    pub fn is_shallow(&self) -> Result<bool> {
+        self.require_git()?;
+        self.require_repo()?;
+        let shallow = self.git(&["rev-parse", "--is-shallow-repository"])?;
+        Ok(shallow.trim() == "true")
+    }
+
+    /// Returns everyone who authored a commit reachable from HEAD, sorted by descending commit count.
+    /// Authors are merged by their lowercased email, so name or casing changes do not split a contributor.
+    /// Empty repos return an empty Vec.
+    /// ## Example
+    /// ```
+    ///  # use commit_info::Info;
+    ///  # use std::env;
+    ///  # fn main() -> anyhow::Result<()> {
+    ///  # let mut path = env::current_dir().unwrap();
+    ///  # path.push("test_project");
+    ///  # let dir = path.to_string_lossy().to_string();
+    ///  // let dir = "/path/to/repo"; <- Point to the location of t=your repo
+    ///  let contributors = Info::new(&dir).contributors()?;
+    ///  println!("{:#?}", contributors);
+    ///  # Ok(())
+    ///  # }
+    /// ```
+    pub fn contributors(&self) -> Result<Vec<Contributor>> {
+        self.require_git()?;
+
+        let mut contributors: Vec<Contributor> = vec![];
+
+        self.require_repo()?;
+        if !self.has_commits() {
+            return Ok(contributors);
+        }
+
+        let format = format!("--format={}", self.mailmap_format("%aN%x09%aE"));
+        let authors = self.git(&["log", &format, "HEAD"])?;
+
+        // index of each lowercased email in contributors
+        let mut seen: HashMap<String, usize> = HashMap::new();
+
+        for line in authors.lines() {
+            let (name, email) = match line.split_once('\t') {
+                Some(parts) => parts,
+                None => continue,
+            };
+
+            match seen.get(&email.to_lowercase()) {
+                Some(&i) => contributors[i].commit_count += 1,
+                None => {
+                    seen.insert(email.to_lowercase(), contributors.len());
+                    contributors.push(Contributor {
+                        name: name.into(),
+                        email: email.into(),
+                        commit_count: 1,
+                    });
+                }
+            }
+        }
+
+        contributors.sort_by(|a, b| {
+            b.commit_count
+                .cmp(&a.commit_count)
+                .then_with(|| a.name.cmp(&b.name))
+        });
+
+        Ok(contributors)
+    }
+
+    /// Returns the commit HEAD points to, read with ```git log -1```, or None when there are no commits yet.
+    /// Unlike [Info::commit_info] it doesn't look for branches or read any other commits, which is all that
+    /// e.g. a build stamp needs. The history filters, such as [Info::with_merges] or [Info::with_author], don't apply
+    /// ## Example
+    /// ```
+    ///  # use commit_info::Info;
+    ///  # use std::env;
+    ///  # fn main() -> anyhow::Result<()> {
+    ///  # let mut path = env::current_dir().unwrap();
+    ///  # path.push("test_project");
+    ///  # let dir = path.to_string_lossy().to_string();
+    ///  // let dir = "/path/to/repo"; <- Point to the location of t=your repo
+    ///  if let Some(head) = Info::new(&dir).head_commit()? {
+    ///      println!("built from {:?}: {:?}", head.short_hash, head.commit_subject);
+    ///  }
+    ///  # Ok(())
+    ///  # }
+    /// ```
+    pub fn head_commit(&self) -> Result<Option<Commit>> {
+        self.require_git()?;
+        self.require_repo()?;
+
+        if !self.has_commits() {
+            return Ok(None);
+        }
+
+        let query = LogQuery {
+            revision: "HEAD".into(),
+            max_count: Some(1),
+            include_merges: true,
+            use_mailmap: self.use_mailmap,
+            ..LogQuery::default()
+        };
+        Ok(self.log(&query)?.into_iter().next())
+    }
+
+    /// Returns a page of the commits reachable from HEAD, newest first.
+    /// `skip` commits are skipped and at most `take` are returned. The page also tells whether more commits follow it
+    /// and, when it can, how many commits there are in total, e.g. to show the number of pages
+    /// ## Example
+    /// ```
+    ///  # use commit_info::Info;
+    ///  # use std::env;
+    ///  # fn main() -> anyhow::Result<()> {
+    ///  # let mut path = env::current_dir().unwrap();
+    ///  # path.push("test_project");
+    ///  # let dir = path.to_string_lossy().to_string();
+    ///  // let dir = "/path/to/repo"; <- Point to the location of t=your repo
+    ///  let second_page = Info::new(&dir).commits_page(20, 20)?;
+    ///  println!("{:#?}", second_page.commits);
+    ///  if second_page.has_more {
+    ///      println!("{} commits in all", second_page.total_hint.unwrap_or_default());
+    ///  }
+    ///  # Ok(())
+    ///  # }
+    /// ```
+    pub fn commits_page(&self, skip: usize, take: usize) -> Result<CommitPage> {
+        self.require_git()?;
+        self.require_repo()?;
+
+        if !self.has_commits() {
+            return Ok(CommitPage {
+                total_hint: Some(0),
+                ..CommitPage::default()
+            });
+        }
+
+        // one commit more than asked for shows whether another page follows
+        let mut query = self.log_query("HEAD");
+        query.skip = skip;
+        query.max_count = Some(take.saturating_add(1));
+        let mut commits = self.log(&query)?;
+        let has_more = commits.len() > take;
+        commits.truncate(take);
+
+        // the end of the history is on this page, so its length already gives the total
+        let total_hint = if !has_more && (!commits.is_empty() || skip == 0) {
+            Some(skip + commits.len())
+        } else {
+            self.count_commits(&query).ok()
+        };
+
+        Ok(CommitPage {
+            commits,
+            has_more,
+            total_hint,
+        })
+    }
+
+    /// Returns the commits made after `since_ref` up to HEAD that changed anything matching `pathspecs`,
+    /// e.g. ```&["packages/foo/"]```. An empty Vec means nothing under those paths changed
+    /// ## Example
+    /// ```
+    ///  # use commit_info::Info;
+    ///  # use std::env;
+    ///  # fn main() -> anyhow::Result<()> {
+    ///  # let mut path = env::current_dir().unwrap();
+    ///  # path.push("test_project");
+    ///  # let dir = path.to_string_lossy().to_string();
+    ///  // let dir = "/path/to/repo"; <- Point to the location of t=your repo
+    ///  let changes = Info::new(&dir).commits_touching(&["src/"], "HEAD~1");
+    ///  println!("{:#?}", changes);
+    ///  # Ok(())
+    ///  # }
+    /// ```
+    pub fn commits_touching(&self, pathspecs: &[&str], since_ref: &str) -> Result<Vec<Commit>> {
+        self.require_git()?;
+        self.require_repo()?;
+
+        // every commit of the range, whatever the commit limit
+        let mut touching = self.clone().with_ref(format!("{}..HEAD", since_ref));
+        touching.commit_limit = None;
+        touching.commits_for_paths(pathspecs)
+    }
+
+    /// Returns every commit reachable from HEAD whose message matches `search`, newest first, like ```git log --grep```.
+    /// A plain string is searched for as an extended regular expression; use a [CommitSearch] to match it literally
+    /// or to ignore case. The subject and body are searched, and ```^``` and ```$``` match at the start and end of each line.
+    /// The other history options, such as [Info::with_author] or [Info::with_since], still apply
+    /// ## Example
+    /// ```
+    ///  # use commit_info::{CommitSearch, Info};
+    ///  # use std::env;
+    ///  # fn main() -> anyhow::Result<()> {
+    ///  # let mut path = env::current_dir().unwrap();
+    ///  # path.push("test_project");
+    ///  # let dir = path.to_string_lossy().to_string();
+    ///  // let dir = "/path/to/repo"; <- Point to the location of t=your repo
+    ///  let search = CommitSearch::new("cve-2024-").with_ignore_case(true);
+    ///  let fixes = Info::new(&dir).search_commits(search)?;
+    ///  println!("{:#?}", fixes);
+    ///  # Ok(())
+    ///  # }
+    /// ```
+    pub fn search_commits(&self, search: impl Into<CommitSearch>) -> Result<Vec<Commit>> {
+        self.require_git()?;
+        self.require_repo()?;
+
+        if !self.has_commits() {
+            return Ok(vec![]);
+        }
+
+        let mut query = self.log_query("HEAD");
+        query.grep = Some(search.into());
+        self.log(&query)
+    }
+
+    /// Counts the commits reachable from `reference`, like ```git rev-list --count```, without reading any of them.
+    /// The history options of this Info, such as [Info::with_merges] or [Info::with_since], apply.
+    /// Empty repos have no commits
+    /// ## Example
+    /// ```
+    ///  # use commit_info::Info;
+    ///  # use std::env;
+    ///  # fn main() -> anyhow::Result<()> {
+    ///  # let mut path = env::current_dir().unwrap();
+    ///  # path.push("test_project");
+    ///  # let dir = path.to_string_lossy().to_string();
+    ///  // let dir = "/path/to/repo"; <- Point to the location of t=your repo
+    ///  let total = Info::new(&dir).commit_count("HEAD")?;
+    ///  println!("{} commits", total);
+    ///  # Ok(())
+    ///  # }
+    /// ```
+    pub fn commit_count(&self, reference: &str) -> Result<usize> {
+        self.require_git()?;
+        self.require_repo()?;
+
+        if !self.has_commits() {
+            return Ok(0);
+        }
+        self.verify_ref(reference)?;
+
+        self.count_commits(&self.log_query(reference))
+    }
+
+    /// Counts the commits of every local branch, see [Info::commit_count], keyed by branch name
+    /// ## Example
+    /// ```
+    ///  # use commit_info::Info;
+    ///  # use std::env;
+    ///  # fn main() -> anyhow::Result<()> {
+    ///  # let mut path = env::current_dir().unwrap();
+    ///  # path.push("test_project");
+    ///  # let dir = path.to_string_lossy().to_string();
+    ///  // let dir = "/path/to/repo"; <- Point to the location of t=your repo
+    ///  for (branch, count) in Info::new(&dir).branch_commit_counts()? {
+    ///      println!("{}: {} commits", branch, count);
+    ///  }
+    ///  # Ok(())
+    ///  # }
+    /// ```
+    pub fn branch_commit_counts(&self) -> Result<BTreeMap<String, usize>> {
+        self.require_git()?;
+        self.require_repo()?;
+
+        let branches = self.git(&["for-each-ref", "--format=%(refname)", "refs/heads/"])?;
+        let mut counts = BTreeMap::new();
+        for branch in branches.lines() {
+            let count = self.count_commits(&self.log_query(branch))?;
+            let name = branch.strip_prefix("refs/heads/").unwrap_or(branch);
+            counts.insert(name.to_string(), count);
+        }
+        Ok(counts)
+    }
+
+    /// Returns the commits reachable from `to` but not from `from`, newest first, like ```git log from..to```,
+    /// e.g. everything merged into ```main``` since ```v1.4.0```. The history options of this Info, such as
+    /// [Info::with_merges] or [Info::with_author], apply, but the commit limit doesn't
+    /// ## Example
+    /// ```
+    ///  # use commit_info::Info;
+    ///  # use std::env;
+    ///  # fn main() -> anyhow::Result<()> {
+    ///  # let mut path = env::current_dir().unwrap();
+    ///  # path.push("test_project");
+    ///  # let dir = path.to_string_lossy().to_string();
+    ///  // let dir = "/path/to/repo"; <- Point to the location of t=your repo
+    ///  # let info = Info::new(&dir);
+    ///  # if info.commits_page(1, 1)?.commits.is_empty() { return Ok(()); }
+    ///  let unreleased = Info::new(&dir).commits_between("HEAD~1", "HEAD")?;
+    ///  println!("{:#?}", unreleased);
+    ///  # Ok(())
+    ///  # }
+    /// ```
+    pub fn commits_between(&self, from: &str, to: &str) -> Result<Vec<Commit>> {
+        self.require_git()?;
+        self.require_repo()?;
+
+        self.verify_ref(from)?;
+        self.verify_ref(to)?;
+
+        self.log(&self.log_query(&format!("{}..{}", from, to)))
+    }
+
+    /// Returns the latest commits that changed anything matching `pathspecs`, like ```git log -- <pathspecs>```,
+    /// e.g. ```&["crates/parser/"]``` for the history of one crate in a workspace. Paths are relative to the repo root.
+    /// The history of HEAD is read, or of the ref set with [Info::with_ref], and the limit and filters of this Info,
+    /// such as [Info::with_commit_limit], [Info::with_since] or [Info::with_author], apply
+    /// ## Example
+    /// ```
+    ///  # use commit_info::Info;
+    ///  # use std::env;
+    ///  # fn main() -> anyhow::Result<()> {
+    ///  # let mut path = env::current_dir().unwrap();
+    ///  # path.push("test_project");
+    ///  # let dir = path.to_string_lossy().to_string();
+    ///  // let dir = "/path/to/repo"; <- Point to the location of t=your repo
+    ///  let history = Info::new(&dir)
+    ///      .with_commit_limit(20)
+    ///      .commits_for_paths(&["crates/parser/", "Cargo.lock"])?;
+    ///  println!("{:#?}", history);
+    ///  # Ok(())
+    ///  # }
+    /// ```
+    pub fn commits_for_paths(&self, pathspecs: &[&str]) -> Result<Vec<Commit>> {
+        self.require_git()?;
+        self.require_repo()?;
+
+        if !self.has_commits() {
+            return Ok(vec![]);
+        }
+        let revision = self.reference.as_deref().unwrap_or("HEAD");
+        // both ends of a range such as `v1.0..HEAD` must exist
+        for reference in revision.split("..") {
+            self.verify_ref(reference)?;
+        }
+
+        let mut query = self.log_query(revision);
+        query.max_count = self.commit_limit;
+        query.pathspecs = pathspecs.iter().map(|p| p.to_string()).collect();
+        self.log(&query)
+    }
+
+    /// Returns every commit reachable from HEAD that changed the file at `path`, newest first, like
+    /// ```git log --follow -- <path>```. Renames are followed, so the commits made under the file's earlier names are
+    /// included too, back to the one that added it. The history options of this Info apply, but [Commit::stats] and
+    /// [Commit::files] aren't read. The ```git``` binary is always used, whichever backend is set
+    /// ## Example
+    /// ```
+    ///  # use commit_info::Info;
+    ///  # use std::env;
+    ///  # fn main() -> anyhow::Result<()> {
+    ///  # let mut path = env::current_dir().unwrap();
+    ///  # path.push("test_project");
+    ///  # let dir = path.to_string_lossy().to_string();
+    ///  // let dir = "/path/to/repo"; <- Point to the location of t=your repo
+    ///  let history = Info::new(&dir).file_history("config/app.toml")?;
+    ///  if let Some(added) = history.last() {
+    ///      println!("added by {:?}", added.hash);
+    ///  }
+    ///  # Ok(())
+    ///  # }
+    /// ```
+    pub fn file_history(&self, path: &str) -> Result<Vec<Commit>> {
+        self.require_git()?;
+        self.require_repo()?;
+
+        if !self.has_commits() {
+            return Ok(vec![]);
+        }
+
+        let mut query = self.log_query("HEAD");
+        query.pathspecs = vec![path.into()];
+        // --follow takes a single path, which the library backends can't follow through renames
+        let mut args = log_args(&query);
+        args.insert(1, "--follow".into());
+        let args: Vec<&str> = args.iter().map(String::as_str).collect();
+        Ok(parse_log(&self.git(&args)?)?)
+    }
+
+    /// Describes HEAD relative to the most recent tag, like ```git describe --tags --always --dirty```.
+    /// Its parts can be used separately, e.g. to embed the tag and hash in a build, and it displays as git prints it:
+    /// e.g. ```v1.2.3``` on a tag, ```v1.2.3-5-gabc1234``` five commits past it and
+    /// ```v1.2.3-dirty``` when the working tree has changes. Without any tags it is the short hash.
+    /// Empty repos return None
+    /// ## Example
+    /// ```
+    ///  # use commit_info::Info;
+    ///  # use std::env;
+    ///  # fn main() -> anyhow::Result<()> {
+    ///  # let mut path = env::current_dir().unwrap();
+    ///  # path.push("test_project");
+    ///  # let dir = path.to_string_lossy().to_string();
+    ///  // let dir = "/path/to/repo"; <- Point to the location of t=your repo
+    ///  if let Some(describe) = Info::new(&dir).describe()? {
+    ///      println!("{} is {:?} +{}", describe, describe.tag, describe.commits_since_tag);
+    ///  }
+    ///  # Ok(())
+    ///  # }
+    /// ```
+    pub fn describe(&self) -> Result<Option<Describe>> {
+        self.require_git()?;
+        self.require_repo()?;
+
+        if !self.has_commits() {
+            return Ok(None);
+        }
+
+        // --long spells out the count and hash on a tag too, so every description splits the same way
+        let description = self.git(&["describe", "--tags", "--always", "--dirty", "--long"])?;
+        Ok(Some(parse_describe(description.trim())))
+    }
+
+    /// Returns the tag HEAD points at, or None if HEAD is not exactly on a tag
+    /// ## Example
+    /// ```
+    ///  # use commit_info::Info;
+    ///  # use std::env;
+    ///  # fn main() -> anyhow::Result<()> {
+    ///  # let mut path = env::current_dir().unwrap();
+    ///  # path.push("test_project");
+    ///  # let dir = path.to_string_lossy().to_string();
+    ///  // let dir = "/path/to/repo"; <- Point to the location of t=your repo
+    ///  match Info::new(&dir).exact_tag()? {
+    ///      Some(tag) => println!("release build {}", tag),
+    ///      None => println!("dev build"),
+    ///  }
+    ///  # Ok(())
+    ///  # }
+    /// ```
+    pub fn exact_tag(&self) -> Result<Option<String>> {
+        self.require_git()?;
+        self.require_repo()?;
+
+        if !self.has_commits() {
+            return Ok(None);
+        }
+
+        // fails when no tag points at HEAD
+        match self.git(&["describe", "--exact-match", "--tags"]) {
+            Ok(tag) => Ok(Some(tag.trim().into())),
+            Err(_) => Ok(None),
+        }
+    }
+
+    /// Builds a link to view `commit` in the browser, based on the origin remote.
+    /// GitHub, GitLab and Bitbucket remotes are recognized, over both SSH and HTTPS.
+    /// Returns None if the commit has no hash or the remote isn't one of those
+    /// ## Example
+    /// ```
+    ///  # use commit_info::Info;
+    ///  # use std::env;
+    ///  # fn main() -> anyhow::Result<()> {
+    ///  # let mut path = env::current_dir().unwrap();
+    ///  # path.push("test_project");
+    ///  # let dir = path.to_string_lossy().to_string();
+    ///  // let dir = "/path/to/repo"; <- Point to the location of t=your repo
+    ///  let info = Info::new(&dir).commit_info()?;
+    ///  for commit in info.commits.iter().flatten() {
+    ///      println!("{:?}", info.commit_web_url(commit));
+    ///  }
+    ///  # Ok(())
+    ///  # }
+    /// ```
+    pub fn commit_web_url(&self, commit: &Commit) -> Option<String> {
+        let hash = commit.hash.as_ref()?;
+        if !self.is_git {
+            return None;
+        }
+
+        let remote = self.git(&["remote", "get-url", "origin"]).ok()?;
+        let (host, base) = web_base(&remote)?;
+
+        match host.as_str() {
+            "github.com" => Some(format!("{}/commit/{}", base, hash)),
+            "bitbucket.org" => Some(format!("{}/commits/{}", base, hash)),
+            h if h == "gitlab.com" || h.starts_with("gitlab.") => {
+                Some(format!("{}/-/commit/{}", base, hash))
+            }
+            _ => None,
+        }
+    }
+
+    /// Tells whether `path`, relative to the repo root, is tracked, untracked, ignored or doesn't exist at all
+    /// ## Example
+    /// ```
+    ///  # use commit_info::Info;
+    ///  # use std::env;
+    ///  # fn main() -> anyhow::Result<()> {
+    ///  # let mut path = env::current_dir().unwrap();
+    ///  # path.push("test_project");
+    ///  # let dir = path.to_string_lossy().to_string();
+    ///  // let dir = "/path/to/repo"; <- Point to the location of t=your repo
+    ///  let status = Info::new(&dir).path_status("src/main.rs")?;
+    ///  println!("{:?}", status);
+    ///  # Ok(())
+    ///  # }
+    /// ```
+    pub fn path_status(&self, path: &str) -> Result<PathStatus> {
+        self.require_git()?;
+        self.require_repo()?;
+
+        let dir = &self.dir;
+
+        if self
+            .git(&["ls-files", "--error-unmatch", "--", path])
+            .is_ok()
+        {
+            return Ok(PathStatus::Tracked);
+        }
+
+        if !PathBuf::from(dir).join(path).exists() {
+            return Ok(PathStatus::NotFound);
+        }
+
+        // check-ignore exits with 1 when the path isn't ignored
+        match self.git(&["check-ignore", "-q", "--", path]) {
+            Ok(_) => Ok(PathStatus::Ignored),
+            Err(_) => Ok(PathStatus::Untracked),
+        }
+    }
+
+    /// Lists the files that are neither tracked nor ignored, relative to the repo root, like ```git ls-files --others```.
+    ///
+    /// With a `max_depth` git doesn't look inside directories that hold no tracked files, which are listed once
+    /// with a trailing slash as ```git status``` does, and paths more than `max_depth` levels deep are cut
+    /// to their directory, so a large untracked tree such as ```node_modules``` is never walked. None lists every file
+    /// ## Example
+    /// ```
+    ///  # use commit_info::Info;
+    ///  # use std::env;
+    ///  # fn main() -> anyhow::Result<()> {
+    ///  # let mut path = env::current_dir().unwrap();
+    ///  # path.push("test_project");
+    ///  # let dir = path.to_string_lossy().to_string();
+    ///  // let dir = "/path/to/repo"; <- Point to the location of t=your repo
+    ///  for path in Info::new(&dir).untracked_files(Some(2))? {
+    ///      println!("{}", path);
+    ///  }
+    ///  # Ok(())
+    ///  # }
+    /// ```
+    pub fn untracked_files(&self, max_depth: Option<usize>) -> Result<Vec<String>> {
+        self.other_files(&[], max_depth)
+    }
+
+    /// Lists the files ignored by ```.gitignore``` and the other exclude files, relative to the repo root,
+    /// like ```git ls-files --others --ignored --exclude-standard```. See [Info::untracked_files] for `max_depth`
+    /// ## Example
+    /// ```
+    ///  # use commit_info::Info;
+    ///  # use std::env;
+    ///  # fn main() -> anyhow::Result<()> {
+    ///  # let mut path = env::current_dir().unwrap();
+    ///  # path.push("test_project");
+    ///  # let dir = path.to_string_lossy().to_string();
+    ///  // let dir = "/path/to/repo"; <- Point to the location of t=your repo
+    ///  let ignored = Info::new(&dir).ignored_files(Some(1))?;
+    ///  println!("{} ignored paths", ignored.len());
+    ///  # Ok(())
+    ///  # }
+    /// ```
+    pub fn ignored_files(&self, max_depth: Option<usize>) -> Result<Vec<String>> {
+        self.other_files(&["--ignored"], max_depth)
+    }
+
+    // Runs `git ls-files --others` with extra args and cuts the paths it lists to max_depth levels
+    fn other_files(&self, extra: &[&str], max_depth: Option<usize>) -> Result<Vec<String>> {
+        self.require_git()?;
+        self.require_repo()?;
+
+        let mut args = vec!["ls-files", "--others", "--exclude-standard", "-z"];
+        args.extend(extra);
+        if max_depth.is_some() {
+            args.push("--directory");
+        }
+        let output = self.git(&args)?;
+
+        let mut paths: Vec<String> = output
+            .split('\0')
+            .filter(|path| !path.is_empty())
+            .map(|path| match max_depth {
+                Some(depth) => truncate_path(path, depth.max(1)),
+                None => path.into(),
+            })
+            .collect();
+        paths.sort();
+        paths.dedup();
+        Ok(paths)
+    }
+
+    /// Lists the files that differ between the trees of `from` and `to`, as reported by `git diff --name-status`.
+    /// Renames are detected and carry the path they were moved from
+    /// ## Example
+    /// ```
+    ///  # use commit_info::Info;
+    ///  # use std::env;
+    ///  # fn main() -> anyhow::Result<()> {
+    ///  # let mut path = env::current_dir().unwrap();
+    ///  # path.push("test_project");
+    ///  # let dir = path.to_string_lossy().to_string();
+    ///  // let dir = "/path/to/repo"; <- Point to the location of t=your repo
+    ///  # let info = Info::new(&dir);
+    ///  # if info.commits_page(1, 1)?.commits.is_empty() { return Ok(()); }
+    ///  for change in Info::new(&dir).diff_refs("HEAD~1", "HEAD")? {
+    ///      println!("{:?} {}", change.change, change.path);
+    ///  }
+    ///  # Ok(())
+    ///  # }
+    /// ```
+    pub fn diff_refs(&self, from: &str, to: &str) -> Result<Vec<FileChange>> {
+        self.require_git()?;
+        self.require_repo()?;
+
+        self.verify_ref(from)?;
+        self.verify_ref(to)?;
+
+        let output = self.git(&[
+            "-c",
+            "core.quotepath=false",
+            "diff",
+            "--name-status",
+            "-z",
+            "-M",
+            from,
+            to,
+            "--",
+        ])?;
+
+        // with -z every field is NUL terminated: the status, then one path, or two for renames and copies
+        let mut fields = output.split('\0').filter(|f| !f.is_empty());
+        let mut changes = vec![];
+        while let Some(status) = fields.next() {
+            match read_file_change(status, &mut fields) {
+                Some(change) => changes.push(change),
+                None => break,
+            }
+        }
+
+        Ok(changes)
+    }
+
+    /// Returns the hash of the best common ancestor of `a` and `b`, or None when their histories are unrelated.
+    /// An error is returned if either ref does not exist
+    /// ## Example
+    /// ```
+    ///  # use commit_info::Info;
+    ///  # use std::env;
+    ///  # fn main() -> anyhow::Result<()> {
+    ///  # let mut path = env::current_dir().unwrap();
+    ///  # path.push("test_project");
+    ///  # let dir = path.to_string_lossy().to_string();
+    ///  // let dir = "/path/to/repo"; <- Point to the location of t=your repo
+    ///  # if Info::new(&dir).commits_page(0, 1)?.commits.is_empty() { return Ok(()); }
+    ///  let base = Info::new(&dir).merge_base("HEAD", "HEAD")?;
+    ///  println!("{:?}", base);
+    ///  # Ok(())
+    ///  # }
+    /// ```
+    pub fn merge_base(&self, a: &str, b: &str) -> Result<Option<String>> {
+        self.merge_base_all(&[a, b])
+    }
+
+    /// Same as [Info::merge_base] for any number of refs, using ```git merge-base --octopus```
+    /// to find the best common ancestor of all of them
+    /// ## Example
+    /// ```
+    ///  # use commit_info::Info;
+    ///  # use std::env;
+    ///  # fn main() -> anyhow::Result<()> {
+    ///  # let mut path = env::current_dir().unwrap();
+    ///  # path.push("test_project");
+    ///  # let dir = path.to_string_lossy().to_string();
+    ///  // let dir = "/path/to/repo"; <- Point to the location of t=your repo
+    ///  # if Info::new(&dir).commits_page(0, 1)?.commits.is_empty() { return Ok(()); }
+    ///  let base = Info::new(&dir).merge_base_all(&["HEAD", "HEAD", "HEAD"])?;
+    ///  println!("{:?}", base);
+    ///  # Ok(())
+    ///  # }
+    /// ```
+    pub fn merge_base_all(&self, refs: &[&str]) -> Result<Option<String>> {
+        self.require_git()?;
+        self.require_repo()?;
+        for reference in refs {
+            self.verify_ref(reference)?;
+        }
+
+        // merge-base exits with 1 when the refs have no common ancestor
+        let mut args = vec!["merge-base", "--octopus"];
+        args.extend(refs);
+        let base = self.git(&args).ok();
+        Ok(base.filter(|base| !base.is_empty()))
+    }
+
+    /// Returns the header and unified diff of the commit `hash`, as printed by ```git show --patch```.
+    /// With `max_bytes` set, at most that many bytes of the patch are read from git and
+    /// a truncation marker is appended when there was more. An error is returned if `hash` isn't a commit
+    /// ## Example
+    /// ```
+    ///  # use commit_info::Info;
+    ///  # use std::env;
+    ///  # fn main() -> anyhow::Result<()> {
+    ///  # let mut path = env::current_dir().unwrap();
+    ///  # path.push("test_project");
+    ///  # let dir = path.to_string_lossy().to_string();
+    ///  // let dir = "/path/to/repo"; <- Point to the location of t=your repo
+    ///  # if Info::new(&dir).commits_page(0, 1)?.commits.is_empty() { return Ok(()); }
+    ///  let patch = Info::new(&dir).commit_patch("HEAD", Some(64 * 1024))?;
+    ///  println!("{}", patch);
+    ///  # Ok(())
+    ///  # }
+    /// ```
+    pub fn commit_patch(&self, hash: &str, max_bytes: Option<usize>) -> Result<String> {
+        self.require_git()?;
+        self.require_repo()?;
+        self.verify_ref(hash)?;
+
+        let mut child = self
+            .within(|| RunOptions::current().command(&self.dir))
+            .args([
+                "show",
+                "--format=medium",
+                "--patch",
+                "--no-color",
+                "--no-ext-diff",
+            ])
+            .arg(format!("{}^{{commit}}", hash))
+            .arg("--")
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()?;
+
+        let mut patch = vec![];
+        let stdout = child
+            .stdout
+            .take()
+            .ok_or_else(|| anyhow!("git show has no output"))?;
+        // read one byte past the cap to find out whether there is more
+        let limit = max_bytes.map_or(u64::MAX, |max| max as u64 + 1);
+        stdout.take(limit).read_to_end(&mut patch)?;
+
+        if max_bytes.is_some_and(|max| patch.len() > max) {
+            // git doesn't need to finish writing a patch nobody reads
+            let _ = child.kill();
+            let _ = child.wait();
+            patch.truncate(max_bytes.unwrap_or_default());
+            let mut patch = String::from_utf8_lossy(&patch).into_owned();
+            patch.push_str("\n[... patch truncated ...]\n");
+            return Ok(patch);
+        }
+
+        let status = child.wait()?;
+        if !status.success() {
+            bail!("git show failed with {}", status);
+        }
+        Ok(String::from_utf8_lossy(&patch).into_owned())
+    }
+
+    /// Checks that every object reachable from the refs of the repo is present and readable, with
+    /// ```git fsck --connectivity-only```. It is much faster than a full ```git fsck``` as the content of blobs isn't
+    /// checked, yet catches a mirror with missing or corrupt objects before its commit info is trusted
+    /// ## Example
+    /// ```
+    ///  # use commit_info::Info;
+    ///  # use std::env;
+    ///  # fn main() -> anyhow::Result<()> {
+    ///  # let mut path = env::current_dir().unwrap();
+    ///  # path.push("test_project");
+    ///  # let dir = path.to_string_lossy().to_string();
+    ///  // let dir = "/path/to/repo"; <- Point to the location of t=your repo
+    ///  let report = Info::new(&dir).verify()?;
+    ///  if !report.is_ok() {
+    ///      println!("missing {:?}, errors {:?}", report.missing, report.errors);
+    ///  }
+    ///  # Ok(())
+    ///  # }
+    /// ```
+    pub fn verify(&self) -> Result<IntegrityReport> {
+        self.require_git()?;
+        self.require_repo()?;
+
+        // fsck fails when it finds a problem, so its output is read whatever the exit status
+        let args = ["fsck", "--connectivity-only", "--no-progress"];
+        let output = self.within(|| RunOptions::current().raw_output(&self.dir, &args))?;
+        let report = parse_fsck(
+            &String::from_utf8_lossy(&output.stdout),
+            &String::from_utf8_lossy(&output.stderr),
+        );
+        if !output.status.success() && report.is_ok() {
+            return Err(command_output(&args, Ok(output)).unwrap_err().into());
+        }
+        Ok(report)
+    }
+
+    /// Checks the signature of every commit reachable from HEAD, newest first, against a list of trusted signer emails.
+    /// Each commit hash is paired with true only when its signature is good and its signer is in `trusted_emails`.
+    /// Unsigned commits and bad or unverifiable signatures are false. Emails are compared case insensitively
+    /// ## Example
+    /// ```
+    ///  # use commit_info::Info;
+    ///  # use std::env;
+    ///  # fn main() -> anyhow::Result<()> {
+    ///  # let mut path = env::current_dir().unwrap();
+    ///  # path.push("test_project");
+    ///  # let dir = path.to_string_lossy().to_string();
+    ///  // let dir = "/path/to/repo"; <- Point to the location of t=your repo
+    ///  for (hash, trusted) in Info::new(&dir).verify_commits(&["release@example.com"])? {
+    ///      if !trusted {
+    ///          println!("{} is not signed by a trusted key", hash);
+    ///      }
+    ///  }
+    ///  # Ok(())
+    ///  # }
+    /// ```
+    pub fn verify_commits(&self, trusted_emails: &[&str]) -> Result<Vec<(String, bool)>> {
+        self.require_git()?;
+        self.require_repo()?;
+
+        if !self.has_commits() {
+            return Ok(vec![]);
+        }
+
+        let options = self.history_options();
+        let mut args = vec!["log", "--format=%H%x00%G?%x00%GS"];
+        args.extend(options.iter().map(String::as_str));
+        args.extend(["HEAD", "--"]);
+        let output = self.git(&args)?;
+
+        let mut verified = vec![];
+        for line in output.lines() {
+            let mut fields = line.split('\0');
+            let (hash, validity, signer) = match (fields.next(), fields.next(), fields.next()) {
+                (Some(hash), Some(validity), Some(signer)) => (hash, validity, signer),
+                _ => continue,
+            };
+            // the allow-list decides the trust here, so keys gpg doesn't trust yet count as good
+            let good = SignatureStatus::from_code(validity).is_some_and(|s| s.is_good());
+            let email = signer_email(signer);
+            let trusted = good
+                && !email.is_empty()
+                && trusted_emails.iter().any(|t| t.eq_ignore_ascii_case(email));
+            verified.push((hash.to_string(), trusted));
+        }
+
+        Ok(verified)
+    }
+
+    /// Serializes the whole Info into a JSON value, the same as serializing it with serde: the public fields of [Info],
+    /// with the [Status] under ```status``` and each [Commit] under ```commits```.
+    /// `status` and `commits` are null until [Info::status_info] and [Info::commit_info] have been run.
+    /// With the ```dates``` feature dates are rendered in RFC 3339, unless set with [Info::with_date_format]
+    /// or [Info::with_display_timezone]; without it they are kept as git reports them
+    /// ## Example
+    /// ```
+    ///  # use commit_info::Info;
+    ///  # use std::env;
+    ///  # fn main() -> anyhow::Result<()> {
+    ///  # let mut path = env::current_dir().unwrap();
+    ///  # path.push("test_project");
+    ///  # let dir = path.to_string_lossy().to_string();
+    ///  // let dir = "/path/to/repo"; <- Point to the location of t=your repo
+    ///  let json = Info::new(&dir).status_info()?.commit_info()?.to_json();
+    ///  println!("{}", json["is_git"]);
+    ///  # Ok(())
+    ///  # }
+    /// ```
+    pub fn to_json(&self) -> Value {
+        // every map of an Info has string keys and its paths are serialized lossily, so this can't fail
+        #[allow(unused_mut)]
+        let mut json = serde_json::to_value(self).unwrap_or_default();
+        #[cfg(feature = "dates")]
+        if self.display_timezone.is_some() || self.date_format.is_some() {
+            let (tz, format) = (self.display_timezone.as_ref(), self.date_format.as_deref());
+            let rendered = json["commits"].as_array_mut().into_iter().flatten();
+            for (commit, json) in self.commits.iter().flatten().zip(rendered) {
+                for (key, date) in [
+                    ("commit_date", &commit.commit_date),
+                    ("author_date", &commit.author_date),
+                ] {
+                    if let Some(date) = date {
+                        json[key] = my_date_format::format_in(date, tz, format).into();
+                    }
+                }
+            }
+        }
+        json
+    }
+
+    /// Same as [Info::to_json] but rendered as an indented JSON string
+    /// ## Example
+    /// ```
+    ///  # use commit_info::Info;
+    ///  # use std::env;
+    ///  # fn main() -> anyhow::Result<()> {
+    ///  # let mut path = env::current_dir().unwrap();
+    ///  # path.push("test_project");
+    ///  # let dir = path.to_string_lossy().to_string();
+    ///  // let dir = "/path/to/repo"; <- Point to the location of t=your repo
+    ///  println!("{}", Info::new(&dir).status_info()?.to_json_pretty());
+    ///  # Ok(())
+    ///  # }
+    /// ```
+    pub fn to_json_pretty(&self) -> String {
+        // serializing a Value can't fail
+        serde_json::to_string_pretty(&self.to_json()).unwrap_or_default()
+    }
+
+    /// Returns the paths that make the repo dirty, see [Status::dirty_paths].
+    /// If the status hasn't been gathered with [Info::status_info], the returned Vec is empty
+    /// ## Example
+    /// ```
+    ///  # use commit_info::Info;
+    ///  # use std::env;
+    ///  # fn main() -> anyhow::Result<()> {
+    ///  # let mut path = env::current_dir().unwrap();
+    ///  # path.push("test_project");
+    ///  # let dir = path.to_string_lossy().to_string();
+    ///  // let dir = "/path/to/repo"; <- Point to the location of t=your repo
+    ///  let dirty = Info::new(&dir).status_info()?.dirty_paths();
+    ///  println!("{:#?}", dirty);
+    ///  # Ok(())
+    ///  # }
+    /// ```
+    pub fn dirty_paths(&self) -> Vec<String> {
+        match &self.status {
+            Some(status) => status.dirty_paths(),
+            None => vec![],
+        }
+    }
+
+    /// Returns true only when the repo is known to be clean, see [Status::is_clean].
+    /// If the status hasn't been gathered yet it is gathered now. When it can't be determined
+    /// (not a git repo, git failed) the repo is not considered clean
+    /// ## Example
+    /// ```
+    ///  # use commit_info::Info;
+    ///  # use std::env;
+    ///  # fn main() -> anyhow::Result<()> {
+    ///  # let mut path = env::current_dir().unwrap();
+    ///  # path.push("test_project");
+    ///  # let dir = path.to_string_lossy().to_string();
+    ///  // let dir = "/path/to/repo"; <- Point to the location of t=your repo
+    ///  if !Info::new(&dir).is_clean() {
+    ///      println!("commit your changes first");
+    ///  }
+    ///  # Ok(())
+    ///  # }
+    /// ```
+    pub fn is_clean(&self) -> bool {
+        match &self.status {
+            Some(status) => status.is_clean(),
+            None => self
+                .status_info()
+                .ok()
+                .and_then(|info| info.status)
+                .is_some_and(|status| status.is_clean()),
+        }
+    }
+
+    // The latest commits of reference, up to the commit limit, or None if there are none
+    fn top_commits(&self, reference: &str) -> Result<Option<Vec<Commit>>, CommitInfoError> {
+        let query = LogQuery {
+            max_count: self.commit_limit,
+            ..self.log_query(reference)
+        };
+        let commits = self.log(&query)?;
+
+        if !commits.is_empty() {
+            Ok(Some(commits))
+        } else {
+            Ok(None)
+        }
+    }
+
+    // Runs work with the git commands it spawns held to the timeout and cancellation token of this Info
+    fn limited<T>(
+        &self,
+        work: impl FnOnce() -> Result<T, CommitInfoError>,
+    ) -> Result<T, CommitInfoError> {
+        let options = RunOptions {
+            deadline: self
+                .timeout
+                .map(|timeout| (Instant::now() + timeout, timeout)),
+            cancellation: self.cancellation.clone(),
+            ..self.run_options()
+        };
+        let result = RunOptions::enter(options.clone(), work);
+        // commands stopped early may have been taken for a missing ref or the like
+        options.check()?;
+        result
+    }
+
+    // Returns what work gathered under key within the cache TTL, copied onto this Info with copy,
+    // or runs it and caches the result. Work always runs without a cache
+    fn cached(
+        &self,
+        key: &[&str],
+        copy: fn(&Info, &mut Info),
+        work: impl FnOnce() -> Result<Info, CommitInfoError>,
+    ) -> Result<Info, CommitInfoError> {
+        let Some(cache) = &self.cache else {
+            return work();
+        };
+        // the same call made with other options gathers something else
+        let key = format!(
+            "{:?}",
+            (
+                key,
+                (self.commit_limit, self.since, self.until),
+                (&self.author, &self.committer, &self.notes_ref),
+                (self.include_merges, self.first_parent, self.use_mailmap),
+                (self.stats, self.files, self.signatures, self.notes),
+                (&self.git_binary, &self.git_args, self.inherit_git_env),
+            )
+        );
+        if let Some(found) = cache.get(&key) {
+            let mut git_info = self.clone();
+            copy(&found, &mut git_info);
+            return Ok(git_info);
+        }
+
+        let git_info = work()?;
+        cache.insert(key, &git_info);
+        Ok(git_info)
+    }
+
+    // Copies what commit_info gathers from one Info to another
+    fn copy_commits(from: &Info, to: &mut Info) {
+        to.branch.clone_from(&from.branch);
+        to.default_branch.clone_from(&from.default_branch);
+        to.head_hash.clone_from(&from.head_hash);
+        to.head_short_hash.clone_from(&from.head_short_hash);
+        to.commits.clone_from(&from.commits);
+    }
+
+    // Copies what status_info gathers from one Info to another
+    fn copy_status(from: &Info, to: &mut Info) {
+        to.status.clone_from(&from.status);
+    }
+
+    // Runs work with the git binary and arguments of this Info, unless it already runs within limited
+    fn within<T>(&self, work: impl FnOnce() -> T) -> T {
+        match RunOptions::active() {
+            true => work(),
+            false => RunOptions::enter(self.run_options(), work),
+        }
+    }
+
+    // How this Info spawns git, see with_git_binary, with_git_args and with_inherited_git_env
+    fn run_options(&self) -> RunOptions {
+        let defaults = RunOptions::default();
+        RunOptions {
+            program: self.git_binary.clone().unwrap_or(defaults.program),
+            global_args: self.git_args.clone(),
+            inherit_git_env: self.inherit_git_env,
+            ..defaults
+        }
+    }
+
+    // Runs git with args in the repo, see RunOptions::output
+    fn git(&self, args: &[&str]) -> Result<String, CommitInfoError> {
+        self.within(|| git_output(&self.dir, args))
+    }
+
+    // Fails with GitBinaryMissing unless the git binary of this Info can be run
+    fn require_git(&self) -> Result<()> {
+        self.within(require_git)
+    }
+
+    // Fails with NotARepository unless dir is a git repo
+    fn require_repo(&self) -> Result<(), CommitInfoError> {
+        if !self.is_git {
+            return Err(CommitInfoError::NotARepository(self.dir.clone()));
+        }
+        Ok(())
+    }
+
+    // Spawns `git log` with the given extra args and returns an iterator reading its output lazily
+    fn stream_log(&self, args: &[&str]) -> Result<CommitIter> {
+        let mut command = self.within(|| RunOptions::current().command(&self.dir));
+        command
+            .arg("log")
+            .arg(format!("--format={}", log_format(&self.log_query(""))))
+            .arg("-z")
+            .args(notes_arg(&self.log_query("")))
+            .args(DECORATE_REFS)
+            .args(self.history_options());
+        let mut child = command
+            .args(args)
+            .arg("--")
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(spawn_error)?;
+
+        let stdout = child.stdout.take().map(BufReader::new);
+        Ok(CommitIter {
+            child: Some(child),
+            stdout,
+            bytes_read: 0,
+        })
+    }
+
+    // Swaps the mailmap aware placeholders of a log format for the raw ones unless mailmap is in use
+    fn mailmap_format(&self, format: &str) -> String {
+        mailmap_format(format, self.use_mailmap)
+    }
+
+    // The `git log` options selecting which commits of the history are walked
+    fn history_options(&self) -> Vec<String> {
+        history_options(&self.log_query(""))
+    }
+
+    // A query for the commits of revision, walked with the history options of this Info
+    fn log_query(&self, revision: &str) -> LogQuery {
+        LogQuery {
+            revision: revision.into(),
+            include_merges: self.include_merges,
+            first_parent: self.first_parent,
+            use_mailmap: self.use_mailmap,
+            since: self.since,
+            until: self.until,
+            author: self.author.clone(),
+            committer: self.committer.clone(),
+            notes: self.notes,
+            notes_ref: self.notes_ref.clone(),
+            stats: self.stats,
+            files: self.files,
+            signatures: self.signatures,
+            ..LogQuery::default()
+        }
+    }
+
+    // Lists the commits selected by query through the backend
+    fn log(&self, query: &LogQuery) -> Result<Vec<Commit>> {
+        self.within(|| self.backend.log(&self.dir, query))
+    }
+
+    // Fails unless reference resolves to a commit
+    fn verify_ref(&self, reference: &str) -> Result<()> {
+        self.within(|| self.backend.verify_ref(&self.dir, reference))
+    }
+
+    // Counts the commits query selects with `git rev-list --count`, whatever its skip and limit
+    fn count_commits(&self, query: &LogQuery) -> Result<usize> {
+        let query = LogQuery {
+            skip: 0,
+            max_count: None,
+            ..query.clone()
+        };
+        let mut args = vec!["rev-list".to_string(), "--count".into()];
+        args.extend(selection_args(&query));
+        let args: Vec<&str> = args.iter().map(String::as_str).collect();
+        Ok(self.git(&args)?.trim().parse()?)
+    }
+
+    // Checks that HEAD points at a commit, which is not the case in freshly initialized repos
+    fn has_commits(&self) -> bool {
+        self.git(&["rev-parse", "--verify", "-q", "HEAD"]).is_ok()
+    }
+}
+
+impl GitBackend for GitCli {
+    fn check(&self) -> Result<()> {
+        require_git()
+    }
+
+    fn status(&self, dir: &Path, pathspecs: &[&str]) -> Result<Status> {
+        let mut status = Status::default();
+
+        // both commands are read only, so the diff can run alongside the status
+        let options = RunOptions::current();
+        let (resp, diff) = thread::scope(|scope| {
+            //check diff
+            let diff = scope.spawn(|| {
+                let mut args = vec!["diff", "--stat", "--"];
+                args.extend(pathspecs);
+                options.output(dir, &args)
+            });
+            let mut args = STATUS_ARGS.to_vec();
+            args.extend(pathspecs);
+            (git_output(dir, &args), join(diff))
+        });
+
+        status.summarize(&resp?, diff);
+        Ok(status)
+    }
+
+    fn log(&self, dir: &Path, query: &LogQuery) -> Result<Vec<Commit>> {
+        let args = log_args(query);
+        let args: Vec<&str> = args.iter().map(String::as_str).collect();
+        let mut commits = parse_log(&git_output(dir, &args)?)?;
+
+        // a second walk over the same commits, -z can't tell numstat lines from the next commit
+        if query.stats {
+            let args = stats_args(query);
+            let args: Vec<&str> = args.iter().map(String::as_str).collect();
+            add_stats(&mut commits, parse_numstat(&git_output(dir, &args)?));
+        }
+        if query.files {
+            let args = files_args(query);
+            let args: Vec<&str> = args.iter().map(String::as_str).collect();
+            add_files(&mut commits, parse_name_status(&git_output(dir, &args)?));
+        }
+        Ok(commits)
+    }
+
+    fn verify_ref(&self, dir: &Path, reference: &str) -> Result<()> {
+        let commit = format!("{}^{{commit}}", reference);
+        match git_output(dir, &["rev-parse", "--verify", "-q", &commit]) {
+            Ok(_) => Ok(()),
+            Err(e @ (CommitInfoError::Timeout(_) | CommitInfoError::Cancelled)) => Err(e.into()),
+            Err(_) => bail!("Unknown git reference: {}", reference),
+        }
+    }
+
+    fn head_hash(&self, dir: &Path) -> Option<String> {
+        git_output(dir, &["rev-parse", "-q", "--verify", "HEAD"]).ok()
+    }
+
+    fn head_short_hash(&self, dir: &Path) -> Option<String> {
+        git_output(dir, &["rev-parse", "-q", "--verify", "--short", "HEAD"]).ok()
+    }
+
+    // Resolves the branch origin/HEAD points to, asking the remote itself if that ref isn't set locally.
+    // That is the only query that goes over the network, and it never prompts for credentials
+    fn default_branch(&self, dir: &Path) -> Option<String> {
+        let head = git_output(
+            dir,
+            &["symbolic-ref", "--short", "-q", "refs/remotes/origin/HEAD"],
+        );
+        if let Ok(head) = head {
+            if let Some(branch) = head.trim().strip_prefix("origin/") {
+                return Some(branch.into());
+            }
+        }
+
+        // there is no point asking a remote that doesn't exist
+        git_output(dir, &["remote", "get-url", "origin"]).ok()?;
+
+        let remote = RunOptions::current()
+            .remote_output(dir, &["remote", "show", "origin"])
+            .ok()?;
+        remote
+            .lines()
+            .find_map(|line| line.trim().strip_prefix("HEAD branch: "))
+            .filter(|branch| *branch != "(unknown)")
+            .map(|branch| branch.into())
+    }
+
+    fn remote_branch(&self, dir: &Path) -> Option<String> {
+        let branches = git_output(dir, &["branch", "-r"]).ok()?;
+        branches
+            .lines()
+            .find(|branch| !branch.contains("HEAD"))
+            .map(|branch| branch.trim().to_string())
+            .filter(|branch| !branch.is_empty())
+    }
+
+    // Tries in order: the current branch, its upstream, the nearest tag and finally the short hash of a detached HEAD
+    fn head_name(&self, dir: &Path) -> Option<String> {
+        self.head_hash(dir)?;
+        let name = |args: &[&str]| git_output(dir, args).ok().filter(|name| !name.is_empty());
+
+        name(&["symbolic-ref", "--short", "-q", "HEAD"])
+            .or_else(|| name(&["rev-parse", "--abbrev-ref", "@{upstream}"]))
+            .or_else(|| name(&["describe", "--tags"]))
+            .or_else(|| {
+                name(&["rev-parse", "--short", "HEAD"]).map(|hash| format!("(detached: {})", hash))
+            })
+    }
+}
+
+impl Backend {
+    /// The backend [Backend::Auto] resolves to on this machine
+    /// ## Example
+    /// ```
+    ///  # use commit_info::Backend;
+    ///  println!("{:?}", Backend::detect());
+    /// ```
+    pub fn detect() -> Backend {
+        static DETECTED: OnceLock<Backend> = OnceLock::new();
+        // without a library to fall back to there is nothing to detect
+        if cfg!(not(any(feature = "backend-git2", feature = "backend-gix"))) {
+            return Backend::Cli;
+        }
+        *DETECTED.get_or_init(|| Backend::fallback(RunOptions::default().git_runs()))
+    }
+
+    // The backend to use depending on whether git is installed
+    fn fallback(git_found: bool) -> Backend {
+        if git_found {
+            return Backend::Cli;
+        }
+        #[cfg(feature = "backend-git2")]
+        return Backend::Git2;
+        #[cfg(all(feature = "backend-gix", not(feature = "backend-git2")))]
+        return Backend::Gix;
+        #[allow(unreachable_code)]
+        Backend::Cli
+    }
+
+    fn backend(self) -> &'static dyn GitBackend {
+        match self {
+            Backend::Auto => Backend::detect().backend(),
+            Backend::Cli => &GitCli,
+            #[cfg(feature = "backend-git2")]
+            Backend::Git2 => &Git2Backend,
+            #[cfg(feature = "backend-gix")]
+            Backend::Gix => &GixBackend,
+        }
+    }
+}
+
+impl GitBackend for Backend {
+    fn check(&self) -> Result<()> {
+        self.backend().check()
+    }
+
+    fn status(&self, dir: &Path, pathspecs: &[&str]) -> Result<Status> {
+        self.backend().status(dir, pathspecs)
+    }
+
+    fn log(&self, dir: &Path, query: &LogQuery) -> Result<Vec<Commit>> {
+        self.backend().log(dir, query)
+    }
+
+    fn verify_ref(&self, dir: &Path, reference: &str) -> Result<()> {
+        self.backend().verify_ref(dir, reference)
+    }
+
+    fn head_hash(&self, dir: &Path) -> Option<String> {
+        self.backend().head_hash(dir)
+    }
+
+    fn head_short_hash(&self, dir: &Path) -> Option<String> {
+        self.backend().head_short_hash(dir)
+    }
+
+    fn default_branch(&self, dir: &Path) -> Option<String> {
+        self.backend().default_branch(dir)
+    }
+
+    fn remote_branch(&self, dir: &Path) -> Option<String> {
+        self.backend().remote_branch(dir)
+    }
+
+    fn head_name(&self, dir: &Path) -> Option<String> {
+        self.backend().head_name(dir)
+    }
+}
+
+/// Gathers the status and commits of every git repo directly inside `root`, see [Info::gather].
+/// Only the immediate subdirectories are looked at, so repos nested inside them are not scanned.
+/// Directories that are not git repos are skipped. The repos are scanned in parallel and returned sorted by dir
+/// ## Example
+/// ```
+///  # use commit_info::scan_repos;
+///  # use std::env;
+///  # fn main() -> anyhow::Result<()> {
+///  # let root = env::current_dir().unwrap().to_string_lossy().to_string();
+///  // let root = "/path/to/projects"; <- Point to the directory holding your repos
+///  for info in scan_repos(&root)? {
+///      println!("{} {:?}", info.dir.display(), info.status.and_then(|s| s.git_dirty));
+///  }
+///  # Ok(())
+///  # }
+/// ```
+pub fn scan_repos(root: impl AsRef<Path>) -> Result<Vec<Info>> {
+    let mut repos = vec![];
+    for entry in std::fs::read_dir(root)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            let info = Info::new(&path);
+            if info.is_git {
+                repos.push(info);
+            }
+        }
+    }
+    repos.sort_by(|a, b| a.dir.cmp(&b.dir));
+
+    let dirs = repos.iter().map(|info| info.dir.as_path());
+    scan_all(dirs, &ScanOptions::new())
+        .into_iter()
+        .map(|info| Ok(info?))
+        .collect()
+}
+
+/// How [scan_all] gathers the repos it is given
+/// ## Example
+/// ```
+///  # use commit_info::ScanOptions;
+///  let options = ScanOptions::new()
+///      .with_concurrency(8)
+///      .with_info(|info| info.with_commit_limit(1));
+/// ```
+#[derive(Clone)]
+pub struct ScanOptions {
+    // how many repos are gathered at once
+    concurrency: usize,
+    // applied to the Info of every repo before it is gathered, see with_info
+    configure: Option<Arc<dyn Fn(Info) -> Info + Send + Sync>>,
+}
+
+impl std::fmt::Debug for ScanOptions {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ScanOptions")
+            .field("concurrency", &self.concurrency)
+            .field("configure", &self.configure.is_some())
+            .finish()
+    }
+}
+
+impl Default for ScanOptions {
+    fn default() -> Self {
+        ScanOptions {
+            concurrency: thread::available_parallelism().map_or(4, |n| n.get()),
+            configure: None,
+        }
+    }
+}
+
+impl ScanOptions {
+    /// Gathers as many repos at once as the machine has cores, with the default [Info] options
+    pub fn new() -> ScanOptions {
+        ScanOptions::default()
+    }
+
+    /// Sets how many repos are gathered at once, at least one. Each of them runs a few git commands concurrently
+    pub fn with_concurrency(mut self, concurrency: usize) -> ScanOptions {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+
+    /// Sets the options of the [Info] of every repo, e.g. ```|info| info.with_commit_limit(1)```
+    pub fn with_info(
+        mut self,
+        configure: impl Fn(Info) -> Info + Send + Sync + 'static,
+    ) -> ScanOptions {
+        self.configure = Some(Arc::new(configure));
+        self
+    }
+}
+
+/// Gathers the status and commits of every repo in `paths` in parallel, see [Info::gather].
+/// No more than [ScanOptions::with_concurrency] repos are gathered at once.
+/// The results are in the order of `paths`, with an error for each path that couldn't be gathered,
+/// e.g. [CommitInfoError::NotARepository]
+/// ## Example
+/// ```
+///  # use commit_info::{scan_all, ScanOptions};
+///  # use std::env;
+///  # let mut path = env::current_dir().unwrap();
+///  # path.push("test_project");
+///  # let dir = path.to_string_lossy().to_string();
+///  // let dir = "/path/to/repo"; <- Point to the location of t=your repo
+///  let paths = vec![dir, "/not/a/repo".to_string()];
+///  for info in scan_all(&paths, &ScanOptions::new().with_concurrency(4)) {
+///      match info {
+///          Ok(info) => println!("{} {:?}", info.dir.display(), info.status.and_then(|s| s.git_dirty)),
+///          Err(e) => println!("{}", e),
+///      }
+///  }
+/// ```
+pub fn scan_all<P: AsRef<Path>>(
+    paths: impl IntoIterator<Item = P>,
+    options: &ScanOptions,
+) -> Vec<Result<Info, CommitInfoError>> {
+    let paths: Vec<PathBuf> = paths
+        .into_iter()
+        .map(|path| path.as_ref().to_path_buf())
+        .collect();
+    let next = std::sync::atomic::AtomicUsize::new(0);
+    let gather = |path: &PathBuf| {
+        let info = Info::new(path);
+        match &options.configure {
+            Some(configure) => configure(info).gather(),
+            None => info.gather(),
+        }
+    };
+
+    // each worker takes the next path until none are left
+    let mut results: Vec<_> = thread::scope(|scope| {
+        let workers: Vec<_> = (0..options.concurrency.min(paths.len()))
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut gathered = vec![];
+                    loop {
+                        let index = next.fetch_add(1, Ordering::SeqCst);
+                        match paths.get(index) {
+                            Some(path) => gathered.push((index, gather(path))),
+                            None => return gathered,
+                        }
+                    }
+                })
+            })
+            .collect();
+        workers.into_iter().flat_map(join).collect()
+    });
+    results.sort_by_key(|(index, _)| *index);
+    results.into_iter().map(|(_, info)| info).collect()
+}
+
+/// A handle to the gathered [Info] of a repo that threads can share, e.g. in a long running daemon.
+/// Clones share the same data. Concurrent refreshes are coalesced, so however many threads ask for fresh data
+/// at once, git is only run by one of them and the others get its result
+/// ## Example
+/// ```
+///  # use commit_info::{Info, SharedInfo};
+///  # use std::{env, thread, time::Duration};
+///  # fn main() -> anyhow::Result<()> {
+///  # let mut path = env::current_dir().unwrap();
+///  # path.push("test_project");
+///  # let dir = path.to_string_lossy().to_string();
+///  // let dir = "/path/to/repo"; <- Point to the location of t=your repo
+///  let shared = SharedInfo::new(Info::new(&dir), Duration::from_secs(2));
+///  let handle = shared.clone();
+///  thread::spawn(move || {
+///      let info = handle.get_or_refresh().unwrap();
+///      println!("{:?}", info.status);
+///  })
+///  .join()
+///  .unwrap();
+///  println!("{:?}", shared.get().map(|info| info.branch.clone()));
+///  # Ok(())
+///  # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct SharedInfo {
+    state: Arc<SharedState>,
+}
+
+#[derive(Debug)]
+struct SharedState {
+    // gathered to refresh, with the options it was given
+    info: Info,
+    // how long gathered data is handed out by get_or_refresh
+    max_age: Duration,
+    // the data and the count of refreshes so far
+    current: std::sync::RwLock<SharedSnapshot>,
+    // held while refreshing
+    refreshing: Mutex<()>,
+}
+
+#[derive(Debug, Default)]
+struct SharedSnapshot {
+    gathered: Option<(Instant, Arc<Info>)>,
+    refreshes: u64,
+}
+
+impl SharedInfo {
+    /// Shares what `info` gathers, see [Info::gather]. Nothing is gathered until asked for,
+    /// and [SharedInfo::get_or_refresh] gathers again once the data is older than `max_age`
+    pub fn new(info: Info, max_age: Duration) -> SharedInfo {
+        SharedInfo {
+            state: Arc::new(SharedState {
+                info,
+                max_age,
+                current: Default::default(),
+                refreshing: Mutex::new(()),
+            }),
+        }
+    }
+
+    /// The data gathered last, however old it is. None until it has been gathered once
+    pub fn get(&self) -> Option<Arc<Info>> {
+        let current = self.state.current.read().unwrap_or_else(|e| e.into_inner());
+        current.gathered.as_ref().map(|(_, info)| info.clone())
+    }
+
+    /// The data gathered last if it is younger than the max age, otherwise gathers it again.
+    /// A thread that finds a refresh running waits for it and returns its result
+    pub fn get_or_refresh(&self) -> Result<Arc<Info>, CommitInfoError> {
+        let refreshes = {
+            let current = self.state.current.read().unwrap_or_else(|e| e.into_inner());
+            match &current.gathered {
+                Some((gathered, info)) if gathered.elapsed() < self.state.max_age => {
+                    return Ok(info.clone())
+                }
+                _ => current.refreshes,
+            }
+        };
+        self.refresh_after(refreshes)
+    }
+
+    /// Gathers the data again, whatever its age. A refresh already running is joined instead of starting another
+    pub fn refresh(&self) -> Result<Arc<Info>, CommitInfoError> {
+        let refreshes = self
+            .state
+            .current
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .refreshes;
+        self.refresh_after(refreshes)
+    }
+
+    // Gathers the data unless another thread did so since it had been refreshed `refreshes` times.
+    // If that refresh failed, this one tries again
+    fn refresh_after(&self, refreshes: u64) -> Result<Arc<Info>, CommitInfoError> {
+        let _refreshing = self
+            .state
+            .refreshing
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        {
+            let current = self.state.current.read().unwrap_or_else(|e| e.into_inner());
+            if let (true, Some((_, info))) = (current.refreshes != refreshes, &current.gathered) {
+                return Ok(info.clone());
+            }
+        }
+
+        let info = Arc::new(self.state.info.gather()?);
+        let mut current = self
+            .state
+            .current
+            .write()
+            .unwrap_or_else(|e| e.into_inner());
+        current.gathered = Some((Instant::now(), info.clone()));
+        current.refreshes += 1;
+        Ok(info)
+    }
+}
+
+// Swaps the mailmap aware placeholders of a log format for the raw ones unless use_mailmap is set
+fn mailmap_format(format: &str, use_mailmap: bool) -> String {
+    if use_mailmap {
+        return format.into();
+    }
+    ["%aN", "%aE", "%cN", "%cE"]
+        .iter()
+        .fold(format.into(), |format: String, placeholder| {
+            format.replace(placeholder, &placeholder.to_lowercase())
+        })
+}
+
+// The results kept by Info::with_cache_ttl, shared by the clones of an Info.
+// Entries hold copies of the Info that gathered them, without the cache itself
+#[derive(Debug, Clone)]
+struct ResultCache {
+    ttl: Duration,
+    entries: Arc<Mutex<HashMap<String, (Instant, Info)>>>,
+}
+
+impl ResultCache {
+    // The Info cached under key, unless it is older than the TTL
+    fn get(&self, key: &str) -> Option<Info> {
+        let entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        let (cached, git_info) = entries.get(key)?;
+        (cached.elapsed() < self.ttl).then(|| git_info.clone())
+    }
+
+    fn insert(&self, key: String, git_info: &Info) {
+        let mut git_info = git_info.clone();
+        git_info.cache = None;
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        entries.retain(|_, (cached, _)| cached.elapsed() < self.ttl);
+        entries.insert(key, (Instant::now(), git_info));
+    }
+
+    fn clear(&self) {
+        self.entries
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clear();
+    }
+}
+
+// How often a running git command is checked against its RunOptions
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+// Overrides the git binary commands are run with, unless set with Info::with_git_binary
+const GIT_BIN_VAR: &str = "COMMIT_INFO_GIT_BIN";
+
+// The variables that make git read another repo than the one it runs in, from `git rev-parse --local-env-vars`.
+// Git sets several of them for hooks, so they are cleared unless Info::with_inherited_git_env says otherwise
+const REPO_ENV_VARS: [&str; 13] = [
+    "GIT_DIR",
+    "GIT_WORK_TREE",
+    "GIT_INDEX_FILE",
+    "GIT_COMMON_DIR",
+    "GIT_OBJECT_DIRECTORY",
+    "GIT_ALTERNATE_OBJECT_DIRECTORIES",
+    "GIT_IMPLICIT_WORK_TREE",
+    "GIT_PREFIX",
+    "GIT_INTERNAL_SUPER_PREFIX",
+    "GIT_SHALLOW_FILE",
+    "GIT_GRAFT_FILE",
+    "GIT_NO_REPLACE_OBJECTS",
+    "GIT_REPLACE_REF_BASE",
+];
+
+// Set for the commands that may contact a remote, so they fail instead of waiting on a password or passphrase prompt
+const NO_PROMPT_ENV: [(&str, &str); 2] = [
+    ("GIT_TERMINAL_PROMPT", "0"),
+    ("GIT_SSH_COMMAND", "ssh -o BatchMode=yes"),
+];
+
+thread_local! {
+    // The options of the Info whose commands this thread is running, see Info::within
+    static RUN_OPTIONS: std::cell::RefCell<Option<RunOptions>> = const { std::cell::RefCell::new(None) };
+}
+
+// How git commands are spawned: the binary, the arguments passed before the subcommand, whether
+// they see REPO_ENV_VARS and the timeout and cancellation token they are held to
+#[derive(Debug, Clone)]
+struct RunOptions {
+    program: PathBuf,
+    global_args: Vec<String>,
+    inherit_git_env: bool,
+    deadline: Option<(Instant, Duration)>,
+    cancellation: Option<CancellationToken>,
+}
+
+impl Default for RunOptions {
+    fn default() -> Self {
+        RunOptions {
+            program: std::env::var_os(GIT_BIN_VAR)
+                .filter(|program| !program.is_empty())
+                .map(PathBuf::from)
+                .unwrap_or_else(|| "git".into()),
+            global_args: vec![],
+            inherit_git_env: false,
+            deadline: None,
+            cancellation: None,
+        }
+    }
+}
+
+impl RunOptions {
+    // The options of the calling thread
+    fn current() -> RunOptions {
+        RUN_OPTIONS
+            .with(|options| options.borrow().clone())
+            .unwrap_or_default()
+    }
+
+    // Whether the calling thread runs commands for an Info
+    fn active() -> bool {
+        RUN_OPTIONS.with(|options| options.borrow().is_some())
+    }
+
+    // Runs work with options applied to the calling thread, restoring the previous ones afterwards
+    fn enter<T>(options: RunOptions, work: impl FnOnce() -> T) -> T {
+        let previous = RUN_OPTIONS.with(|current| current.replace(Some(options)));
+        let result = work();
+        RUN_OPTIONS.with(|current| current.replace(previous));
+        result
+    }
+
+    // A git command in dir, waiting for its subcommand
+    fn command(&self, dir: &Path) -> Command {
+        let mut command = Command::new(&self.program);
+        command.current_dir(dir).args(&self.global_args);
+        if !self.inherit_git_env {
+            for var in REPO_ENV_VARS {
+                command.env_remove(var);
+            }
+        }
+        command
+    }
+
+    // Runs git with args in dir and returns its output, or what it printed to stderr if it failed
+    fn output(&self, dir: &Path, args: &[&str]) -> Result<String, CommitInfoError> {
+        command_output(args, self.raw_output(dir, args))
+    }
+
+    // Like output, but returns what git printed and its exit status even when it fails
+    fn raw_output(
+        &self,
+        dir: &Path,
+        args: &[&str],
+    ) -> Result<std::process::Output, CommitInfoError> {
+        let mut command = self.command(dir);
+        command.args(args);
+        self.execute(command)
+    }
+
+    // Like output, for commands that may contact a remote: they never read stdin nor prompt for credentials
+    fn remote_output(&self, dir: &Path, args: &[&str]) -> Result<String, CommitInfoError> {
+        let mut command = self.command(dir);
+        command.args(args).stdin(Stdio::null()).envs(NO_PROMPT_ENV);
+        command_output(args, self.execute(command))
+    }
+
+    // Runs command to completion, held to the limits if there are any
+    fn execute(&self, mut command: Command) -> Result<std::process::Output, CommitInfoError> {
+        match self.is_limited() {
+            true => self.run(command),
+            false => command.output().map_err(spawn_error),
+        }
+    }
+
+    // Whether `git --version` succeeds
+    fn git_runs(&self) -> bool {
+        Command::new(&self.program)
+            .arg("--version")
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .is_ok_and(|status| status.success())
+    }
+
+    fn is_limited(&self) -> bool {
+        self.deadline.is_some() || self.cancellation.is_some()
+    }
+
+    // Fails once the token is cancelled or the deadline has passed
+    fn check(&self) -> Result<(), CommitInfoError> {
+        if self
+            .cancellation
+            .as_ref()
+            .is_some_and(CancellationToken::is_cancelled)
+        {
+            return Err(CommitInfoError::Cancelled);
+        }
+        match self.deadline {
+            Some((deadline, timeout)) if Instant::now() >= deadline => {
+                Err(CommitInfoError::Timeout(timeout))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    // Runs command to completion, killing it as soon as the limits are exceeded
+    fn run(&self, mut command: Command) -> Result<std::process::Output, CommitInfoError> {
+        self.check()?;
+        let mut child = command
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(spawn_error)?;
+
+        // the pipes are drained on their own threads, which are left behind if git is killed,
+        // as anything git started, e.g. ssh, may still hold them open
+        let read = |pipe: Option<Box<dyn Read + Send>>| {
+            thread::spawn(move || {
+                let mut bytes = vec![];
+                if let Some(mut pipe) = pipe {
+                    let _ = pipe.read_to_end(&mut bytes);
+                }
+                bytes
+            })
+        };
+        let stdout = read(child.stdout.take().map(|pipe| Box::new(pipe) as _));
+        let stderr = read(child.stderr.take().map(|pipe| Box::new(pipe) as _));
+
+        let status = loop {
+            if let Some(status) = child.try_wait().map_err(anyhow::Error::from)? {
+                break status;
+            }
+            if let Err(e) = self.check() {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(e);
+            }
+            thread::sleep(POLL_INTERVAL);
+        };
+
+        let join = |reader: thread::JoinHandle<Vec<u8>>| reader.join().unwrap_or_default();
+        Ok(std::process::Output {
+            status,
+            stdout: join(stdout),
+            stderr: join(stderr),
+        })
+    }
+}
+
+// The `git status` invocation GitCli reads the status from, to be followed by any pathspecs
+const STATUS_ARGS: [&str; 8] = [
+    "-c",
+    "core.quotepath=false",
+    "status",
+    "--porcelain=v2",
+    "--branch",
+    "--ignored",
+    "-z",
+    "--",
+];
+
+// The full `git log` invocation listing the commits selected by query in LOG_FORMAT
+fn log_args(query: &LogQuery) -> Vec<String> {
+    let mut args = vec![
+        "log".to_string(),
+        format!("--format={}", log_format(query)),
+        "-z".into(),
+    ];
+    args.extend(notes_arg(query));
+    args.extend(DECORATE_REFS.map(String::from));
+    args.extend(selection_args(query));
+    args
+}
+
+// LOG_FORMAT with the placeholders query asks for. The signature ones are left empty unless signatures are read,
+// since they make git verify every signed commit, and the notes one unless notes are read
+fn log_format(query: &LogQuery) -> String {
+    let mut format = mailmap_format(LOG_FORMAT, query.use_mailmap);
+    if !query.notes {
+        format = format.replace("%N", "");
+    }
+    if query.signatures {
+        return format;
+    }
+    ["%GS", "%GK", "%G?", "%GF", "%GT"]
+        .iter()
+        .fold(format, |format, placeholder| {
+            format.replace(placeholder, "")
+        })
+}
+
+// The `git log` option showing the notes of the notes ref of query, or of the default notes ref. None unless notes are read
+fn notes_arg(query: &LogQuery) -> Option<String> {
+    match &query.notes_ref {
+        _ if !query.notes => None,
+        Some(notes_ref) => Some(format!("--notes={}", notes_ref)),
+        None => Some("--notes".into()),
+    }
+}
+
+// The `git log` invocation listing the files and lines changed by the commits selected by query, see parse_numstat
+fn stats_args(query: &LogQuery) -> Vec<String> {
+    let mut args = vec![
+        "log".to_string(),
+        "--numstat".into(),
+        "--no-renames".into(),
+        "--format=%x00%H".into(),
+    ];
+    args.extend(selection_args(query));
+    args
+}
+
+// The `git log` invocation listing the files changed by the commits selected by query, see parse_name_status
+fn files_args(query: &LogQuery) -> Vec<String> {
+    let mut args = vec![
+        "log".to_string(),
+        "--name-status".into(),
+        "-z".into(),
+        "-M".into(),
+        "--format=%x00%H".into(),
+    ];
+    args.extend(selection_args(query));
+    args
+}
+
+// The `git log` arguments selecting the commits of query
+fn selection_args(query: &LogQuery) -> Vec<String> {
+    let mut args = history_options(query);
+    args.push(format!("--skip={}", query.skip));
+    args.extend(query.max_count.map(|max| format!("--max-count={}", max)));
+    args.extend([query.revision.clone(), "--".into()]);
+    args.extend(query.pathspecs.iter().cloned());
+    args
+}
+
+// Sums the `git log --numstat --format=%x00%H` output of each commit, keyed by commit hash
+fn parse_numstat(output: &str) -> HashMap<String, CommitStats> {
+    let mut stats: HashMap<String, CommitStats> = HashMap::new();
+    let mut current = None;
+    for line in output.lines() {
+        // every commit starts with its NUL prefixed hash, followed by a line per changed file
+        if let Some(hash) = line.strip_prefix('\0') {
+            current = Some(stats.entry(hash.trim().to_string()).or_default());
+            continue;
+        }
+
+        let mut fields = line.splitn(3, '\t');
+        let fields = (
+            current.as_deref_mut(),
+            fields.next(),
+            fields.next(),
+            fields.next(),
+        );
+        let (commit, added, removed) = match fields {
+            (Some(commit), Some(added), Some(removed), Some(_)) => (commit, added, removed),
+            _ => continue,
+        };
+        commit.files_changed += 1;
+        // binary files are reported as "-"
+        commit.insertions += added.parse::<u64>().unwrap_or(0);
+        commit.deletions += removed.parse::<u64>().unwrap_or(0);
+    }
+    stats
+}
+
+// Reads the change with the given `--name-status` status from the NUL separated fields following it,
+// one path, or two for renames and copies. None when the paths are missing
+fn read_file_change<'a>(
+    status: &str,
+    fields: &mut impl Iterator<Item = &'a str>,
+) -> Option<FileChange> {
+    let change = match status.trim().chars().next() {
+        Some('A') => ChangeKind::Added,
+        Some('D') => ChangeKind::Deleted,
+        Some('R') => ChangeKind::Renamed,
+        Some('C') => ChangeKind::Copied,
+        // type changes and unmerged entries are reported as modifications
+        _ => ChangeKind::Modified,
+    };
+    let old_path = match change {
+        ChangeKind::Renamed | ChangeKind::Copied => Some(fields.next()?.to_string()),
+        _ => None,
+    };
+    Some(FileChange {
+        path: fields.next()?.to_string(),
+        change,
+        old_path,
+    })
+}
+
+// Reads the files each commit changed from `git log --name-status -z --format=%x00%H` output, keyed by commit hash
+fn parse_name_status(output: &str) -> HashMap<String, Vec<FileChange>> {
+    let mut files: HashMap<String, Vec<FileChange>> = HashMap::new();
+    let mut fields = output.split('\0');
+    let mut current = None;
+    while let Some(field) = fields.next() {
+        // every commit starts with an empty field, then its hash, then the status and paths of each changed file
+        if field.is_empty() {
+            current = fields
+                .next()
+                .map(|hash| files.entry(hash.trim().to_string()).or_default());
+            continue;
+        }
+        match (current.as_deref_mut(), read_file_change(field, &mut fields)) {
+            (Some(changes), Some(change)) => changes.push(change),
+            _ => break,
+        }
+    }
+    files
+}
+
+// Sets the files of each commit, sorted by path. Commits git listed no changes for changed nothing
+fn add_files(commits: &mut [Commit], mut files: HashMap<String, Vec<FileChange>>) {
+    for commit in commits {
+        let hash = commit.hash.as_deref().unwrap_or_default();
+        let mut changes = files.remove(hash).unwrap_or_default();
+        changes.sort_by(|a, b| a.path.cmp(&b.path));
+        commit.files = Some(changes);
+    }
+}
+
+// Sets the stats of each commit, commits git listed no changes for changed nothing
+fn add_stats(commits: &mut [Commit], mut stats: HashMap<String, CommitStats>) {
+    for commit in commits {
+        let hash = commit.hash.as_deref().unwrap_or_default();
+        commit.stats = Some(stats.remove(hash).unwrap_or_default());
+    }
+}
+
+// The `git log` options selecting which commits of the history are walked, leaving out the revision and paths
+fn history_options(query: &LogQuery) -> Vec<String> {
+    let mut options = vec![];
+    if !query.include_merges {
+        options.push("--no-merges".into());
+    }
+    if query.first_parent {
+        options.push("--first-parent".into());
+    }
+    // the exact timestamps --since and --until resolve to
+    options.extend(query.since.map(|since| format!("--max-age={}", since)));
+    options.extend(query.until.map(|until| format!("--min-age={}", until)));
+    if let Some(author) = &query.author {
+        // closer to the regex syntax the library backends use than git's default basic regexps
+        options.push("--extended-regexp".into());
+        options.push(format!("--author={}", author));
+    }
+    if let Some(committer) = &query.committer {
+        options.push("--extended-regexp".into());
+        options.push(format!("--committer={}", committer));
+    }
+    if let Some(grep) = &query.grep {
+        options.push("--extended-regexp".into());
+        options.push(format!("--grep={}", grep.regex()));
+        if grep.ignore_case {
+            options.push("--regexp-ignore-case".into());
+        }
+    }
+    options
+}
+
+// The email of a signer as reported by %GS: either `Name <email>` for gpg keys or the principal for ssh keys
+fn signer_email(signer: &str) -> &str {
+    match signer.rsplit_once('<') {
+        Some((_, email)) => email.trim_end_matches('>').trim(),
+        None => signer.trim(),
+    }
+}
+
+// Fails with GitBinaryMissing unless the git binary of the calling thread can be run.
+// Each binary is only checked once per process
+fn require_git() -> Result<()> {
+    static GIT_RUNS: OnceLock<Mutex<HashMap<PathBuf, bool>>> = OnceLock::new();
+
+    let options = RunOptions::current();
+    let mut checked = GIT_RUNS
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(|e| e.into_inner());
+    let runs = *checked
+        .entry(options.program.clone())
+        .or_insert_with(|| options.git_runs());
+    if runs {
+        Ok(())
+    } else {
+        Err(CommitInfoError::GitBinaryMissing.into())
+    }
+}
+
+// Runs git with args in dir with the options of the calling thread, see RunOptions::output
+fn git_output(dir: &Path, args: &[&str]) -> Result<String, CommitInfoError> {
+    RunOptions::current().output(dir, args)
+}
+
+// The error for a git command that couldn't be started
+fn spawn_error(error: std::io::Error) -> CommitInfoError {
+    match error.kind() {
+        std::io::ErrorKind::NotFound => CommitInfoError::GitBinaryMissing,
+        _ => anyhow::Error::from(error).into(),
+    }
+}
+
+// The stdout of the git command run with args, or the error it failed with
+fn command_output(
+    args: &[&str],
+    output: Result<std::process::Output, CommitInfoError>,
+) -> Result<String, CommitInfoError> {
+    let output = output?;
+
+    if !output.status.success() {
+        return Err(CommitInfoError::CommandFailed {
+            command: format!("git {}", args.join(" ")),
+            stderr: String::from_utf8_lossy(&output.stderr).trim().into(),
+        });
+    }
+
+    let mut stdout = String::from_utf8_lossy(&output.stdout).into_owned();
+    // like run_fun!, drop the final newline
+    if stdout.ends_with('\n') {
+        stdout.pop();
+    }
+    Ok(stdout)
+}
+
+// Whether dir is itself a git directory with `core.bare` set, as created by `git init --bare` or `git clone --bare`
+fn is_bare_repo(dir: &Path) -> bool {
+    if !dir.join("HEAD").is_file() || !dir.join("objects").is_dir() {
+        return false;
+    }
+    let config = std::fs::read_to_string(dir.join("config")).unwrap_or_default();
+    config.lines().any(|line| {
+        let line = line.trim().to_lowercase();
+        matches!(line.split_once('='), Some((key, value)) if key.trim() == "bare" && value.trim() == "true")
+    })
+}
+
+// Parses a date in git's ISO like format, e.g. `2014-08-29 16:09:40 -0600`
+#[cfg(feature = "dates")]
+fn parse_date(date: &str) -> Option<CommitDate> {
+    DateTime::parse_from_str(date.trim(), "%Y-%m-%d %H:%M:%S %z").ok()
+}
+
+// Without the dates feature, dates are kept as git reports them
+#[cfg(not(feature = "dates"))]
+fn parse_date(date: &str) -> Option<CommitDate> {
+    Some(date.trim().into())
+}
+
+// Waits for a scoped thread, passing on any panic
+fn join<T>(handle: thread::ScopedJoinHandle<'_, T>) -> T {
+    match handle.join() {
+        Ok(value) => value,
+        Err(e) => std::panic::resume_unwind(e),
+    }
+}
+
+// Turns a remote url like `git@github.com:owner/repo.git` or `https://github.com/owner/repo.git`
+// into its host and the https address of the project, e.g. `https://github.com/owner/repo`
+fn web_base(remote: &str) -> Option<(String, String)> {
+    let remote = remote.trim();
+    let remote = remote.strip_suffix(".git").unwrap_or(remote);
+
+    let (host, path) = match remote.split_once("://") {
+        // https://host/path, ssh://git@host:port/path
+        Some((_, rest)) => rest.split_once('/')?,
+        // scp like syntax, git@host:path
+        None => remote.split_once(':')?,
+    };
+    // drop any user and port
+    let host = host.rsplit('@').next()?;
+    let host = host.split(':').next()?.to_lowercase();
+
+    if host.is_empty() || path.is_empty() {
+        return None;
+    }
+    let base = format!("https://{}/{}", host, path.trim_matches('/'));
+    Some((host, base))
+}
+
+// Each commit is printed as LOG_FIELDS NUL separated fields: the hash, tree hash, author and committer dates, subject,
+// author and committer names and emails, the signer and signing key, the notes, the short hash, the parent hashes,
+// the signature status, the signing key's fingerprint and trust level, the tags and branches pointing at the commit
+// and the raw message.
+// Run with `-z` so each commit also ends with a NUL. Fields are printed as is, so a message may contain anything but NUL
+const LOG_FORMAT: &str =
+    "%H%x00%t%x00%ai%x00%ci%x00%s%x00%aN%x00%aE%x00%cN%x00%cE%x00%GS%x00%GK%x00%N%x00%h%x00%P%x00%G?%x00%GF%x00%GT%x00%D%x00%B";
+
+// The number of fields each commit has in LOG_FORMAT
+const LOG_FIELDS: usize = 19;
+
+// Limits the %D decorations of LOG_FORMAT to the refs the library backends read too
+const DECORATE_REFS: [&str; 3] = [
+    "--decorate-refs=refs/heads",
+    "--decorate-refs=refs/remotes",
+    "--decorate-refs=refs/tags",
+];
+
+// Parses `git log -z --format=LOG_FORMAT` output, failing on records that aren't valid commits
+fn parse_log(output: &str) -> Result<Vec<Commit>, CommitInfoError> {
+    let fields: Vec<&str> = output.split('\0').collect();
+    let records = fields.chunks_exact(LOG_FIELDS);
+    // only the empty string after the final NUL should be left over
+    if records
+        .remainder()
+        .iter()
+        .any(|field| !field.trim().is_empty())
+    {
+        return Err(CommitInfoError::ParseError(
+            "git log output ended in the middle of a commit".into(),
+        ));
+    }
+    records
+        .map(|record| {
+            parse_commit(record).ok_or_else(|| {
+                CommitInfoError::ParseError("git log listed a commit without a hash".into())
+            })
+        })
+        .collect()
+}
+
+// Parses the LOG_FIELDS fields of a single commit of `git log -z --format=LOG_FORMAT` output
+fn parse_commit<S: AsRef<str>>(fields: &[S]) -> Option<Commit> {
+    // on Windows lines may end with a CRLF
+    let field = |index: usize| fields.get(index).map(|f| f.as_ref().trim());
+    let text = |index: usize| field(index).filter(|f| !f.is_empty()).map(String::from);
+
+    let hash = text(0)?;
+    let message = fields.get(18).map(|f| f.as_ref()).unwrap_or_default();
+    let (tags, branches) = parse_decorations(field(17).unwrap_or_default());
+    // unsigned commits have no key
+    let signed_by = text(10).map(|key_id| Signer {
+        name: text(9),
+        key_id: Some(key_id),
+        fingerprint: text(15),
+        trust_level: field(16).and_then(TrustLevel::from_name),
+    });
+    Some(
+        Commit {
+            commit_date: field(3).and_then(parse_date),
+            author_date: field(2).and_then(parse_date),
+            commit_message: field(4).map(String::from),
+            commit_subject: field(4).map(String::from),
+            commit_body: parse_body(message),
+            author_name: field(5).map(String::from),
+            author_email: field(6).map(String::from),
+            committer_name: field(7).map(String::from),
+            committer_email: field(8).map(String::from),
+            tree_hash: text(1),
+            hash: Some(hash),
+            short_hash: text(12),
+            parent_hashes: field(13)
+                .unwrap_or_default()
+                .split_whitespace()
+                .map(String::from)
+                .collect(),
+            tags,
+            branches,
+            stats: None,
+            files: None,
+            author_tz_offset_minutes: field(2).and_then(parse_offset),
+            committer_tz_offset_minutes: field(3).and_then(parse_offset),
+            signed_off_by: vec![],
+            trailers: parse_trailers(message),
+            conventional: None,
+            issue_refs: vec![],
+            notes: text(11),
+            signature: field(14).and_then(SignatureStatus::from_code),
+            signed_by,
+        }
+        .with_message_fields(),
+    )
+}
+
+// Words for a time `seconds` ago, rounded the way `git log --date=relative` rounds them
+#[cfg(feature = "dates")]
+fn relative_time(seconds: i64) -> String {
+    let units = |count: i64, unit: &str| match count {
+        1 => format!("1 {}", unit),
+        _ => format!("{} {}s", count, unit),
+    };
+    if seconds < 0 {
+        return "in the future".into();
+    }
+    // the same thresholds and rounding as git's show_date_relative
+    let minutes = (seconds + 30) / 60;
+    let hours = (minutes + 30) / 60;
+    let days = (hours + 12) / 24;
+    let ago = if seconds < 90 {
+        units(seconds, "second")
+    } else if minutes < 90 {
+        units(minutes, "minute")
+    } else if hours < 36 {
+        units(hours, "hour")
+    } else if days < 14 {
+        units(days, "day")
+    } else if days < 70 {
+        units((days + 3) / 7, "week")
+    } else if days < 365 {
+        units((days + 15) / 30, "month")
+    } else if days < 1825 {
+        // a year and some months, rounded to whole months
+        let total_months = (days * 12 * 2 + 365) / (365 * 2);
+        let (years, months) = (total_months / 12, total_months % 12);
+        match months {
+            0 => units(years, "year"),
+            _ => format!("{}, {}", units(years, "year"), units(months, "month")),
+        }
+    } else {
+        units((days + 183) / 365, "year")
+    };
+    format!("{} ago", ago)
+}
+
+// Reads what `git fsck --connectivity-only` printed: `dangling <type> <hash>` and `missing <type> <hash>` on stdout,
+// errors on stderr along with notices, e.g. about an empty repo, which are left out
+fn parse_fsck(stdout: &str, stderr: &str) -> IntegrityReport {
+    let mut report = IntegrityReport::default();
+    for line in stdout.lines() {
+        let mut words = line.split_whitespace();
+        let (found, hash) = match (words.next(), words.next(), words.next()) {
+            (Some(found), Some(_), Some(hash)) => (found, hash.to_string()),
+            _ => continue,
+        };
+        match found {
+            "dangling" => report.dangling.push(hash),
+            "missing" => report.missing.push(hash),
+            _ => report.errors.push(line.into()),
+        }
+    }
+    report.errors.extend(
+        stderr
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with("notice:"))
+            .map(String::from),
+    );
+    report
+}
+
+// Splits the output of `git describe --tags --always --dirty --long`: either `<tag>-<count>-g<hash>`
+// or just the hash when there are no tags, each followed by `-dirty` when the working tree has changes
+fn parse_describe(description: &str) -> Describe {
+    let (description, dirty) = match description.strip_suffix("-dirty") {
+        Some(description) => (description, true),
+        None => (description, false),
+    };
+
+    // tags may contain dashes themselves, so split from the end
+    let mut parts = description.rsplitn(3, '-');
+    let parts = (parts.next(), parts.next(), parts.next());
+    if let (Some(hash), Some(count), Some(tag)) = parts {
+        if let (Some(hash), Ok(count)) = (hash.strip_prefix('g'), count.parse()) {
+            return Describe {
+                tag: Some(tag.into()),
+                commits_since_tag: count,
+                short_hash: hash.into(),
+                dirty,
+            };
+        }
+    }
+    Describe {
+        tag: None,
+        commits_since_tag: 0,
+        short_hash: description.into(),
+        dirty,
+    }
+}
+
+// Cuts path to its first depth components, keeping the trailing slash of directories
+fn truncate_path(path: &str, depth: usize) -> String {
+    match path.match_indices('/').nth(depth - 1) {
+        Some((end, _)) => path[..=end].into(),
+        None => path.into(),
+    }
+}
+
+// Splits %D decorations, e.g. `HEAD -> main, tag: v1.0, origin/main, origin/HEAD`, into sorted tags and branches
+fn parse_decorations(decorations: &str) -> (Vec<String>, Vec<String>) {
+    let (mut tags, mut branches) = (vec![], vec![]);
+    for decoration in decorations.split(", ").map(str::trim) {
+        let decoration = decoration.strip_prefix("HEAD -> ").unwrap_or(decoration);
+        match decoration.strip_prefix("tag: ") {
+            Some(tag) => tags.push(tag.to_string()),
+            // HEAD and the remotes' HEADs only point at the branches listed anyway
+            None if decoration.is_empty()
+                || decoration == "HEAD"
+                || decoration.ends_with("/HEAD") => {}
+            None => branches.push(decoration.to_string()),
+        }
+    }
+    tags.sort();
+    branches.sort();
+    (tags, branches)
+}
+
+// The tags and branches each commit is decorated with, keyed by commit hash, from the full names of refs
+// and the hashes of the commits they point at. Used by the library backends to match %D
+#[cfg(any(feature = "backend-git2", feature = "backend-gix"))]
+fn decorations(
+    refs: impl IntoIterator<Item = (String, String)>,
+) -> HashMap<String, (Vec<String>, Vec<String>)> {
+    let mut decorations: HashMap<String, (Vec<String>, Vec<String>)> = HashMap::new();
+    for (name, hash) in refs {
+        let (tags, branches) = decorations.entry(hash).or_default();
+        if let Some(tag) = name.strip_prefix("refs/tags/") {
+            tags.push(tag.into());
+        } else if let Some(branch) = name
+            .strip_prefix("refs/heads/")
+            .or_else(|| name.strip_prefix("refs/remotes/"))
+            .filter(|branch| !branch.ends_with("/HEAD"))
+        {
+            branches.push(branch.into());
+        }
+    }
+    for (tags, branches) in decorations.values_mut() {
+        tags.sort();
+        branches.sort();
+    }
+    decorations
+}
+
+// The body of a raw commit message, what follows the subject paragraph, as git prints it with %b
+fn parse_body(message: &str) -> Option<String> {
+    let message = message.replace("\r\n", "\n");
+    let (_, body) = message.trim_start_matches('\n').split_once("\n\n")?;
+    Some(body.trim().to_string()).filter(|body| !body.is_empty())
+}
+
+// Splits `Name <email>` into its name and email
+fn parse_identity(value: &str) -> Option<(String, String)> {
+    let (name, email) = value.trim().split_once('<')?;
+    let email = email.strip_suffix('>')?;
+    Some((name.trim().to_string(), email.trim().to_string()))
+}
+
+// The `Key: value` trailers of a raw commit message. Like `git interpret-trailers --parse`, they are read from the
+// last paragraph of the body, and only when every line of it is a trailer or the indented continuation of one
+fn parse_trailers(message: &str) -> Vec<(String, String)> {
+    let body = parse_body(message).unwrap_or_default();
+    let paragraph = body.rsplit("\n\n").next().unwrap_or_default();
+
+    let mut trailers: Vec<(String, String)> = vec![];
+    for line in paragraph.lines() {
+        if line.starts_with([' ', '\t']) {
+            match trailers.last_mut() {
+                Some((_, value)) => {
+                    value.push(' ');
+                    value.push_str(line.trim());
+                    continue;
+                }
+                None => return vec![],
+            }
+        }
+        let trailer = line.split_once(':').filter(|(key, _)| {
+            !key.is_empty() && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+        });
+        match trailer {
+            Some((key, value)) => trailers.push((key.into(), value.trim().into())),
+            None => return vec![],
+        }
+    }
+    trailers
+}
+
+// Finds the references Commit::references lists
+fn parse_references(message: &str) -> Vec<IssueRef> {
+    static REFERENCE: OnceLock<Regex> = OnceLock::new();
+    let reference = REFERENCE.get_or_init(|| {
+        Regex::new(
+            r"(?:\b(?i:(close[sd]?|fix(?:e[sd])?|resolve[sd]?))\b:?[ \t]+)?(#\d+\b|\b[A-Z][A-Z0-9]+-\d+\b)",
+        )
+        .unwrap()
+    });
+
+    reference
+        .captures_iter(message)
+        .map(|captures| {
+            let found = &captures[2];
+            let kind = match found {
+                _ if found.starts_with('#') => IssueRefKind::Number,
+                _ if found.starts_with("GH-") => IssueRefKind::GitHub,
+                _ => IssueRefKind::Tracker,
+            };
+            IssueRef {
+                reference: found.into(),
+                kind,
+                keyword: captures.get(1).map(|keyword| keyword.as_str().into()),
+                position: captures.get(2).unwrap().start(),
+            }
+        })
+        .collect()
+}
+
+// Reads commits from a running `git log` one record at a time.
+// The process is killed when the iterator is dropped before reaching the end of the log
+struct CommitIter {
+    child: Option<Child>,
+    stdout: Option<BufReader<ChildStdout>>,
+    bytes_read: usize,
+}
+
+impl CommitIter {
+    fn empty() -> CommitIter {
+        CommitIter {
+            child: None,
+            stdout: None,
+            bytes_read: 0,
+        }
+    }
+
+    // Reaps the finished process, reporting an error if it failed
+    fn finish(&mut self) -> Option<Result<Commit>> {
+        self.stdout = None;
+        let status = self.child.take()?.wait();
+        match status {
+            Ok(status) if status.success() => None,
+            Ok(status) => Some(Err(anyhow!("git log failed with {}", status))),
+            Err(e) => Some(Err(e.into())),
+        }
+    }
+}
+
+impl Iterator for CommitIter {
+    type Item = Result<Commit>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut field = vec![];
+        let mut fields = Vec::with_capacity(LOG_FIELDS);
+        loop {
+            field.clear();
+            let read = match self.stdout.as_mut()?.read_until(b'\0', &mut field) {
+                Ok(read) => read,
+                Err(e) => {
+                    self.stdout = None;
+                    return Some(Err(e.into()));
+                }
+            };
+            if read == 0 {
+                return self.finish();
+            }
+            self.bytes_read += read;
+
+            let value = field.strip_suffix(b"\0").unwrap_or(&field);
+            fields.push(String::from_utf8_lossy(value).into_owned());
+            if fields.len() < LOG_FIELDS {
+                continue;
+            }
+            return Some(parse_commit(&fields).ok_or_else(|| {
+                CommitInfoError::ParseError("git log listed a commit without a hash".into()).into()
+            }));
+        }
+    }
+}
+
+impl Drop for CommitIter {
+    fn drop(&mut self) {
+        self.stdout = None;
+        if let Some(mut child) = self.child.take() {
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+    }
+}
+
+// Formats a git timestamp the way %ci does, e.g. `2014-08-29 16:09:40 -0600`
+#[cfg(any(feature = "backend-git2", feature = "backend-gix"))]
+fn git_date(seconds: i64, offset: i32) -> String {
+    let local = seconds + offset as i64 * 60;
+    let (days, seconds) = (local.div_euclid(86_400), local.rem_euclid(86_400));
+
+    // civil date from days since 1970-01-01, see http://howardhinnant.github.io/date_algorithms.html
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}:{:02} {}{:02}{:02}",
+        year,
+        month,
+        day,
+        seconds / 3600,
+        seconds % 3600 / 60,
+        seconds % 60,
+        if offset < 0 { '-' } else { '+' },
+        offset.abs() / 60,
+        offset.abs() % 60
+    )
+}
+
+// Reads the UTC offset in minutes from the end of a git date, e.g. 330 from `2014-08-29 16:09:40 +0530`
+fn parse_offset(date: &str) -> Option<i32> {
+    let offset = date.trim().rsplit(' ').next()?;
+    let (sign, digits) = match offset.split_at_checked(1)? {
+        ("+", digits) => (1, digits),
+        ("-", digits) => (-1, digits),
+        _ => return None,
+    };
+    if digits.len() != 4 {
+        return None;
+    }
+
+    let hours: i32 = digits[..2].parse().ok()?;
+    let minutes: i32 = digits[2..].parse().ok()?;
+    Some(sign * (hours * 60 + minutes))
+}
+
+// Serializes path as a string even when it isn't valid UTF-8, which serde refuses to do for a Path
+fn lossy_path<S>(path: &Path, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    serializer.serialize_str(&path.to_string_lossy())
+}
+
+#[cfg(feature = "dates")]
+mod my_date_format {
+    use super::DisplayTimezone;
+    use chrono::{DateTime, FixedOffset, NaiveDateTime, TimeZone};
+    use serde::{self, Deserialize, Deserializer, Serializer};
+    use std::fmt::{Display, Write};
+
+    // 2014-08-29T16:09:40-06:00
+
+    // The format dates are rendered in with a display timezone, e.g. `2014-08-29 22:09:40 UTC`
+    const FORMAT: &str = "%Y-%m-%d %H:%M:%S %Z";
+    // The format git uses for %ci, which dates were serialized in before RFC 3339
+    const GIT_FORMAT: &str = "%Y-%m-%d %H:%M:%S %z";
+
+    // Renders date in the given display timezone and strftime format. The display timezone defaults to the date's own
+    // offset and the format to RFC 3339, or to FORMAT with a display timezone
+    pub fn format_in(
+        date: &DateTime<FixedOffset>,
+        tz: Option<&DisplayTimezone>,
+        format: Option<&str>,
+    ) -> String {
+        match tz {
+            None => render(date, format),
+            Some(DisplayTimezone::Fixed(offset)) => {
+                render(&date.with_timezone(offset), format.or(Some(FORMAT)))
+            }
+            #[cfg(feature = "tz")]
+            Some(DisplayTimezone::Named(tz)) => {
+                render(&date.with_timezone(tz), format.or(Some(FORMAT)))
+            }
+        }
+    }
+
+    // Renders date in format, or in RFC 3339 when there is none or chrono can't render it
+    fn render<Tz: TimeZone>(date: &DateTime<Tz>, format: Option<&str>) -> String
+    where
+        Tz::Offset: Display,
+    {
+        let mut rendered = String::new();
+        match format {
+            // an invalid format only fails once it is written
+            Some(format) if write!(rendered, "{}", date.format(format)).is_ok() => rendered,
+            _ => date.to_rfc3339(),
+        }
+    }
+
+    // The signature of a serialize_with function must follow the pattern:
+    //
+    //    fn serialize<S>(&T, S) -> Result<S::Ok, S::Error>
+    //    where
+    //        S: Serializer
+    //
+    // although it may also be generic over the input types T.
+    pub fn serialize<S>(
+        date: &Option<DateTime<FixedOffset>>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match date {
+            Some(dt) => serializer.serialize_str(&dt.to_rfc3339()),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    // The signature of a deserialize_with function must follow the pattern:
+    //
+    //    fn deserialize<'de, D>(D) -> Result<T, D::Error>
+    //    where
+    //        D: Deserializer<'de>
+    //
+    // although it may also be generic over the output types T.
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Option<DateTime<FixedOffset>>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let Some(s) = Option::<String>::deserialize(deserializer)? else {
+            return Ok(None);
+        };
+        // what earlier versions wrote for a missing date
+        if s == "null" {
+            return Ok(None);
+        }
+
+        // earlier versions wrote dates the way git reports them, and before that in UTC
+        let dt = match DateTime::parse_from_rfc3339(&s) {
+            Ok(dt) => dt,
+            Err(_) => match DateTime::parse_from_str(&s, GIT_FORMAT) {
+                Ok(dt) => dt,
+                Err(_) => NaiveDateTime::parse_from_str(&s, FORMAT)
+                    .map_err(serde::de::Error::custom)?
+                    .and_utc()
+                    .fixed_offset(),
+            },
+        };
+
+        Ok(Some(dt))
+    }
+}
+
+// To successfully run tests, first create a "test_project" directory at the home of this crate
+// Do so by running cargo new test_project
+// It is not included so you will need to create it yourself
+#[cfg(test)]
+mod tests {
+
+    use super::{
+        CancellationToken, ChangeKind, Commit, CommitInfoError, CommitSearch, FileChange, Info,
+        IntegrityReport, IssueRefKind, PathStatus, SignatureStatus, Signer, Status, StatusCounts,
+        TrustLevel,
+    };
+    use cmd_lib::{run_cmd, run_fun};
+    use std::{
+        env, fs, thread,
+        time::{Duration, Instant},
+    };
+
+    fn test_dir() -> String {
+        let mut path = env::current_dir().unwrap();
+        path.push("test_project");
+
+        path.to_string_lossy().to_string()
+    }
+
+    // Creates a fresh, empty repo under the system temp dir
+    fn fixture(name: &str) -> String {
+        let mut path = env::temp_dir();
+        path.push(format!("commit_info_{}_{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&path);
+        fs::create_dir_all(&path).unwrap();
+
+        let dir = path.to_string_lossy().to_string();
+        run_cmd!(
+            cd ${dir};
+            git init -q -b main;
+            git config user.name "Test User";
+            git config user.email "test@example.com";
+            git config commit.gpgsign false;
+            git config tag.gpgsign false;
+        )
+        .unwrap();
+
+        dir
+    }
+
+    // Writes `file` and commits it with the given message and author
+    fn commit_as(dir: &str, file: &str, message: &str, name: &str, email: &str) {
+        fs::write(format!("{}/{}", dir, file), message).unwrap();
+        let author = format!("{} <{}>", name, email);
+        run_cmd!(
+            cd ${dir};
+            git add -A;
+            git commit -q -m $message --author $author;
+        )
+        .unwrap();
+    }
+
+    // Commits `file` with both the author and committer date set to `date`
+    fn commit_at(dir: &str, file: &str, message: &str, date: &str) {
+        fs::write(format!("{}/{}", dir, file), message).unwrap();
+        run_cmd!(
+            cd ${dir};
+            git add -A;
+            GIT_AUTHOR_DATE=$date GIT_COMMITTER_DATE=$date git commit -q -m $message;
+        )
+        .unwrap();
+    }
+
+    // A repo where `git status` takes at least 10 seconds, as it runs a slow clean filter on the changed file
+    fn slow_status(name: &str) -> String {
+        let dir = fixture(name);
+        commit(&dir, "a.txt", "first");
+        run_cmd!(cd ${dir}; git config filter.slow.clean "sleep 10; cat").unwrap();
+        fs::write(format!("{}/.git/info/attributes", dir), "* filter=slow\n").unwrap();
+        fs::write(format!("{}/a.txt", dir), "changed").unwrap();
+        dir
+    }
+
+    // Clones `source` into a new fixture dir so that the copy has an origin remote
+    fn clone(source: &str, name: &str) -> String {
+        let dir = fixture(name);
+        fs::remove_dir_all(&dir).unwrap();
+        run_cmd!(
+            git clone -q $source $dir;
+            cd ${dir};
+            git config user.name "Test User";
+            git config user.email "test@example.com";
+        )
+        .unwrap();
+        dir
+    }
+
+    fn commit(dir: &str, file: &str, message: &str) {
+        commit_as(dir, file, message, "Test User", "test@example.com");
+    }
+
+    // Creates an ssh signing key for each name, allowed to sign as `{name}@example.com`, and returns their paths
+    fn ssh_signers(dir: &str, names: &[&str]) -> Vec<String> {
+        let keys = format!("{}/.keys", dir);
+        fs::create_dir_all(&keys).unwrap();
+        fs::write(format!("{}/.gitignore", dir), ".keys\n").unwrap();
+
+        let mut paths = vec![];
+        let mut signers = String::new();
+        for name in names {
+            let key = format!("{}/{}", keys, name);
+            run_cmd!(ssh-keygen -q -t ed25519 -N "" -C $name -f $key).unwrap();
+            let public = fs::read_to_string(format!("{}.pub", key)).unwrap();
+            signers.push_str(&format!("{}@example.com {}", name, public));
+            paths.push(key);
+        }
+        let allowed = format!("{}/allowed_signers", keys);
+        fs::write(&allowed, signers).unwrap();
+        run_cmd!(cd ${dir}; git config gpg.format ssh; git config gpg.ssh.allowedSignersFile $allowed)
+            .unwrap();
+        paths
+    }
+
+    // Commits `file` signed with the ssh `key`
+    fn commit_signed(dir: &str, file: &str, message: &str, key: &str) {
+        fs::write(format!("{}/{}", dir, file), message).unwrap();
+        run_cmd!(cd ${dir}; git add -A; git -c user.signingkey=$key commit -q -S -m $message)
+            .unwrap();
+    }
+
+    #[test]
+    fn it_works() {
+        let dir = test_dir();
+
+        let info = Info::new(&dir)
+            .status_info()
+            .expect("Unable to get status info")
+            .commit_info()
+            .expect("Unable to get commit info");
+
+        assert_eq!(None, info.commits);
+        assert_eq!(Some(true), info.status.expect("err").git_dirty);
+    }
+
+    #[test]
+    fn ignored_files_do_not_make_repo_dirty() {
+        let dir = fixture("ignored");
+        fs::write(format!("{}/.gitignore", dir), "*.log\n").unwrap();
+        commit(&dir, "a.txt", "first");
+        fs::write(format!("{}/debug.log", dir), "noise").unwrap();
+
+        let status = Info::new(&dir).status_info().unwrap().status.unwrap();
+
+        assert_eq!(vec!["debug.log".to_string()], status.ignored);
+        assert_eq!(Some(&false), status.summary.get("is_dirty"));
+        assert_eq!(Some(&false), status.summary.get("is_modified"));
+        assert_eq!(Some(false), status.git_dirty);
+    }
+
+    #[test]
+    fn failed_diff_leaves_dirty_state_unknown() {
+        let mut status = Status::default();
+        let diff = Err(CommitInfoError::CommandFailed {
+            command: "git diff --stat".into(),
+            stderr: "fatal: unable to read index".into(),
+        });
+
+        status.summarize("1 .M N... 100644 100644 100644 e69de29 e69de29 a.txt", diff);
+
+        assert_eq!(None, status.git_dirty);
+        assert_eq!(
+            Some("git diff --stat failed: fatal: unable to read index".into()),
+            status.error
+        );
+        assert_eq!(None, status.summary.get("is_dirty"));
+    }
+
+    #[test]
+    fn dirty_paths_lists_changed_files() {
+        let dir = fixture("dirty_paths");
+        commit(&dir, "a.txt", "first");
+        commit(&dir, "b.txt", "second");
+        fs::write(format!("{}/a.txt", dir), "changed").unwrap();
+        fs::write(format!("{}/c.txt", dir), "staged").unwrap();
+        run_cmd!(cd ${dir}; git add c.txt).unwrap();
+        fs::write(format!("{}/d.txt", dir), "untracked").unwrap();
+
+        let info = Info::new(&dir);
+        assert!(info.dirty_paths().is_empty());
+
+        let info = info.status_info().unwrap();
+        let status = info.status.as_ref().unwrap();
+
+        assert_eq!(vec!["c.txt".to_string()], status.staged);
+        assert_eq!(vec!["a.txt".to_string()], status.unstaged);
+        assert_eq!(vec!["d.txt".to_string()], status.untracked);
+        assert_eq!(vec!["a.txt", "c.txt", "d.txt"], info.dirty_paths());
+    }
+
+    #[test]
+    fn is_clean_reports_clean_dirty_and_unknown() {
+        let dir = fixture("is_clean");
+        commit(&dir, "a.txt", "first");
+
+        let info = Info::new(&dir);
+        assert!(info.is_clean());
+        assert!(info.status_info().unwrap().status.unwrap().is_clean());
+
+        fs::write(format!("{}/a.txt", dir), "changed").unwrap();
+        assert!(!info.is_clean());
+        assert!(!info.status_info().unwrap().is_clean());
+
+        // unknown status is never clean
+        let mut status = Status::default();
+        status.summarize("", Err(CommitInfoError::Cancelled));
+        assert!(!status.is_clean());
+
+        let not_git = env::temp_dir().to_string_lossy().to_string();
+        assert!(!Info::new(&not_git).is_clean());
+    }
+
+    #[test]
+    fn diff_refs_lists_changes_between_commits() {
+        let dir = fixture("diff_refs");
+        commit(&dir, "a.txt", "first");
+        fs::write(
+            format!("{}/moved.txt", dir),
+            "some content\nthat is long enough\n",
+        )
+        .unwrap();
+        commit(&dir, "b.txt", "second");
+
+        fs::write(format!("{}/a.txt", dir), "changed").unwrap();
+        fs::write(format!("{}/c.txt", dir), "new").unwrap();
+        run_cmd!(cd ${dir}; git mv moved.txt renamed.txt).unwrap();
+        run_cmd!(cd ${dir}; git add -A; git commit -q -m third).unwrap();
+
+        let mut changes = Info::new(&dir).diff_refs("HEAD~1", "HEAD").unwrap();
+        changes.sort_by(|a, b| a.path.cmp(&b.path));
+
+        let change = |path: &str, change, old_path: Option<&str>| FileChange {
+            path: path.into(),
+            change,
+            old_path: old_path.map(String::from),
+        };
+        assert_eq!(
+            vec![
+                change("a.txt", ChangeKind::Modified, None),
+                change("c.txt", ChangeKind::Added, None),
+                change("renamed.txt", ChangeKind::Renamed, Some("moved.txt")),
+            ],
+            changes
+        );
+        assert!(Info::new(&dir).diff_refs("nope", "HEAD").is_err());
+    }
+
+    fn messages(info: &Info) -> Vec<String> {
+        info.commits
+            .iter()
+            .flatten()
+            .map(|c| c.commit_message.clone().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn commit_info_for_branch_and_tag() {
+        let dir = fixture("commit_info_for");
+        commit(&dir, "a.txt", "first");
+        run_cmd!(cd ${dir}; git tag v1; git checkout -q -b feature).unwrap();
+        commit(&dir, "b.txt", "on feature");
+        run_cmd!(cd ${dir}; git checkout -q main).unwrap();
+        commit(&dir, "c.txt", "on main");
+
+        let info = Info::new(&dir);
+
+        let feature = info.commit_info_for("feature").unwrap();
+        assert_eq!(Some("feature".to_string()), feature.branch);
+        assert_eq!(vec!["on feature", "first"], messages(&feature));
+
+        let tagged = info.commit_info_for("v1").unwrap();
+        assert_eq!(vec!["first"], messages(&tagged));
+
+        assert!(info.commit_info_for("no-such-ref").is_err());
+    }
+
+    #[test]
+    fn commits_are_paginated() {
+        let dir = fixture("commits_page");
+        for i in 1..=5 {
+            commit(&dir, &format!("{}.txt", i), &format!("commit {}", i));
+        }
+        let info = Info::new(&dir);
+        let page = |skip, take| -> Vec<String> {
+            info.commits_page(skip, take)
+                .unwrap()
+                .commits
+                .into_iter()
+                .map(|c| c.commit_message.unwrap())
+                .collect()
+        };
+
+        assert_eq!(vec!["commit 5", "commit 4"], page(0, 2));
+        assert_eq!(vec!["commit 3", "commit 2"], page(2, 2));
+        assert_eq!(vec!["commit 1"], page(4, 2));
+        assert!(page(6, 2).is_empty());
+        assert!(page(0, 0).is_empty());
+
+        let paged = info.commits_page(0, 2).unwrap();
+        assert_eq!(2, paged.commits.len());
+        assert!(paged.has_more);
+        assert_eq!(Some(5), paged.total_hint);
+
+        let last = info.commits_page(3, 2).unwrap();
+        assert_eq!(2, last.commits.len());
+        assert_eq!(Some("commit 1".into()), last.commits[1].commit_message);
+        assert!(!last.has_more);
+        assert_eq!(Some(5), last.total_hint);
+
+        let past_the_end = info.commits_page(6, 2).unwrap();
+        assert!(past_the_end.commits.is_empty() && !past_the_end.has_more);
+        assert_eq!(Some(5), past_the_end.total_hint);
+
+        let empty = Info::new(fixture("commits_page_empty")).commits_page(0, 2);
+        assert_eq!(Some(0), empty.unwrap().total_hint);
+    }
+
+    #[test]
+    fn head_commit_is_read() {
+        let dir = fixture("head_commit");
+        assert_eq!(None, Info::new(&dir).head_commit().unwrap());
+
+        commit(&dir, "a.txt", "first");
+        run_cmd!(cd ${dir}; git checkout -q -b topic).unwrap();
+        commit_as(&dir, "b.txt", "second", "Ann", "ann@example.com");
+        run_cmd!(cd ${dir}; git checkout -q main).unwrap();
+        commit(&dir, "c.txt", "third");
+        run_cmd!(cd ${dir}; git merge -q --no-ff -m "merge topic" topic).unwrap();
+
+        let head = Info::new(&dir).head_commit().unwrap().unwrap();
+        assert_eq!(Some("merge topic".into()), head.commit_message);
+        assert_eq!(run_fun!(cd ${dir}; git rev-parse HEAD).ok(), head.hash);
+        // the history filters would skip HEAD, but it is still read
+        let filtered = Info::new(&dir).with_merges(false).with_author("^Ann");
+        assert_eq!(head, filtered.head_commit().unwrap().unwrap());
+    }
+
+    #[cfg(feature = "dates")]
+    #[test]
+    fn relative_times_read_like_git() {
+        use super::relative_time;
+
+        let day = 86_400;
+        assert_eq!("1 second ago", relative_time(1));
+        assert_eq!("89 seconds ago", relative_time(89));
+        assert_eq!("2 minutes ago", relative_time(90));
+        assert_eq!("3 hours ago", relative_time(3 * 3600 + 10));
+        assert_eq!("3 days ago", relative_time(3 * day));
+        assert_eq!("2 weeks ago", relative_time(14 * day));
+        assert_eq!("5 months ago", relative_time(150 * day));
+        assert_eq!("1 year ago", relative_time(366 * day));
+        assert_eq!("1 year, 2 months ago", relative_time(426 * day));
+        assert_eq!("10 years ago", relative_time(3650 * day));
+        assert_eq!("in the future", relative_time(-5));
+
+        let dir = fixture("relative_time");
+        commit_at(&dir, "a.txt", "first", "2014-08-29T16:09:40-06:00");
+        let commit = Info::new(&dir).head_commit().unwrap().unwrap();
+        assert!(commit.age().unwrap().num_days() > 3650);
+        assert!(commit.relative_time().unwrap().ends_with(" years ago"));
+        assert_eq!(None, super::Commit::default().relative_time());
+    }
+
+    #[test]
+    fn commits_are_searched() {
+        let dir = fixture("search_commits");
+        commit(&dir, "a.txt", "fix CVE-2024-1234\n\nreported by Ann");
+        commit(&dir, "b.txt", "docs: mention cve-2024-1234");
+        commit_as(&dir, "c.txt", "bump to 1.2", "Bob", "bob@example.com");
+        commit(&dir, "d.txt", "bump to 1x2");
+        let info = Info::new(&dir);
+        let found = |search: CommitSearch| -> Vec<String> {
+            info.search_commits(search)
+                .unwrap()
+                .into_iter()
+                .map(|c| c.commit_message.unwrap())
+                .collect()
+        };
+
+        assert_eq!(vec!["fix CVE-2024-1234"], found("CVE-2024-1234".into()));
+        assert_eq!(
+            vec!["docs: mention cve-2024-1234", "fix CVE-2024-1234"],
+            found(CommitSearch::new("cve-2024-1234").with_ignore_case(true))
+        );
+        // the body is searched too, and ^ matches at the start of each line
+        assert_eq!(vec!["fix CVE-2024-1234"], found("^reported by".into()));
+        assert_eq!(
+            vec!["bump to 1x2", "bump to 1.2"],
+            found("bump to 1.2".into())
+        );
+        assert_eq!(
+            vec!["bump to 1.2"],
+            found(CommitSearch::new("bump to 1.2").with_fixed_string(true))
+        );
+        assert!(found("no such thing".into()).is_empty());
+
+        let bobs = info.clone().with_author("^bob");
+        assert!(bobs.search_commits("bump").unwrap().is_empty());
+        let bobs = bobs
+            .search_commits(CommitSearch::new("BUMP").with_ignore_case(true))
+            .unwrap();
+        assert_eq!(Some("Bob".into()), bobs[0].author_name);
+        assert_eq!(1, bobs.len());
+    }
+
+    #[test]
+    fn path_status_is_relative_to_repo_root() {
+        let dir = fixture("path_status");
+        fs::write(format!("{}/.gitignore", dir), "*.log\n").unwrap();
+        commit(&dir, "tracked.txt", "first");
+        fs::write(format!("{}/debug.log", dir), "noise").unwrap();
+        fs::write(format!("{}/new.txt", dir), "new").unwrap();
+
+        let info = Info::new(&dir);
+
+        assert_eq!(
+            PathStatus::Tracked,
+            info.path_status("tracked.txt").unwrap()
+        );
+        assert_eq!(PathStatus::Ignored, info.path_status("debug.log").unwrap());
+        assert_eq!(PathStatus::Untracked, info.path_status("new.txt").unwrap());
+        assert_eq!(
+            PathStatus::NotFound,
+            info.path_status("missing.txt").unwrap()
+        );
+    }
+
+    #[test]
+    fn untracked_and_ignored_files_are_listed() {
+        let dir = fixture("other_files");
+        fs::write(format!("{}/.gitignore", dir), "node_modules/\n").unwrap();
+        fs::create_dir_all(format!("{}/src", dir)).unwrap();
+        commit(&dir, "src/lib.rs", "first");
+        fs::write(format!("{}/b.txt", dir), "new").unwrap();
+        fs::write(format!("{}/src/new.rs", dir), "new").unwrap();
+        fs::create_dir_all(format!("{}/build/out", dir)).unwrap();
+        fs::write(format!("{}/build/out/deep.txt", dir), "new").unwrap();
+        fs::create_dir_all(format!("{}/node_modules/x/y", dir)).unwrap();
+        fs::write(format!("{}/node_modules/x/y/z.js", dir), "ignored").unwrap();
+
+        let info = Info::new(&dir);
+
+        assert_eq!(
+            vec!["b.txt", "build/out/deep.txt", "src/new.rs"],
+            info.untracked_files(None).unwrap()
+        );
+        assert_eq!(
+            vec!["b.txt", "build/", "src/new.rs"],
+            info.untracked_files(Some(2)).unwrap()
+        );
+        assert_eq!(
+            vec!["b.txt", "build/", "src/"],
+            info.untracked_files(Some(1)).unwrap()
+        );
+        assert_eq!(
+            vec!["node_modules/x/y/z.js"],
+            info.ignored_files(None).unwrap()
+        );
+        assert_eq!(vec!["node_modules/"], info.ignored_files(Some(3)).unwrap());
+
+        let not_git = env::temp_dir().to_string_lossy().to_string();
+        assert!(Info::new(&not_git).untracked_files(None).is_err());
+    }
+
+    #[test]
+    fn commits_touching_only_matches_pathspecs() {
+        let dir = fixture("commits_touching");
+        commit(&dir, "root.txt", "base");
+        run_cmd!(cd ${dir}; git tag base; mkdir -p packages/foo packages/bar docs).unwrap();
+        commit(&dir, "packages/foo/lib.rs", "change foo");
+        commit(&dir, "packages/bar/lib.rs", "change bar");
+        commit(&dir, "docs/index.md", "change docs");
+        commit(&dir, "packages/foo/main.rs", "change foo again");
+
+        let info = Info::new(&dir);
+        let touching = |pathspecs: &[&str]| -> Vec<String> {
+            info.commits_touching(pathspecs, "base")
+                .unwrap()
+                .into_iter()
+                .map(|c| c.commit_message.unwrap())
+                .collect()
+        };
+
+        assert_eq!(
+            vec!["change foo again", "change foo"],
+            touching(&["packages/foo/"])
+        );
+        assert_eq!(
+            vec!["change docs", "change bar"],
+            touching(&["packages/bar", "docs"])
+        );
+        assert!(touching(&["root.txt"]).is_empty());
+        // the whole range is listed, whatever the commit limit
+        let limited = info.clone().with_commit_limit(1);
+        assert_eq!(
+            3,
+            limited
+                .commits_touching(&["packages/"], "base")
+                .unwrap()
+                .len()
+        );
+        assert!(info
+            .commits_touching(&["packages/"], "no-such-ref")
+            .is_err());
+
+        let for_paths = |info: Info, pathspecs: &[&str]| -> Vec<String> {
+            info.commits_for_paths(pathspecs)
+                .unwrap()
+                .into_iter()
+                .map(|c| c.commit_message.unwrap())
+                .collect()
+        };
+        assert_eq!(
+            vec!["change foo again", "change foo"],
+            for_paths(info.clone(), &["packages/foo/"])
+        );
+        assert_eq!(
+            vec!["change docs"],
+            for_paths(info.clone().with_commit_limit(1), &["docs", "packages/bar"])
+        );
+        assert_eq!(
+            vec!["base"],
+            for_paths(info.clone().with_ref("base"), &["root.txt", "docs"])
+        );
+        assert!(for_paths(info.clone(), &["missing/"]).is_empty());
+        assert!(info
+            .with_ref("no-such-ref")
+            .commits_for_paths(&["docs"])
+            .is_err());
+    }
+
+    #[test]
+    fn commits_between_lists_a_range() {
+        let dir = fixture("commits_between");
+        commit(&dir, "a.txt", "first");
+        run_cmd!(cd ${dir}; git tag v1.4.0).unwrap();
+        commit(&dir, "b.txt", "second");
+        run_cmd!(cd ${dir}; git checkout -q -b topic).unwrap();
+        commit(&dir, "c.txt", "on topic");
+        run_cmd!(cd ${dir}; git checkout -q main).unwrap();
+        commit(&dir, "d.txt", "third");
+
+        let info = Info::new(&dir);
+        let between = |from, to| -> Vec<String> {
+            info.commits_between(from, to)
+                .unwrap()
+                .into_iter()
+                .map(|c| c.commit_message.unwrap())
+                .collect()
+        };
+
+        let mut since_release = between("v1.4.0", "main");
+        since_release.sort();
+        assert_eq!(vec!["second", "third"], since_release);
+        assert_eq!(vec!["on topic"], between("main", "topic"));
+        assert!(between("main", "v1.4.0").is_empty());
+        assert!(info.commits_between("v9", "main").is_err());
+        assert!(info.commits_between("main", "v9").is_err());
+    }
+
+    #[test]
+    fn file_history_follows_renames() {
+        let dir = fixture("file_history");
+        let content = "key = 1\nother = 2\nthird = 3\n";
+        fs::write(format!("{}/app.conf", dir), content).unwrap();
+        run_cmd!(cd ${dir}; git add -A; git commit -q -m "add config").unwrap();
+        commit(&dir, "unrelated.txt", "unrelated");
+        run_cmd!(cd ${dir}; mkdir config; git mv app.conf config/app.toml).unwrap();
+        run_cmd!(cd ${dir}; git commit -q -m "move config").unwrap();
+        fs::write(
+            format!("{}/config/app.toml", dir),
+            format!("{}fourth = 4\n", content),
+        )
+        .unwrap();
+        run_cmd!(cd ${dir}; git commit -q -am "change config").unwrap();
+
+        let info = Info::new(&dir);
+        let history = |path| -> Vec<String> {
+            info.file_history(path)
+                .unwrap()
+                .into_iter()
+                .map(|c| c.commit_message.unwrap())
+                .collect()
+        };
+        assert_eq!(
+            vec!["change config", "move config", "add config"],
+            history("config/app.toml")
+        );
+        // the old name was removed by the move
+        assert_eq!(vec!["move config", "add config"], history("app.conf"));
+        assert!(history("missing.txt").is_empty());
+    }
+
+    #[test]
+    fn verify_reports_broken_objects() {
+        let dir = fixture("verify");
+        assert_eq!(
+            IntegrityReport::default(),
+            Info::new(&dir).verify().unwrap()
+        );
+
+        commit(&dir, "a.txt", "first");
+        commit(&dir, "b.txt", "second");
+        let info = Info::new(&dir);
+        let report = info.verify().unwrap();
+        assert!(report.is_ok() && report.dangling.is_empty(), "{:?}", report);
+
+        let tree = "HEAD^{tree}";
+        let dangling = run_fun!(cd ${dir}; git commit-tree $tree -m dangling).unwrap();
+        let blob = run_fun!(cd ${dir}; git rev-parse HEAD:a.txt).unwrap();
+        fs::remove_file(format!(
+            "{}/.git/objects/{}/{}",
+            dir,
+            &blob[..2],
+            &blob[2..]
+        ))
+        .unwrap();
+        let report = info.verify().unwrap();
+        assert_eq!(vec![dangling], report.dangling);
+        assert_eq!(vec![blob], report.missing);
+        assert!(!report.is_ok());
+
+        let tree = run_fun!(cd ${dir}; git rev-parse $tree).unwrap();
+        let object = format!("{}/.git/objects/{}/{}", dir, &tree[..2], &tree[2..]);
+        fs::remove_file(&object).unwrap();
+        fs::write(&object, "garbage").unwrap();
+        let report = info.verify().unwrap();
+        assert!(
+            report.errors.iter().any(|e| e.contains(&tree)),
+            "{:?}",
+            report
+        );
+
+        assert!(Info::new(env::temp_dir()).verify().is_err());
+    }
+
+    #[test]
+    fn commits_are_counted() {
+        let dir = fixture("commit_count");
+        let info = Info::new(&dir);
+        assert_eq!(0, info.commit_count("HEAD").unwrap());
+        assert!(info.branch_commit_counts().unwrap().is_empty());
+
+        commit(&dir, "a.txt", "first");
+        commit(&dir, "b.txt", "second");
+        run_cmd!(cd ${dir}; git checkout -q -b topic).unwrap();
+        commit(&dir, "c.txt", "on topic");
+        run_cmd!(cd ${dir}; git checkout -q main).unwrap();
+        run_cmd!(cd ${dir}; git merge -q --no-ff -m "merge topic" topic).unwrap();
+
+        assert_eq!(4, info.commit_count("HEAD").unwrap());
+        assert_eq!(3, info.commit_count("topic").unwrap());
+        assert_eq!(
+            3,
+            info.clone()
+                .with_merges(false)
+                .commit_count("main")
+                .unwrap()
+        );
+        assert!(info.commit_count("no-such-ref").is_err());
+        assert_eq!(
+            vec![("main".to_string(), 4), ("topic".to_string(), 3)],
+            info.branch_commit_counts()
+                .unwrap()
+                .into_iter()
+                .collect::<Vec<_>>()
+        );
+        assert!(Info::new(env::temp_dir()).commit_count("HEAD").is_err());
+    }
+
+    #[test]
+    fn default_branch_is_resolved_from_origin() {
+        let origin = fixture("default_branch_origin");
+        run_cmd!(cd ${origin}; git checkout -q -b develop).unwrap();
+        commit(&origin, "a.txt", "on develop");
+        // sorts before develop, so it is what `git branch -r` would list first
+        run_cmd!(cd ${origin}; git checkout -q -b aaa).unwrap();
+        commit(&origin, "b.txt", "on aaa");
+        run_cmd!(cd ${origin}; git checkout -q develop).unwrap();
+        let dir = clone(&origin, "default_branch");
+        run_cmd!(cd ${dir}; git checkout -q aaa).unwrap();
+
+        let info = Info::new(&dir).commit_info().unwrap();
+
+        assert_eq!(Some("develop".to_string()), info.default_branch);
+        assert_eq!(Some("origin/develop".to_string()), info.branch);
+        assert_eq!(vec!["on develop"], messages(&info));
+    }
+
+    #[test]
+    fn default_branch_is_asked_without_prompting() {
+        let origin = fixture("unfetched_origin");
+        run_cmd!(cd ${origin}; git checkout -q -b develop).unwrap();
+        commit(&origin, "a.txt", "on develop");
+        let dir = fixture("unfetched");
+        commit(&dir, "a.txt", "first");
+        // without a fetch there is no refs/remotes/origin/HEAD to read
+        run_cmd!(cd ${dir}; git remote add origin $origin).unwrap();
+        let wrapper = format!("{}/.git/wrapper", dir);
+        let calls = format!("{}/.git/wrapper_calls", dir);
+        fs::write(
+            &wrapper,
+            format!(
+                "#!/bin/sh\necho \"$@|$GIT_TERMINAL_PROMPT|$GIT_SSH_COMMAND\" >> {}\nexec git \"$@\"\n",
+                calls
+            ),
+        )
+        .unwrap();
+        run_cmd!(chmod +x $wrapper).unwrap();
+
+        let info = Info::new(&dir)
+            .with_backend(super::Backend::Cli)
+            .with_git_binary(&wrapper)
+            .commit_info()
+            .unwrap();
+
+        assert_eq!(Some("develop".to_string()), info.default_branch);
+        let calls = fs::read_to_string(&calls).unwrap();
+        assert!(calls
+            .lines()
+            .any(|call| call == "remote show origin|0|ssh -o BatchMode=yes"));
+    }
+
+    #[test]
+    fn commits_are_read_locally_without_remote() {
+        let dir = fixture("no_remote");
+        commit(&dir, "a.txt", "first");
+
+        let info = Info::new(&dir).commit_info().unwrap();
+
+        assert_eq!(None, info.default_branch);
+        assert_eq!(Some("main".to_string()), info.branch);
+        assert_eq!(vec!["first"], messages(&info));
+    }
+
+    #[test]
+    fn commit_web_urls() {
+        let dir = fixture("web_url");
+        commit(&dir, "a.txt", "first");
+        let info = Info::new(&dir).commit_info().unwrap();
+        let commit = &info.commits.as_ref().unwrap()[0];
+        let hash = commit.hash.clone().unwrap();
+
+        assert_eq!(None, info.commit_web_url(commit));
+
+        let url = |remote: &str| {
+            run_cmd!(cd ${dir}; git remote remove origin).ok();
+            run_cmd!(cd ${dir}; git remote add origin $remote).unwrap();
+            info.commit_web_url(commit)
+        };
+
+        assert_eq!(
+            Some(format!(
+                "https://github.com/mugendi/commit_info/commit/{}",
+                hash
+            )),
+            url("git@github.com:mugendi/commit_info.git")
+        );
+        assert_eq!(
+            Some(format!(
+                "https://github.com/mugendi/commit_info/commit/{}",
+                hash
+            )),
+            url("https://github.com/mugendi/commit_info.git")
+        );
+        assert_eq!(
+            Some(format!(
+                "https://gitlab.com/group/sub/project/-/commit/{}",
+                hash
+            )),
+            url("ssh://git@gitlab.com:2222/group/sub/project.git")
+        );
+        assert_eq!(None, url("/srv/git/project.git"));
+    }
+
+    #[test]
+    fn describe_and_exact_tag() {
+        let dir = fixture("describe");
+        let info = Info::new(&dir);
+        let describe = || {
+            info.describe()
+                .unwrap()
+                .map(|describe| describe.to_string())
+        };
+        assert_eq!(None, describe());
+
+        commit(&dir, "a.txt", "first");
+        run_cmd!(cd ${dir}; git tag v1.0.0).unwrap();
+        assert_eq!(Some("v1.0.0".to_string()), describe());
+        assert_eq!(Some("v1.0.0".to_string()), info.exact_tag().unwrap());
+
+        commit(&dir, "b.txt", "second");
+        commit(&dir, "c.txt", "third");
+        let git_describe = || run_fun!(cd ${dir}; git describe --tags --always --dirty).unwrap();
+        assert_eq!(Some(git_describe()), describe());
+        assert!(git_describe().starts_with("v1.0.0-2-g"));
+        assert_eq!(None, info.exact_tag().unwrap());
+
+        fs::write(format!("{}/a.txt", dir), "changed").unwrap();
+        assert_eq!(Some(git_describe()), describe());
+        assert!(git_describe().ends_with("-dirty"));
+
+        let parts = info.describe().unwrap().unwrap();
+        let short = run_fun!(cd ${dir}; git rev-parse --short HEAD).unwrap();
+        assert_eq!(Some("v1.0.0".to_string()), parts.tag);
+        assert_eq!(2, parts.commits_since_tag);
+        assert_eq!(short, parts.short_hash);
+        assert!(parts.dirty);
+
+        run_cmd!(cd ${dir}; git checkout -q -- a.txt; git tag release-2-rc).unwrap();
+        let parts = info.describe().unwrap().unwrap();
+        assert_eq!(Some("release-2-rc".to_string()), parts.tag);
+        assert_eq!("release-2-rc", parts.to_string());
+        assert_eq!((0, false), (parts.commits_since_tag, parts.dirty));
+
+        let untagged = fixture("describe_untagged");
+        commit(&untagged, "a.txt", "first");
+        let parts = Info::new(&untagged).describe().unwrap().unwrap();
+        let short = run_fun!(cd ${untagged}; git rev-parse --short HEAD).unwrap();
+        assert_eq!(short, parts.to_string());
+        assert_eq!(None, parts.tag);
+        assert_eq!(short, parts.short_hash);
+        assert!(!parts.dirty);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn non_utf8_paths_are_kept() {
+        use std::{ffi::OsStr, os::unix::ffi::OsStrExt, path::PathBuf};
+
+        let mut dir = PathBuf::from(fixture("non_utf8"));
+        dir.push(OsStr::from_bytes(b"with space \xff"));
+        fs::create_dir_all(&dir).unwrap();
+        run_cmd!(
+            cd ${dir};
+            git init -q -b main;
+            git config user.name "Test User";
+            git config user.email "test@example.com";
+            git config commit.gpgsign false;
+            touch a.txt;
+            git add -A;
+            git commit -q -m "first";
+            touch b.txt;
+        )
+        .unwrap();
+
+        let info = Info::new(&dir).gather().unwrap();
+
+        assert_eq!(dir, info.dir);
+        assert!(info.is_git);
+        assert_eq!(vec!["first"], messages(&info));
+        let json = info.to_json();
+        assert_eq!(Some(dir.to_string_lossy().as_ref()), json["dir"].as_str());
+        assert_eq!(Some(true), json["is_git"].as_bool());
+        assert_eq!(vec!["b.txt"], info.status.unwrap().untracked);
+    }
+
+    #[test]
+    fn bare_repos_list_commits_without_status() {
+        let origin = fixture("bare_origin");
+        commit(&origin, "a.txt", "first");
+        commit(&origin, "b.txt", "second");
+        let bare = format!("{}.git", fixture("bare"));
+        // left behind by an earlier run with the same process id
+        let _ = fs::remove_dir_all(&bare);
+        run_cmd!(git clone -q --bare $origin $bare).unwrap();
+
+        let info = Info::new(&bare);
+        assert!(info.is_git);
+        assert!(info.is_bare);
+        assert!(!Info::new(&origin).is_bare);
+        // the git directory of a normal repo isn't bare
+        assert!(!Info::new(format!("{}/.git", origin)).is_bare);
+
+        let info = info.gather().unwrap();
+        assert_eq!(vec!["second", "first"], messages(&info));
+        assert_eq!(Some("main".to_string()), info.branch);
+        let status = info.status.unwrap();
+        assert_eq!(None, status.git_dirty);
+        assert!(status.untracked.is_empty());
+
+        let found = Info::discover(format!("{}/refs/heads", bare)).unwrap();
+        assert!(found.is_bare);
+        assert_eq!(fs::canonicalize(&bare).unwrap(), found.dir);
+    }
+
+    #[test]
+    fn linked_worktrees_are_detected() {
+        let main = fixture("worktree_main");
+        commit(&main, "a.txt", "first");
+        let linked = format!("{}_linked", main);
+        let _ = fs::remove_dir_all(&linked);
+        run_cmd!(cd ${main}; git worktree add -q -b feature $linked).unwrap();
+        commit(&linked, "b.txt", "on feature");
+        fs::write(format!("{}/a.txt", linked), "changed").unwrap();
+
+        assert_eq!(None, Info::new(&main).worktree);
+        let info = Info::new(&linked).gather().unwrap();
+        assert!(info.is_git);
+
+        let worktree = info.worktree.clone().unwrap();
+        let name = std::path::Path::new(&linked).file_name().unwrap();
+        assert_eq!(name.to_string_lossy(), worktree.name);
+        assert_eq!(fs::canonicalize(&main).unwrap(), worktree.main_dir);
+        assert!(worktree
+            .git_dir
+            .ends_with(format!(".git/worktrees/{}", worktree.name)));
+
+        assert_eq!(Some("feature".to_string()), info.branch);
+        assert_eq!(vec!["on feature", "first"], messages(&info));
+        assert_eq!(vec!["a.txt"], info.status.unwrap().unstaged);
+    }
+
+    #[test]
+    fn discover_walks_up_to_the_repo_root() {
+        let dir = fixture("discover");
+        commit(&dir, "a.txt", "first");
+        let nested = format!("{}/src/bin", dir);
+        fs::create_dir_all(&nested).unwrap();
+        let root = fs::canonicalize(&dir).unwrap();
+
+        let info = Info::discover(&nested).unwrap();
+        assert_eq!(root, info.dir);
+        assert_eq!(std::path::Path::new("src/bin"), info.subpath);
+        assert!(info.is_git);
+        assert_eq!(vec!["first"], messages(&info.commit_info().unwrap()));
+
+        let info = Info::discover(&dir).unwrap();
+        assert_eq!(root, info.dir);
+        assert_eq!(std::path::Path::new(""), info.subpath);
+
+        let outside = env::temp_dir().join(format!(
+            "commit_info_discover_outside_{}",
+            std::process::id()
+        ));
+        fs::create_dir_all(&outside).unwrap();
+        assert!(matches!(
+            Info::discover(&outside),
+            Err(CommitInfoError::NotARepository(path)) if path == outside
+        ));
+        let missing = std::path::PathBuf::from(format!("{}/missing", dir));
+        assert!(matches!(
+            Info::discover(&missing),
+            Err(CommitInfoError::DirectoryNotFound(path)) if path == missing
+        ));
+    }
+
+    #[test]
+    fn try_new_checks_the_directory() {
+        let missing =
+            std::path::PathBuf::from(format!("{}/does/not/exist", fixture("try_new_missing")));
+        let error = Info::try_new(&missing).unwrap_err();
+        assert!(matches!(&error, CommitInfoError::DirectoryNotFound(path) if *path == missing));
+        assert!(error.to_string().contains("does not exist"));
+        let file = format!("{}/a.txt", fixture("try_new_file"));
+        fs::write(&file, "not a directory").unwrap();
+        assert!(matches!(
+            Info::try_new(&file),
+            Err(CommitInfoError::NotADirectory(_))
+        ));
+
+        let plain = fixture("try_new_plain");
+        fs::remove_dir_all(format!("{}/.git", plain)).unwrap();
+        assert!(!Info::try_new(&plain).unwrap().is_git);
+
+        assert!(Info::try_new(fixture("try_new_repo")).unwrap().is_git);
+    }
+
+    #[test]
+    fn commit_info_honors_the_options() {
+        let dir = fixture("options");
+        commit_at(&dir, "a.txt", "first", "2021-01-01T12:00:00Z");
+        commit_at(&dir, "b.txt", "second", "2022-01-01T12:00:00Z");
+        run_cmd!(cd ${dir}; git checkout -q -b topic).unwrap();
+        commit_as(&dir, "c.txt", "third", "Ann", "ann@example.com");
+        commit_as(&dir, "d.txt", "fourth", "Bob", "bob@example.com");
+        let info = Info::new(&dir);
+        let listed = |info: Info| messages(&info.commit_info().unwrap());
+
+        assert_eq!(
+            vec!["fourth", "third", "second", "first"],
+            listed(info.clone().with_commit_limit(10))
+        );
+        assert_eq!(
+            vec!["fourth", "third"],
+            listed(info.clone().with_commit_limit(2))
+        );
+        let listed_n = |limit| messages(&info.commit_info_n(limit).unwrap());
+        assert_eq!(vec!["fourth"], listed_n(Some(1)));
+        assert_eq!(vec!["fourth", "third", "second", "first"], listed_n(None));
+
+        let main = info.clone().with_ref("main").commit_info().unwrap();
+        assert_eq!(Some("main".to_string()), main.branch);
+        assert_eq!(
+            vec!["second", "first"],
+            listed(info.clone().with_ref("main"))
+        );
+        assert!(info.clone().with_ref("missing").commit_info().is_err());
+
+        // 2021-06-01 and 2022-06-01
+        assert_eq!(
+            vec!["fourth", "third", "second"],
+            listed(info.clone().with_since(1_622_505_600))
+        );
+        assert_eq!(
+            vec!["first"],
+            listed(info.clone().with_until(1_622_505_600))
+        );
+        assert_eq!(
+            vec!["second"],
+            listed(
+                info.clone()
+                    .with_since(1_622_505_600)
+                    .with_until(1_654_041_600)
+            )
+        );
+
+        #[cfg(feature = "dates")]
+        {
+            let date = |date| chrono::DateTime::parse_from_rfc3339(date).unwrap();
+            assert_eq!(
+                vec!["second"],
+                listed(
+                    info.clone()
+                        .with_since_date(date("2021-06-01T03:00:00+03:00"))
+                        .with_until_date(date("2022-06-01T00:00:00Z"))
+                )
+            );
+        }
+
+        assert_eq!(vec!["third"], listed(info.clone().with_author("^Ann")));
+        assert_eq!(
+            vec!["fourth", "third"],
+            listed(info.clone().with_author("ann|bob"))
+        );
+        assert_eq!(
+            vec!["fourth", "third", "second", "first"],
+            listed(info.clone().with_committer("^Test User <test@"))
+        );
+        assert!(listed(info.clone().with_committer("ann|bob")).is_empty());
+        assert_eq!(
+            vec!["fourth"],
+            listed(info.clone().with_author("bob").with_committer("Test"))
+        );
+    }
+
+    #[test]
+    fn slow_git_is_stopped() {
+        let dir = slow_status("slow");
+        let timeout = Duration::from_millis(300);
+
+        let started = Instant::now();
+        let result = Info::new(&dir).with_timeout(timeout).status_info();
+        assert!(matches!(result, Err(CommitInfoError::Timeout(t)) if t == timeout));
+        assert!(started.elapsed() < Duration::from_secs(5));
+
+        let token = CancellationToken::new();
+        let info = Info::new(&dir).with_cancellation(token.clone());
+        let started = Instant::now();
+        thread::scope(|scope| {
+            scope.spawn(|| {
+                thread::sleep(timeout);
+                token.cancel();
+            });
+            assert!(matches!(
+                info.status_info(),
+                Err(CommitInfoError::Cancelled)
+            ));
+        });
+        assert!(started.elapsed() < Duration::from_secs(5));
+        assert!(matches!(
+            info.commit_info(),
+            Err(CommitInfoError::Cancelled)
+        ));
+
+        // reading the commits doesn't run the filter, so the timeout is never reached
+        let info = Info::new(&dir).with_timeout(Duration::from_secs(60));
+        assert_eq!(vec!["first"], messages(&info.commit_info().unwrap()));
+    }
+
+    #[test]
+    fn configured_git_is_run() {
+        let dir = fixture("configured_git");
+        commit(&dir, "a.txt", "first");
+
+        // a wrapper that logs how it is called before running the real git
+        let wrapper = format!("{}/.git/wrapper", dir);
+        let calls = format!("{}/.git/wrapper_calls", dir);
+        fs::write(
+            &wrapper,
+            format!("#!/bin/sh\necho \"$@\" >> {}\nexec git \"$@\"\n", calls),
+        )
+        .unwrap();
+        run_cmd!(chmod +x $wrapper).unwrap();
+        fs::remove_file(&calls).ok();
+
+        let info = Info::new(&dir)
+            .with_backend(super::Backend::Cli)
+            .with_git_binary(&wrapper)
+            .with_git_args(["-c", "core.quotepath=off"])
+            .status_info()
+            .unwrap()
+            .commit_info()
+            .unwrap();
+        assert_eq!(vec!["first"], messages(&info));
+        let calls = fs::read_to_string(&calls).unwrap();
+        assert!(calls.lines().any(|call| call.contains("status")));
+        assert!(calls.lines().any(|call| call.contains("log")));
+        // only the check that git runs goes without them, and it runs once per binary
+        assert!(calls
+            .lines()
+            .filter(|call| *call != "--version")
+            .all(|call| call.starts_with("-c core.quotepath=off ")));
+        assert_eq!(1, calls.lines().filter(|call| *call == "--version").count());
+
+        let missing = Info::new(&dir).with_git_binary(format!("{}/.git/no_such_git", dir));
+        assert!(matches!(
+            missing.status_info(),
+            Err(CommitInfoError::GitBinaryMissing)
+        ));
+        assert!(matches!(
+            missing.commit_info(),
+            Err(CommitInfoError::GitBinaryMissing)
+        ));
+    }
+
+    #[test]
+    fn results_are_cached_until_refreshed() {
+        let dir = fixture("cached");
+        commit(&dir, "a.txt", "first");
+        let wrapper = format!("{}/.git/wrapper", dir);
+        let calls = format!("{}/.git/wrapper_calls", dir);
+        fs::write(
+            &wrapper,
+            format!("#!/bin/sh\necho \"$@\" >> {}\nexec git \"$@\"\n", calls),
+        )
+        .unwrap();
+        run_cmd!(chmod +x $wrapper).unwrap();
+        let ran = |subcommand: &str| {
+            let calls = fs::read_to_string(&calls).unwrap_or_default();
+            calls
+                .lines()
+                .filter(|call| call.split_whitespace().any(|arg| arg == subcommand))
+                .count()
+        };
+
+        let info = Info::new(&dir)
+            .with_backend(super::Backend::Cli)
+            .with_git_binary(&wrapper)
+            .with_cache_ttl(Duration::from_secs(60));
+        assert_eq!(vec!["first"], messages(&info.commit_info().unwrap()));
+        let status = info.status_info().unwrap();
+        assert_eq!((1, 1), (ran("log"), ran("status")));
+
+        commit(&dir, "b.txt", "second");
+        fs::write(format!("{}/c.txt", dir), "c").unwrap();
+        // the cached status is kept along with the cached commits
+        let both = status.commit_info().unwrap();
+        assert_eq!(vec!["first"], messages(&both));
+        assert!(both.status.unwrap().untracked.is_empty());
+        assert_eq!((1, 1), (ran("log"), ran("status")));
+
+        // other options are another query
+        let limited = info.clone().with_commit_limit(1).commit_info().unwrap();
+        assert_eq!(vec!["second"], messages(&limited));
+        assert_eq!(2, ran("log"));
+
+        info.refresh();
+        assert_eq!(
+            vec!["second", "first"],
+            messages(&info.commit_info().unwrap())
+        );
+        let status = info.status_info().unwrap().status.unwrap();
+        assert_eq!(vec!["c.txt"], status.untracked);
+        assert_eq!((3, 2), (ran("log"), ran("status")));
+
+        // entries expire with the TTL
+        let info = info.with_cache_ttl(Duration::ZERO);
+        info.status_info().unwrap();
+        info.status_info().unwrap();
+        assert_eq!(4, ran("status"));
+    }
+
+    #[test]
+    fn shared_info_refreshes_once() {
+        let dir = fixture("shared");
+        commit(&dir, "a.txt", "first");
+        let wrapper = format!("{}/.git/wrapper", dir);
+        let calls = format!("{}/.git/wrapper_calls", dir);
+        fs::write(
+            &wrapper,
+            format!("#!/bin/sh\necho \"$@\" >> {}\nexec git \"$@\"\n", calls),
+        )
+        .unwrap();
+        run_cmd!(chmod +x $wrapper).unwrap();
+        let ran = || {
+            let calls = fs::read_to_string(&calls).unwrap_or_default();
+            calls
+                .lines()
+                .filter(|call| call.split_whitespace().any(|arg| arg == "status"))
+                .count()
+        };
+
+        let info = Info::new(&dir)
+            .with_backend(super::Backend::Cli)
+            .with_git_binary(&wrapper);
+        let shared = super::SharedInfo::new(info, Duration::from_secs(60));
+        assert!(shared.get().is_none());
+
+        thread::scope(|scope| {
+            for _ in 0..8 {
+                let shared = shared.clone();
+                scope.spawn(move || {
+                    assert_eq!(vec!["first"], messages(&shared.get_or_refresh().unwrap()));
+                });
+            }
+        });
+        assert_eq!(1, ran());
+
+        commit(&dir, "b.txt", "second");
+        assert_eq!(vec!["first"], messages(&shared.get_or_refresh().unwrap()));
+        assert_eq!(
+            vec!["second", "first"],
+            messages(&shared.refresh().unwrap())
+        );
+        assert_eq!(vec!["second", "first"], messages(&shared.get().unwrap()));
+        assert_eq!(2, ran());
+    }
+
+    #[test]
+    fn queries_fail_outside_a_repo() {
+        let plain = fixture("queries_plain");
+        fs::remove_dir_all(format!("{}/.git", plain)).unwrap();
+        let info = Info::new(&plain);
+        let not_a_repo = |result: anyhow::Result<()>| {
+            matches!(
+                result.unwrap_err().downcast_ref(),
+                Some(CommitInfoError::NotARepository(_))
+            )
+        };
+
+        assert!(not_a_repo(info.commits_iter().map(drop)));
+        #[cfg(feature = "dates")]
+        assert!(not_a_repo(info.activity().map(drop)));
+        assert!(not_a_repo(info.churn("HEAD~1..HEAD").map(drop)));
+        assert!(not_a_repo(info.reflog(5).map(drop)));
+        assert!(not_a_repo(info.bisect_status().map(drop)));
+        assert!(not_a_repo(info.is_shallow().map(drop)));
+        assert!(not_a_repo(info.contributors().map(drop)));
+        assert!(not_a_repo(info.head_commit().map(drop)));
+        assert!(not_a_repo(info.commits_page(0, 5).map(drop)));
+        assert!(not_a_repo(
+            info.commits_touching(&["a.txt"], "HEAD").map(drop)
+        ));
+        assert!(not_a_repo(info.search_commits("fix").map(drop)));
+        assert!(not_a_repo(info.commit_count("HEAD").map(drop)));
+        assert!(not_a_repo(info.branch_commit_counts().map(drop)));
+        assert!(not_a_repo(info.commits_between("v1", "HEAD").map(drop)));
+        assert!(not_a_repo(info.commits_for_paths(&["a.txt"]).map(drop)));
+        assert!(not_a_repo(info.file_history("a.txt").map(drop)));
+        assert!(not_a_repo(info.describe().map(drop)));
+        assert!(not_a_repo(info.exact_tag().map(drop)));
+        assert!(not_a_repo(info.path_status("a.txt").map(drop)));
+        assert!(not_a_repo(info.untracked_files(None).map(drop)));
+        assert!(not_a_repo(info.ignored_files(None).map(drop)));
+        assert!(not_a_repo(info.diff_refs("HEAD~1", "HEAD").map(drop)));
+        assert!(not_a_repo(info.merge_base("HEAD~1", "HEAD").map(drop)));
+        assert!(not_a_repo(info.commit_patch("HEAD", None).map(drop)));
+        assert!(not_a_repo(info.verify().map(drop)));
+        assert!(not_a_repo(info.verify_commits(&[]).map(drop)));
+    }
+
+    #[test]
+    fn failures_are_told_apart() {
+        let plain = fixture("errors_plain");
+        fs::remove_dir_all(format!("{}/.git", plain)).unwrap();
+        let info = Info::new(&plain);
+        assert!(matches!(
+            info.status_info(),
+            Err(CommitInfoError::NotARepository(_))
+        ));
+        assert!(matches!(
+            info.commit_info(),
+            Err(CommitInfoError::NotARepository(_))
+        ));
+
+        // a fresh repo simply has no commits to list
+        let dir = fixture("errors_empty");
+        assert_eq!(None, Info::new(&dir).commit_info().unwrap().commits);
+        assert!(matches!(
+            Info::new(&dir).commit_info_for("main"),
+            Err(CommitInfoError::NoCommits)
+        ));
+
+        commit(&dir, "a.txt", "first");
+        match super::git_output(std::path::Path::new(&dir), &["log", "missing"]) {
+            Err(CommitInfoError::CommandFailed { command, stderr }) => {
+                assert_eq!("git log missing", command);
+                assert!(stderr.contains("missing"));
+            }
+            other => panic!("expected CommandFailed, got {:?}", other),
+        }
+        assert!(matches!(
+            super::parse_log("abc\0def"),
+            Err(CommitInfoError::ParseError(_))
+        ));
+    }
+
+    #[test]
+    fn gather_matches_serial_calls() {
+        let origin = fixture("gather_origin");
+        commit(&origin, "a.txt", "first");
+        commit(&origin, "b.txt", "second");
+        let dir = clone(&origin, "gather");
+        fs::write(format!("{}/a.txt", dir), "changed").unwrap();
+        fs::write(format!("{}/c.txt", dir), "new").unwrap();
+
+        let info = Info::new(&dir);
+        let mut serial = info.status_info().unwrap().commit_info().unwrap();
+        serial.shallow = serial.is_shallow().ok();
+        let parallel = info.gather().unwrap();
+
+        assert_eq!(serial.to_json(), parallel.to_json());
+        assert_eq!(serial.default_branch, parallel.default_branch);
+        assert_eq!(Some(true), parallel.status.unwrap().git_dirty);
+        assert_eq!(2, parallel.commits.unwrap().len());
+    }
+
+    #[test]
+    fn reflog_records_checkouts_and_resets() {
+        let dir = fixture("reflog");
+        let info = Info::new(&dir);
+        assert!(info.reflog(10).unwrap().is_empty());
+
+        commit(&dir, "a.txt", "first");
+        commit(&dir, "b.txt", "second");
+        run_cmd!(cd ${dir}; git checkout -q -b feature; git reset -q --hard HEAD~1).unwrap();
+        let commits = info.commits_page(0, 10).unwrap().commits;
+        let first = commits[0].hash.clone().unwrap();
+
+        let reflog = info.reflog(10).unwrap();
+        let actions: Vec<&str> = reflog.iter().map(|e| e.action.as_str()).collect();
+
+        assert_eq!(
+            vec!["reset", "checkout", "commit", "commit (initial)"],
+            actions
+        );
+        assert_eq!("moving to HEAD~1", reflog[0].message);
+        assert_eq!(first, reflog[0].new_hash);
+        assert_eq!(reflog[1].new_hash, reflog[0].old_hash);
+        assert_eq!("0".repeat(40), reflog[3].old_hash);
+
+        let latest = info.reflog(1).unwrap();
+        assert_eq!(1, latest.len());
+        assert_eq!(reflog[0], latest[0]);
+
+        let json = serde_json::to_value(&reflog[0]).unwrap();
+        assert_eq!(reflog[0], serde_json::from_value(json).unwrap());
+    }
+
+    #[test]
+    fn merges_can_be_excluded() {
+        let dir = fixture("merges");
+        commit(&dir, "a.txt", "first");
+        run_cmd!(cd ${dir}; git checkout -q -b feature).unwrap();
+        commit(&dir, "b.txt", "on feature");
+        run_cmd!(cd ${dir}; git checkout -q main).unwrap();
+        commit(&dir, "c.txt", "on main");
+        run_cmd!(cd ${dir}; git merge -q --no-ff -m "merge feature" feature).unwrap();
+
+        let with_merges = Info::new(&dir).commit_info().unwrap();
+        assert_eq!(
+            vec!["merge feature", "on main", "on feature", "first"],
+            messages(&with_merges)
+        );
+
+        let without_merges = Info::new(&dir).with_merges(false).commit_info().unwrap();
+        assert_eq!(
+            vec!["on main", "on feature", "first"],
+            messages(&without_merges)
+        );
+    }
+
+    #[cfg(feature = "dates")]
+    #[test]
+    fn activity_spans_first_to_last_commit() {
+        let dir = fixture("activity");
+        assert_eq!(
+            super::Activity::default(),
+            Info::new(&dir).activity().unwrap()
+        );
+
+        commit_at(&dir, "a.txt", "first", "2022-03-01 10:30:00 +0000");
+        commit_at(&dir, "b.txt", "second", "2022-03-02 08:00:00 +0200");
+        commit_at(&dir, "c.txt", "third", "2022-03-04 10:30:00 +0000");
+
+        let activity = Info::new(&dir).activity().unwrap();
+        let first = activity.first_commit_date.unwrap();
+        let last = activity.last_commit_date.unwrap();
+
+        assert_eq!(
+            "2022-03-01 10:30:00",
+            first.format("%Y-%m-%d %H:%M:%S").to_string()
+        );
+        assert_eq!(3, (last - first).num_days());
+        assert_eq!(
+            Some((chrono::Utc::now() - last).num_days()),
+            activity.days_since_last_commit
+        );
+        assert!(activity.days_since_last_commit.unwrap() > 365);
+    }
+
+    #[test]
+    fn signer_identity_is_read() {
+        let dir = fixture("signer");
+        let keys = ssh_signers(&dir, &["release"]);
+        commit(&dir, "a.txt", "unsigned");
+        commit_signed(&dir, "b.txt", "signed", &keys[0]);
+
+        let public = format!("{}.pub", keys[0]);
+        let fingerprint = run_fun!(ssh-keygen -l -f $public).unwrap();
+        let fingerprint = fingerprint.split(' ').nth(1).unwrap().to_string();
+
+        let unchecked = Info::new(&dir).commit_info().unwrap().commits.unwrap();
+        assert_eq!(None, unchecked[0].signature);
+        assert_eq!(None, unchecked[0].signed_by);
+        assert_eq!(None, unchecked[1].signature);
+
+        let info = Info::new(&dir).with_signatures(true);
+        let commits = info.commit_info().unwrap().commits.unwrap();
+        assert_eq!(Some(SignatureStatus::Good), commits[0].signature);
+        assert_eq!(
+            Some(Signer {
+                name: Some("release@example.com".into()),
+                key_id: Some(fingerprint.clone()),
+                fingerprint: Some(fingerprint),
+                trust_level: Some(TrustLevel::Fully),
+            }),
+            commits[0].signed_by
+        );
+        assert_eq!(Some(SignatureStatus::Unsigned), commits[1].signature);
+        assert_eq!(None, commits[1].signed_by);
+        assert!(!commits[1].signature.unwrap().is_signed());
+
+        // the signature of a key missing from the allowed signers is good, but nobody vouches for it
+        let stranger = format!("{}/.keys/stranger", dir);
+        run_cmd!(ssh-keygen -q -t ed25519 -N "" -C stranger -f $stranger).unwrap();
+        commit_signed(&dir, "c.txt", "stranger", &stranger);
+        let commits = info.commit_info().unwrap().commits.unwrap();
+        assert_eq!(
+            Some(SignatureStatus::GoodUnknownValidity),
+            commits[0].signature
+        );
+        assert!(commits[0].signature.unwrap().is_good());
+        let signed_by = commits[0].signed_by.clone().unwrap();
+        assert_eq!(None, signed_by.name);
+        assert_eq!(Some(TrustLevel::Undefined), signed_by.trust_level);
+    }
+
+    #[test]
+    fn verify_commits_checks_signers() {
+        let dir = fixture("verify_commits");
+        let keys = ssh_signers(&dir, &["trusted", "untrusted"]);
+
+        commit(&dir, "a.txt", "unsigned");
+        commit_signed(&dir, "trusted.txt", "trusted", &keys[0]);
+        commit_signed(&dir, "untrusted.txt", "untrusted", &keys[1]);
+
+        let verified = Info::new(&dir)
+            .verify_commits(&["Trusted@example.com"])
+            .unwrap();
+        let results: Vec<bool> = verified.iter().map(|(_, trusted)| *trusted).collect();
+        assert_eq!(vec![false, true, false], results);
+
+        let head = Info::new(&dir).commits_page(0, 1).unwrap().commits[0]
+            .hash
+            .clone();
+        assert_eq!(head, Some(verified[0].0.clone()));
+        assert_eq!(
+            vec![false, false, false],
+            Info::new(&dir)
+                .verify_commits(&[])
+                .unwrap()
+                .into_iter()
+                .map(|(_, trusted)| trusted)
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn status_can_be_limited_to_paths() {
+        let dir = fixture("status_pathspec");
+        fs::create_dir_all(format!("{}/docs", dir)).unwrap();
+        fs::create_dir_all(format!("{}/src", dir)).unwrap();
+        commit(&dir, "docs/a.txt", "first");
+        commit(&dir, "src/b.txt", "second");
+        fs::write(format!("{}/src/b.txt", dir), "changed").unwrap();
+
+        let info = Info::new(&dir);
+        let docs = info.status_info_for(&["docs"]).unwrap().status.unwrap();
+        assert_eq!(Some(false), docs.git_dirty);
+        assert!(docs.dirty_paths().is_empty());
+
+        let src = info.status_info_for(&["src"]).unwrap().status.unwrap();
+        assert_eq!(Some(true), src.git_dirty);
+        assert_eq!(vec!["src/b.txt"], src.dirty_paths());
+
+        let all = info.status_info_for(&[]).unwrap().status.unwrap();
+        assert_eq!(Some(true), all.git_dirty);
+    }
+
+    #[test]
+    fn shallow_clones_are_detected() {
+        let origin = fixture("shallow_origin");
+        commit(&origin, "a.txt", "first");
+        commit(&origin, "b.txt", "second");
+        let dir = fixture("shallow");
+        fs::remove_dir_all(&dir).unwrap();
+        let url = format!("file://{}", origin);
+        run_cmd!(git clone -q --depth=1 $url $dir).unwrap();
+
+        assert!(Info::new(&dir).is_shallow().unwrap());
+        assert_eq!(Some(true), Info::new(&dir).gather().unwrap().shallow);
+
+        assert!(!Info::new(&origin).is_shallow().unwrap());
+        assert_eq!(Some(false), Info::new(&origin).gather().unwrap().shallow);
+        assert_eq!(None, Info::new(&origin).commit_info().unwrap().shallow);
+    }
+
+    #[test]
+    fn commit_patch_shows_the_diff() {
+        let dir = fixture("commit_patch");
+        fs::write(format!("{}/a.txt", dir), "one\ntwo\nthree\n").unwrap();
+        commit(&dir, "b.txt", "first");
+        fs::write(format!("{}/a.txt", dir), "one\n2\nthree\n").unwrap();
+        commit(&dir, "b.txt", "second");
+        let info = Info::new(&dir);
+        let hash = info.commits_page(0, 1).unwrap().commits[0]
+            .hash
+            .clone()
+            .unwrap();
+
+        let patch = info.commit_patch(&hash, None).unwrap();
+        assert!(patch.starts_with(&format!("commit {}", hash)));
+        assert!(patch.contains("    second"));
+        assert!(patch.contains("\n-two\n+2\n"));
+        assert!(patch.contains("\n-first\n\\ No newline at end of file\n+second"));
+
+        let truncated = info.commit_patch(&hash, Some(20)).unwrap();
+        assert_eq!(
+            format!("{}\n[... patch truncated ...]\n", &patch[..20]),
+            truncated
+        );
+        assert_eq!(patch, info.commit_patch(&hash, Some(patch.len())).unwrap());
+
+        assert!(info.commit_patch("0000000", None).is_err());
+        assert!(info.commit_patch("HEAD^{tree}", None).is_err());
+    }
+
+    #[test]
+    fn scan_repos_finds_immediate_repos() {
+        let root = fixture("scan_root");
+        fs::remove_dir_all(format!("{}/.git", root)).unwrap();
+        for name in ["one", "two"] {
+            let dir = format!("{}/{}", root, name);
+            fs::create_dir_all(&dir).unwrap();
+            run_cmd!(
+                cd ${dir};
+                git init -q -b main;
+                git config user.name "Test User";
+                git config user.email "test@example.com";
+            )
+            .unwrap();
+            commit(&dir, "a.txt", name);
+        }
+        fs::create_dir_all(format!("{}/plain/nested", root)).unwrap();
+        run_cmd!(cd ${root}/plain/nested; git init -q).unwrap();
+        fs::write(format!("{}/file.txt", root), "not a dir").unwrap();
+
+        let repos = super::scan_repos(&root).unwrap();
+
+        let dirs: Vec<&str> = repos
+            .iter()
+            .map(|info| info.dir.to_str().unwrap())
+            .collect();
+        assert_eq!(vec![format!("{}/one", root), format!("{}/two", root)], dirs);
+        assert_eq!(vec!["one"], messages(&repos[0]));
+        assert_eq!(Some(false), repos[1].status.as_ref().unwrap().git_dirty);
+        assert!(super::scan_repos(format!("{}/missing", root)).is_err());
+    }
+
+    #[test]
+    fn info_round_trips_through_serde() {
+        let dir = fixture("serde_info");
+        commit(&dir, "a.txt", "first");
+        fs::write(format!("{}/b.txt", dir), "b").unwrap();
+        let info = Info::new(&dir).with_commit_limit(1).gather().unwrap();
+
+        let json = serde_json::to_value(&info).unwrap();
+        assert_eq!(serde_json::json!(["b.txt"]), json["status"]["untracked"]);
+        assert_eq!(serde_json::json!(false), json["is_bare"]);
+        assert!(json.get("commit_limit").is_none());
+
+        let read: Info = serde_json::from_value(json.clone()).unwrap();
+        assert_eq!(json, serde_json::to_value(&read).unwrap());
+        assert_eq!(Some(5), read.commit_limit);
+        // the options aren't read back
+        assert_ne!(info, read);
+        assert_eq!(info.with_commit_limit(5).with_backend(super::GitCli), read);
+
+        assert_eq!(Info::default(), serde_json::from_str::<Info>("{}").unwrap());
+        let partial: Info =
+            serde_json::from_value(serde_json::json!({ "dir": dir, "is_git": true })).unwrap();
+        assert!(partial.status.is_none());
+        assert_eq!(vec!["first"], messages(&partial.commit_info().unwrap()));
+    }
+
+    #[test]
+    fn scan_all_keeps_the_order_of_paths() {
+        let mut paths = vec![];
+        for i in 0..6 {
+            let dir = fixture(&format!("scan_all_{}", i));
+            commit(&dir, "a.txt", &format!("repo {}", i));
+            paths.push(dir);
+        }
+        paths.insert(2, format!("{}/missing", env::temp_dir().display()));
+
+        let options = super::ScanOptions::new()
+            .with_concurrency(2)
+            .with_info(|info| info.with_commit_limit(1));
+        let results = super::scan_all(&paths, &options);
+
+        assert_eq!(7, results.len());
+        assert!(matches!(
+            results[2],
+            Err(CommitInfoError::NotARepository(_))
+        ));
+        let listed: Vec<Vec<String>> = results
+            .iter()
+            .filter_map(|info| info.as_ref().ok())
+            .map(messages)
+            .collect();
+        let expected: Vec<Vec<String>> = (0..6).map(|i| vec![format!("repo {}", i)]).collect();
+        assert_eq!(expected, listed);
+        assert!(super::scan_all(Vec::<String>::new(), &options).is_empty());
+    }
+
+    #[test]
+    fn bisect_status_reports_the_current_commit() {
+        let dir = fixture("bisect");
+        for i in 0..8 {
+            commit(&dir, "a.txt", &format!("commit {}", i));
+        }
+        let info = Info::new(&dir);
+        assert_eq!(None, info.bisect_status().unwrap());
+
+        let head = || info.commit_info().unwrap().head_hash.unwrap();
+
+        run_cmd!(cd ${dir}; git bisect start).unwrap();
+        let status = info.bisect_status().unwrap().unwrap();
+        assert_eq!(head(), status.current);
+        assert_eq!(None, status.remaining_estimate);
+
+        run_cmd!(cd ${dir}; git bisect bad HEAD; git bisect good HEAD~7 > /dev/null).unwrap();
+        let status = info.bisect_status().unwrap().unwrap();
+        assert_eq!(head(), status.current);
+        assert_ne!(
+            info.commits_page(0, 1).unwrap().commits[0].commit_message,
+            Some("commit 7".to_string())
+        );
+        assert_eq!(Some(2), status.remaining_estimate);
+
+        run_cmd!(cd ${dir}; git bisect reset).unwrap();
+        assert_eq!(None, info.bisect_status().unwrap());
+    }
+
+    #[test]
+    fn local_branch_name_fallbacks() {
+        let dir = fixture("branch_fallbacks");
+        let branch = || Info::new(&dir).commit_info().unwrap().branch;
+
+        // an empty repo has nothing to name
+        assert_eq!(None, branch());
+
+        commit(&dir, "a.txt", "first");
+        commit(&dir, "b.txt", "second");
+        assert_eq!(Some("main".to_string()), branch());
+
+        run_cmd!(cd ${dir}; git tag v1.0 HEAD~1; git checkout -q --detach HEAD).unwrap();
+        let short = run_fun!(cd ${dir}; git rev-parse --short HEAD).unwrap();
+        assert_eq!(Some(format!("v1.0-1-g{}", short)), branch());
+
+        run_cmd!(cd ${dir}; git tag -d v1.0).unwrap();
+        assert_eq!(Some(format!("(detached: {})", short)), branch());
+
+        run_cmd!(cd ${dir}; git tag v2.0; git checkout -q --detach v2.0).unwrap();
+        assert_eq!(Some("v2.0".to_string()), branch());
+    }
+
+    #[test]
+    fn mailmap_normalizes_identities() {
+        let dir = fixture("mailmap");
+        commit_as(&dir, "a.txt", "old", "J. Doe", "jdoe@old.example.com");
+        commit_as(&dir, "b.txt", "new", "Jane Doe", "jane@example.com");
+        fs::write(
+            format!("{}/.mailmap", dir),
+            "Jane Doe <jane@example.com> <jdoe@old.example.com>\n",
+        )
+        .unwrap();
+        // commit writes the message into the file, so add the mailmap alongside another file
+        commit(&dir, "c.txt", "add mailmap");
+
+        let info = Info::new(&dir).commit_info().unwrap();
+        let old = &info.commits.unwrap()[2];
+        assert_eq!(Some("Jane Doe"), old.author_name.as_deref());
+        assert_eq!(Some("jane@example.com"), old.author_email.as_deref());
+
+        let contributors = Info::new(&dir).contributors().unwrap();
+        assert_eq!(2, contributors.len());
+        assert_eq!(
+            ("jane@example.com", 2),
+            (contributors[0].email.as_str(), contributors[0].commit_count)
+        );
+
+        let raw = Info::new(&dir).with_mailmap(false);
+        let old = &raw.commit_info().unwrap().commits.unwrap()[2];
+        assert_eq!(Some("J. Doe"), old.author_name.as_deref());
+        assert_eq!(Some("jdoe@old.example.com"), old.author_email.as_deref());
+        assert_eq!(3, raw.contributors().unwrap().len());
+        assert_eq!(
+            Some("jdoe@old.example.com"),
+            raw.commits_iter()
+                .unwrap()
+                .nth(2)
+                .unwrap()
+                .unwrap()
+                .author_email
+                .as_deref()
+        );
+    }
+
+    #[test]
+    fn branch_status_is_parsed() {
+        let origin = fixture("branch_status_origin");
+        commit(&origin, "a.txt", "first");
+        let dir = clone(&origin, "branch_status");
+        commit(&dir, "b.txt", "local one");
+        commit(&dir, "c.txt", "local two");
+        commit(&origin, "d.txt", "remote");
+        run_cmd!(cd ${dir}; git fetch -q).unwrap();
+
+        let info = Info::new(&dir).gather().unwrap();
+        let branch = info.status.unwrap().branch.unwrap();
+        assert_eq!(
+            super::BranchStatus {
+                oid: info.head_hash,
+                head: Some("main".into()),
+                upstream: Some("origin/main".into()),
+                ahead: Some(2),
+                behind: Some(1),
+            },
+            branch
+        );
+
+        run_cmd!(cd ${dir}; git checkout -q --detach HEAD).unwrap();
+        let branch = Info::new(&dir)
+            .status_info()
+            .unwrap()
+            .status
+            .unwrap()
+            .branch
+            .unwrap();
+        assert_eq!((None, None), (branch.head, branch.upstream));
+
+        let empty = fixture("branch_status_empty");
+        let branch = Info::new(&empty)
+            .status_info()
+            .unwrap()
+            .status
+            .unwrap()
+            .branch
+            .unwrap();
+        assert_eq!(None, branch.oid);
+        assert_eq!(Some("main".to_string()), branch.head);
+    }
+
+    // Serves canned data, to check that Info only reads the repo through its backend
+    #[derive(Debug)]
+    struct StubBackend;
+
+    impl super::GitBackend for StubBackend {
+        fn status(&self, _dir: &std::path::Path, pathspecs: &[&str]) -> anyhow::Result<Status> {
+            Ok(Status {
+                git_dirty: Some(true),
+                untracked: pathspecs.iter().map(|p| p.to_string()).collect(),
+                ..Status::default()
+            })
+        }
+
+        fn log(
+            &self,
+            _dir: &std::path::Path,
+            query: &super::LogQuery,
+        ) -> anyhow::Result<Vec<Commit>> {
+            Ok((0..10)
+                .skip(query.skip)
+                .take(query.max_count.unwrap_or(10))
+                .map(|i| Commit {
+                    commit_message: Some(format!("{} {}", query.revision, i)),
+                    ..Commit::default()
+                })
+                .collect())
+        }
+
+        fn verify_ref(&self, _dir: &std::path::Path, reference: &str) -> anyhow::Result<()> {
+            match reference {
+                "stub" => Ok(()),
+                _ => anyhow::bail!("unknown"),
+            }
+        }
+
+        fn head_hash(&self, _dir: &std::path::Path) -> Option<String> {
+            Some("0123456789".into())
+        }
+
+        fn head_short_hash(&self, _dir: &std::path::Path) -> Option<String> {
+            Some("0123".into())
+        }
+
+        fn default_branch(&self, _dir: &std::path::Path) -> Option<String> {
+            None
+        }
+
+        fn remote_branch(&self, _dir: &std::path::Path) -> Option<String> {
+            None
+        }
+
+        fn head_name(&self, _dir: &std::path::Path) -> Option<String> {
+            Some("stub".into())
+        }
+    }
+
+    #[test]
+    fn info_reads_through_its_backend() {
+        // an empty repo, so anything read from git itself would differ
+        let dir = fixture("backend");
+        let info = Info::new(&dir).with_backend(StubBackend);
+
+        let gathered = info.gather().unwrap();
+        assert_eq!(Some("stub".to_string()), gathered.branch);
+        assert_eq!(Some("0123456789".to_string()), gathered.head_hash);
+        assert_eq!(Some("0123".to_string()), gathered.head_short_hash);
+        assert_eq!(
+            vec!["HEAD 0", "HEAD 1", "HEAD 2", "HEAD 3", "HEAD 4"],
+            messages(&gathered)
+        );
+        assert_eq!(Some(true), gathered.status.unwrap().git_dirty);
+
+        let status = info.status_info_for(&["docs"]).unwrap().status.unwrap();
+        assert_eq!(vec!["docs"], status.untracked);
+
+        assert_eq!(
+            vec!["stub 0"],
+            messages(&info.commit_info_for("stub").unwrap())[..1]
+        );
+        assert!(info.commit_info_for("other").is_err());
+    }
+
+    #[test]
+    fn upstream_is_read() {
+        let origin = fixture("upstream_origin");
+        commit(&origin, "a.txt", "first");
+        let dir = clone(&origin, "upstream");
+        let upstream = || {
+            Info::new(&dir)
+                .status_info()
+                .unwrap()
+                .status
+                .unwrap()
+                .upstream()
+                .map(String::from)
+        };
+
+        assert_eq!(Some("origin/main".to_string()), upstream());
+
+        run_cmd!(cd ${dir}; git checkout -q -b feature).unwrap();
+        assert_eq!(None, upstream());
+        let status = Info::new(&dir).status_info().unwrap().status.unwrap();
+        assert_eq!(None, status.error);
+        assert_eq!(Some(false), status.git_dirty);
+
+        run_cmd!(cd ${dir}; git branch -q -u origin/main).unwrap();
+        assert_eq!(Some("origin/main".to_string()), upstream());
+
+        commit(&dir, "b.txt", "local");
+        commit(&origin, "c.txt", "remote one");
+        commit(&origin, "d.txt", "remote two");
+        run_cmd!(cd ${dir}; git fetch -q).unwrap();
+        let status = Info::new(&dir).status_info().unwrap().status.unwrap();
+        assert_eq!((Some(1), Some(2)), (status.ahead(), status.behind()));
+
+        run_cmd!(cd ${dir}; git branch -q --unset-upstream).unwrap();
+        let status = Info::new(&dir).status_info().unwrap().status.unwrap();
+        assert_eq!((None, None), (status.ahead(), status.behind()));
+    }
+
+    #[test]
+    fn summary_accessors_match_the_map() {
+        let dir = fixture("summary_accessors");
+        commit(&dir, "a.txt", "first");
+        let check = |modified, dirty| {
+            let status = Info::new(&dir).status_info().unwrap().status.unwrap();
+            assert_eq!(
+                status.summary.get("is_modified").copied(),
+                status.is_modified()
+            );
+            assert_eq!(
+                status.summary.get("is_dirty").copied(),
+                status.is_dirty_flag()
+            );
+            assert_eq!(
+                (Some(modified), Some(dirty)),
+                (status.is_modified(), status.is_dirty_flag())
+            );
+        };
+
+        check(false, false);
+        fs::write(format!("{}/b.txt", dir), "untracked").unwrap();
+        check(true, false);
+        fs::write(format!("{}/a.txt", dir), "changed").unwrap();
+        check(true, true);
+
+        let status = Status::default();
+        assert_eq!((None, None), (status.is_modified(), status.is_dirty_flag()));
+    }
+
+    #[test]
+    fn git_availability_is_detected() {
+        assert!(Info::git_available());
+        assert!(super::require_git().is_ok());
+
+        let missing = super::RunOptions {
+            program: env::temp_dir().join("commit_info_no_git_path").join("git"),
+            ..Default::default()
+        };
+        assert!(!missing.git_runs());
+    }
+
+    #[test]
+    fn repo_env_vars_are_cleared() {
+        let dir = fixture("repo_env_vars");
+        let removed = |command: std::process::Command| {
+            command
+                .get_envs()
+                .filter(|(_, value)| value.is_none())
+                .map(|(var, _)| var.to_string_lossy().to_string())
+                .collect::<Vec<_>>()
+        };
+
+        let info = Info::new(&dir);
+        let cleared = removed(info.run_options().command(info.dir.as_path()));
+        for var in ["GIT_DIR", "GIT_WORK_TREE", "GIT_INDEX_FILE"] {
+            assert!(cleared.iter().any(|cleared| cleared == var));
+        }
+
+        let info = info.with_inherited_git_env(true);
+        assert!(removed(info.run_options().command(info.dir.as_path())).is_empty());
+
+        let error: anyhow::Error = CommitInfoError::GitBinaryMissing.into();
+        assert!(matches!(
+            error.downcast_ref::<CommitInfoError>(),
+            Some(CommitInfoError::GitBinaryMissing)
+        ));
+        // passing it back through anyhow doesn't hide it
+        assert!(matches!(
+            CommitInfoError::from(error),
+            CommitInfoError::GitBinaryMissing
+        ));
+    }
+
+    #[test]
+    fn staged_and_unstaged_changes_are_told_apart() {
+        let dir = fixture("staged_unstaged");
+        commit(&dir, "a.txt", "first");
+        commit(&dir, "b.txt", "second");
+        let flags = || {
+            let status = Info::new(&dir).status_info().unwrap().status.unwrap();
+            (status.has_staged, status.has_unstaged, status.git_dirty)
+        };
+
+        assert_eq!((Some(false), Some(false), Some(false)), flags());
+
+        fs::write(format!("{}/a.txt", dir), "staged").unwrap();
+        run_cmd!(cd ${dir}; git add a.txt).unwrap();
+        assert_eq!((Some(true), Some(false), Some(true)), flags());
+
+        run_cmd!(cd ${dir}; git reset -q).unwrap();
+        assert_eq!((Some(false), Some(true), Some(true)), flags());
+
+        fs::write(format!("{}/b.txt", dir), "staged").unwrap();
+        run_cmd!(cd ${dir}; git add b.txt).unwrap();
+        assert_eq!((Some(true), Some(true), Some(true)), flags());
+
+        let mut status = Status::default();
+        status.summarize("", Err(CommitInfoError::Cancelled));
+        assert_eq!(
+            (Some(false), Some(false)),
+            (status.has_staged, status.has_unstaged)
+        );
+    }
+
+    #[test]
+    fn status_entries_describe_each_path() {
+        let dir = fixture("status_entries");
+        commit(&dir, "a.txt", "first");
+        commit(&dir, "b.txt", "second");
+        commit(&dir, "c.txt", "third");
+        run_cmd!(cd ${dir}; git mv a.txt moved.txt).unwrap();
+        fs::write(format!("{}/b.txt", dir), "staged").unwrap();
+        run_cmd!(cd ${dir}; git add b.txt).unwrap();
+        fs::write(format!("{}/b.txt", dir), "changed again").unwrap();
+        fs::remove_file(format!("{}/c.txt", dir)).unwrap();
+        fs::write(format!("{}/d.txt", dir), "untracked").unwrap();
+
+        let status = Info::new(&dir).status_info().unwrap().status.unwrap();
+        let entries: Vec<_> = status
+            .entries
+            .iter()
+            .map(|entry| {
+                let (x, y) = (entry.index_status, entry.worktree_status);
+                (entry.path.as_str(), x, y, entry.renamed_from.as_deref())
+            })
+            .collect();
+
+        assert_eq!(
+            vec![
+                ("b.txt", 'M', 'M', None),
+                ("c.txt", ' ', 'D', None),
+                ("d.txt", '?', '?', None),
+                ("moved.txt", 'R', ' ', Some("a.txt")),
+            ],
+            entries
+        );
+
+        let counts = StatusCounts {
+            staged: 2,
+            unstaged: 2,
+            untracked: 1,
+            conflicted: 0,
+            renamed: 1,
+            deleted: 1,
+        };
+        assert_eq!(counts, status.counts());
+
+        let mut status = Status::default();
+        let conflict = "u UU N... 100644 100644 100644 100644 e69de29 e69de29 e69de29 a.txt";
+        status.summarize(conflict, Ok(String::new()));
+        assert_eq!(1, status.counts().conflicted);
+        assert_eq!(0, status.counts().staged);
+    }
+
+    #[test]
+    fn head_hash_is_set_by_commit_info() {
+        let dir = fixture("head_hash");
+        let info = Info::new(&dir).commit_info().unwrap();
+        assert_eq!(None, info.head_hash);
+        assert_eq!(None, info.head_short_hash);
+
+        commit(&dir, "a.txt", "first");
+        let info = Info::new(&dir).gather().unwrap();
+        let hash = info.commits.as_ref().unwrap()[0].hash.clone().unwrap();
+        let short = info.head_short_hash.unwrap();
+
+        assert_eq!(Some(hash.clone()), info.head_hash);
+        assert!(short.len() >= 7 && hash.starts_with(&short));
+        assert_eq!(Some(short), info.commits.unwrap()[0].short_hash);
+    }
+
+    #[test]
+    fn first_parent_follows_the_mainline() {
+        let dir = fixture("first_parent");
+        commit(&dir, "a.txt", "first");
+        run_cmd!(cd ${dir}; git checkout -q -b feature).unwrap();
+        commit(&dir, "b.txt", "on feature");
+        commit(&dir, "c.txt", "more on feature");
+        run_cmd!(cd ${dir}; git checkout -q main).unwrap();
+        commit(&dir, "d.txt", "on main");
+        run_cmd!(cd ${dir}; git merge -q --no-ff -m "merge feature" feature).unwrap();
+        commit(&dir, "e.txt", "after merge");
+
+        // the commits share a timestamp, so all of them are read rather than whichever come first
+        let all = Info::new(&dir)
+            .commit_info_n(None)
+            .unwrap()
+            .commits
+            .unwrap();
+        assert_eq!(6, all.len());
+        let find = |message: &str| {
+            all.iter()
+                .find(|c| c.commit_message.as_deref() == Some(message))
+                .unwrap()
+        };
+        let merge = find("merge feature");
+        assert!(merge.is_merge());
+        assert_eq!(1, all.iter().filter(|c| c.is_merge()).count());
+        // the mainline parent comes first
+        assert_eq!(
+            vec![
+                find("on main").hash.clone().unwrap(),
+                find("more on feature").hash.clone().unwrap()
+            ],
+            merge.parent_hashes
+        );
+        assert!(find("first").parent_hashes.is_empty());
+
+        let mainline = Info::new(&dir).first_parent(true).commit_info().unwrap();
+        assert_eq!(
+            vec!["after merge", "merge feature", "on main", "first"],
+            messages(&mainline)
+        );
+
+        let mainline = Info::new(&dir)
+            .first_parent(true)
+            .with_merges(false)
+            .commits_page(0, 10)
+            .unwrap()
+            .commits;
+        assert_eq!(3, mainline.len());
+        assert_eq!(
+            4,
+            Info::new(&dir)
+                .first_parent(true)
+                .commits_iter()
+                .unwrap()
+                .count()
+        );
+    }
+
+    #[test]
+    fn conventional_commit_subjects() {
+        let parse = |message: &str| {
+            let commit = Commit {
+                commit_message: Some(message.into()),
+                ..Commit::default()
+            };
+            commit.conventional()
+        };
+
+        let feat = parse("feat(api): x").unwrap();
+        assert_eq!("feat", feat.kind);
+        assert_eq!(Some("api".to_string()), feat.scope);
+        assert!(!feat.breaking);
+        assert_eq!("x", feat.description);
+
+        let fix = parse("fix!: y").unwrap();
+        assert_eq!("fix", fix.kind);
+        assert_eq!(None, fix.scope);
+        assert!(fix.breaking);
+        assert_eq!("y", fix.description);
+
+        let footer = parse("refactor: z\n\nBREAKING CHANGE: config moved").unwrap();
+        assert!(footer.breaking);
+        let body = Commit {
+            commit_message: Some("refactor: z".into()),
+            commit_body: Some("Moved.\n\nBREAKING CHANGE: config moved".into()),
+            ..Commit::default()
+        };
+        assert!(body.conventional().unwrap().breaking);
+
+        assert_eq!(None, parse("Update the readme"));
+        assert_eq!(None, parse("feat(): empty scope"));
+
+        // commits read from a repo come parsed
+        let dir = fixture("conventional");
+        commit(&dir, "a.txt", "feat(api)!: drop v1 endpoints");
+        commit(&dir, "b.txt", "Update the readme");
+        let commits = Info::new(&dir).commit_info().unwrap().commits.unwrap();
+        assert_eq!(None, commits[0].conventional);
+        let conventional = commits[1].conventional.clone().unwrap();
+        assert_eq!(Some("api".to_string()), conventional.scope);
+        assert!(conventional.breaking);
+        assert_eq!(commits[1].conventional(), commits[1].conventional);
+    }
+
+    #[test]
+    fn issue_references() {
+        let commit = Commit {
+            commit_message: Some("Fix login redirect (GH-7), see JIRA-456".into()),
+            commit_body: Some("Closes #12 and #13\n\nFixes: PROJ-9".into()),
+            ..Commit::default()
+        };
+        let references = commit.references();
+
+        let found: Vec<(&str, IssueRefKind, Option<&str>)> = references
+            .iter()
+            .map(|r| (r.reference.as_str(), r.kind, r.keyword.as_deref()))
+            .collect();
+        assert_eq!(
+            vec![
+                ("GH-7", IssueRefKind::GitHub, None),
+                ("JIRA-456", IssueRefKind::Tracker, None),
+                ("#12", IssueRefKind::Number, Some("Closes")),
+                ("#13", IssueRefKind::Number, None),
+                ("PROJ-9", IssueRefKind::Tracker, Some("Fixes")),
+            ],
+            found
+        );
+        assert_eq!(20, references[0].position);
+        let message =
+            "Fix login redirect (GH-7), see JIRA-456\n\nCloses #12 and #13\n\nFixes: PROJ-9";
+        for reference in &references {
+            assert!(message[reference.position..].starts_with(&reference.reference));
+        }
+
+        assert!(Commit::default().references().is_empty());
+    }
+
+    #[test]
+    fn churn_sums_numstat() {
+        let dir = fixture("churn");
+        fs::write(format!("{}/a.txt", dir), "one\ntwo\nthree\n").unwrap();
+        commit(&dir, "b.txt", "first");
+        fs::write(format!("{}/a.txt", dir), "one\n2\nthree\n").unwrap();
+        fs::write(format!("{}/c.bin", dir), [0u8, 159, 146, 150]).unwrap();
+        commit(&dir, "b.txt", "second");
+
+        let churn = Info::new(&dir).churn("HEAD").unwrap();
+
+        assert_eq!(2, churn.commits);
+        // a.txt: 3 + 1 lines, b.txt: 1 + 1 lines
+        assert_eq!(6, churn.insertions);
+        assert_eq!(2, churn.deletions);
+        assert_eq!(3, churn.files_touched);
+
+        let last = Info::new(&dir).churn("HEAD~1..HEAD").unwrap();
+        assert_eq!(1, last.commits);
+        assert_eq!(2, last.insertions);
+    }
+
+    #[test]
+    fn commit_stats_are_read() {
+        let dir = fixture("commit_stats");
+        fs::write(format!("{}/a.txt", dir), "one\ntwo\nthree\n").unwrap();
+        commit(&dir, "b.txt", "first");
+        fs::write(format!("{}/a.txt", dir), "one\n2\nthree\n").unwrap();
+        fs::write(format!("{}/c.bin", dir), [0u8, 159, 146, 150]).unwrap();
+        commit(&dir, "b.txt", "second");
+        run_cmd!(cd ${dir}; git checkout -q -b feature HEAD~1).unwrap();
+        commit(&dir, "d.txt", "on feature");
+        run_cmd!(cd ${dir}; git checkout -q main; git merge -q --no-ff -m merge feature).unwrap();
+
+        assert!(Info::new(&dir).commit_info().unwrap().commits.unwrap()[0]
+            .stats
+            .is_none());
+
+        // the commits share a timestamp, so they are listed in no particular order
+        let stats = |info: Info| -> Vec<(String, u32, u64, u64)> {
+            let commits = info
+                .with_stats(true)
+                .commit_info()
+                .unwrap()
+                .commits
+                .unwrap();
+            let mut stats: Vec<_> = commits
+                .iter()
+                .map(|c| {
+                    let s = c.stats.unwrap();
+                    let message = c.commit_message.clone().unwrap();
+                    (message, s.files_changed, s.insertions, s.deletions)
+                })
+                .collect();
+            stats.sort();
+            stats
+        };
+        // the binary c.bin counts as a file without lines
+        assert_eq!(
+            vec![
+                ("first".to_string(), 2, 4, 0),
+                ("merge".to_string(), 0, 0, 0),
+                ("on feature".to_string(), 1, 1, 0),
+                ("second".to_string(), 3, 2, 2),
+            ],
+            stats(Info::new(&dir))
+        );
+        // following the first parent, the merge brings in d.txt
+        assert_eq!(
+            vec![
+                ("first".to_string(), 2, 4, 0),
+                ("merge".to_string(), 1, 1, 0),
+                ("second".to_string(), 3, 2, 2),
+            ],
+            stats(Info::new(&dir).first_parent(true))
+        );
+        #[cfg(feature = "backend-git2")]
+        assert_eq!(
+            stats(Info::new(&dir).first_parent(true)),
+            stats(
+                Info::new(&dir)
+                    .first_parent(true)
+                    .with_backend(super::Git2Backend)
+            )
+        );
+        #[cfg(feature = "backend-gix")]
+        assert_eq!(
+            stats(Info::new(&dir)),
+            stats(Info::new(&dir).with_backend(super::GixBackend))
+        );
+    }
+
+    #[test]
+    fn commit_files_are_listed() {
+        let dir = fixture("commit_files");
+        let text = "a line long enough\nto be recognized\nwhen it is moved\n";
+        fs::write(format!("{}/a.txt", dir), text).unwrap();
+        fs::write(format!("{}/c.txt", dir), "gone soon").unwrap();
+        commit(&dir, "b.txt", "first");
+        fs::create_dir_all(format!("{}/docs", dir)).unwrap();
+        run_cmd!(cd ${dir}; git mv a.txt docs/a.md; git rm -q c.txt).unwrap();
+        commit(&dir, "b.txt", "second");
+
+        let change = |path: &str, change, old_path: Option<&str>| FileChange {
+            path: path.into(),
+            change,
+            old_path: old_path.map(String::from),
+        };
+        let read = |info: Info| {
+            info.with_files(true)
+                .commit_info()
+                .unwrap()
+                .commits
+                .unwrap()
+        };
+
+        let commits = read(Info::new(&dir));
+        assert_eq!(
+            Some(vec![
+                change("b.txt", ChangeKind::Modified, None),
+                change("c.txt", ChangeKind::Deleted, None),
+                change("docs/a.md", ChangeKind::Renamed, Some("a.txt")),
+            ]),
+            commits[0].files
+        );
+        assert_eq!(
+            Some(vec![
+                change("a.txt", ChangeKind::Added, None),
+                change("b.txt", ChangeKind::Added, None),
+                change("c.txt", ChangeKind::Added, None),
+            ]),
+            commits[1].files
+        );
+        assert_eq!(
+            None,
+            Info::new(&dir).commit_info().unwrap().commits.unwrap()[0].files
+        );
+
+        #[cfg(feature = "backend-git2")]
+        assert_eq!(
+            commits,
+            read(Info::new(&dir).with_backend(super::Git2Backend))
+        );
+        #[cfg(feature = "backend-gix")]
+        assert_eq!(
+            commits,
+            read(Info::new(&dir).with_backend(super::GixBackend))
+        );
+    }
+
+    #[test]
+    fn timezone_offsets_are_kept() {
+        let dir = fixture("tz_offsets");
+        fs::write(format!("{}/a.txt", dir), "first").unwrap();
+        let author_date = "2022-03-01 10:30:00 +0530";
+        let committer_date = "2022-03-01 00:00:00 -0600";
+        run_cmd!(
+            cd ${dir};
+            git add -A;
+            GIT_AUTHOR_DATE=$author_date GIT_COMMITTER_DATE=$committer_date git commit -q -m first;
+        )
+        .unwrap();
+
+        let commits = Info::new(&dir).commit_info().unwrap().commits.unwrap();
+
+        assert_eq!(Some(330), commits[0].author_tz_offset_minutes);
+        assert_eq!(Some(-360), commits[0].committer_tz_offset_minutes);
+    }
+
+    // Quickly creates `count` empty commits on main with git fast-import
+    fn bulk_commits(dir: &str, count: usize) {
+        use std::io::Write;
+        use std::process::{Command, Stdio};
+
+        let mut stream = String::new();
+        for i in 0..count {
+            let message = format!("commit number {}", i);
+            stream.push_str(&format!(
+                "commit refs/heads/main\ncommitter Test User <test@example.com> {} +0000\ndata {}\n{}\n",
+                1_600_000_000 + i,
+                message.len(),
+                message
+            ));
+        }
+
+        let mut child = Command::new("git")
+            .current_dir(dir)
+            .args(["fast-import", "--quiet"])
+            .stdin(Stdio::piped())
+            .spawn()
+            .unwrap();
+        child
+            .stdin
+            .take()
+            .unwrap()
+            .write_all(stream.as_bytes())
+            .unwrap();
+        assert!(child.wait().unwrap().success());
+    }
+
+    #[test]
+    fn commits_iter_stops_early() {
+        let dir = fixture("commits_iter");
+        bulk_commits(&dir, 5000);
+        let info = Info::new(&dir);
+
+        let mut iter = info.stream_log(&["HEAD"]).unwrap();
+        let first: Vec<String> = iter
+            .by_ref()
+            .take(3)
+            .map(|c| c.unwrap().commit_message.unwrap())
+            .collect();
+
+        assert_eq!(
+            vec![
+                "commit number 4999",
+                "commit number 4998",
+                "commit number 4997"
+            ],
+            first
+        );
+        // the full log is well over a megabyte
+        assert!(iter.bytes_read < 100_000, "read {} bytes", iter.bytes_read);
+        drop(iter);
+
+        assert_eq!(5000, info.commits_iter().unwrap().count());
+        let older = info.clone().with_ref("HEAD~4990").commits_iter().unwrap();
+        assert_eq!(
+            Some("commit number 9".to_string()),
+            older.map(|c| c.unwrap().commit_message).next().flatten()
+        );
+        assert!(info
+            .clone()
+            .with_ref("no-such-branch")
+            .commits_iter()
+            .is_err());
+        assert_eq!(
+            0,
+            Info::new(fixture("commits_iter_empty"))
+                .commits_iter()
+                .unwrap()
+                .count()
+        );
+    }
+
+    #[test]
+    fn sign_offs_and_issue_refs_are_extracted() {
+        let dir = fixture("trailers");
+        let message = "Fix crash on empty input, see #12\n\nAlso closes GH-34 and #12.\n\nSigned-off-by: Jane Doe <jane@example.com>";
+        commit(&dir, "a.txt", message);
+        commit(&dir, "b.txt", "plain");
+
+        let commits = Info::new(&dir).commit_info().unwrap().commits.unwrap();
+
+        assert_eq!("plain", commits[0].commit_message.as_deref().unwrap());
+        assert!(commits[0].signed_off_by.is_empty());
+        assert!(commits[0].trailers.is_empty());
+        assert!(commits[0].issue_refs.is_empty());
+
+        assert_eq!(
+            "Fix crash on empty input, see #12",
+            commits[1].commit_message.as_deref().unwrap()
+        );
+        assert_eq!(
+            vec![("Jane Doe".to_string(), "jane@example.com".to_string())],
+            commits[1].signed_off_by
+        );
+        assert_eq!(vec!["#12", "GH-34"], commits[1].issue_refs);
+        // the same parser as Commit::references, each reference once
+        let references = commits[1].references();
+        assert_eq!(
+            vec!["#12", "GH-34", "#12"],
+            references
+                .iter()
+                .map(|found| found.reference.as_str())
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(None, commits[1].conventional);
+
+        assert_eq!(commits[1].commit_message, commits[1].commit_subject);
+        assert_eq!(
+            Some("Also closes GH-34 and #12.\n\nSigned-off-by: Jane Doe <jane@example.com>"),
+            commits[1].commit_body.as_deref()
+        );
+        assert_eq!(None, commits[0].commit_body);
+        let body = run_fun!(cd ${dir}; git log -1 --format=%b HEAD~1).unwrap();
+        assert_eq!(Some(body.trim()), commits[1].commit_body.as_deref());
+    }
+
+    #[test]
+    fn trailers_are_parsed() {
+        let dir = fixture("parsed_trailers");
+        let message = "Add export\n\nSigned-off-by: in the body doesn't count\n\nCo-authored-by: Ann <ann@example.com>\nco-authored-by: Bob <bob@example.com>\nReviewed-by: Cy\n  Lee\nSigned-off-by: Dee <dee@example.com>";
+        commit(&dir, "a.txt", message);
+        commit(
+            &dir,
+            "b.txt",
+            "Tidy up\n\nNot a trailer\nAcked-by: Ann <ann@example.com>",
+        );
+
+        let commits = Info::new(&dir).commit_info().unwrap().commits.unwrap();
+
+        assert!(commits[0].trailers.is_empty());
+        assert_eq!(
+            vec![
+                (
+                    "Co-authored-by".to_string(),
+                    "Ann <ann@example.com>".to_string()
+                ),
+                (
+                    "co-authored-by".to_string(),
+                    "Bob <bob@example.com>".to_string()
+                ),
+                ("Reviewed-by".to_string(), "Cy Lee".to_string()),
+                (
+                    "Signed-off-by".to_string(),
+                    "Dee <dee@example.com>".to_string()
+                ),
+            ],
+            commits[1].trailers
+        );
+        assert_eq!(
+            vec![
+                ("Ann".to_string(), "ann@example.com".to_string()),
+                ("Bob".to_string(), "bob@example.com".to_string()),
+            ],
+            commits[1].co_authors()
+        );
+        assert_eq!(
+            vec![("Dee".to_string(), "dee@example.com".to_string())],
+            commits[1].signed_off_by()
+        );
+        // a sign-off outside the trailers doesn't count for the field either
+        assert_eq!(commits[1].signed_off_by(), commits[1].signed_off_by);
+
+        // git agrees on what the trailers are
+        let format = "--format=%(trailers:unfold,only)";
+        let parsed = run_fun!(cd ${dir}; git log -1 $format HEAD~1).unwrap();
+        let parsed: Vec<&str> = parsed.lines().collect();
+        assert_eq!(
+            commits[1]
+                .trailers
+                .iter()
+                .map(|(key, value)| format!("{}: {}", key, value))
+                .collect::<Vec<_>>(),
+            parsed
+        );
+    }
+
+    #[test]
+    fn decorations_are_read() {
+        let dir = fixture("decorations");
+        commit(&dir, "a.txt", "first");
+        commit(&dir, "b.txt", "second");
+        commit(&dir, "c.txt", "third");
+        run_cmd!(
+            cd ${dir};
+            git tag v1.0 HEAD~1;
+            git tag -a -m "release" v1.1 HEAD~1;
+            git branch feature HEAD~1;
+            git update-ref refs/remotes/origin/main HEAD;
+            git symbolic-ref refs/remotes/origin/HEAD refs/remotes/origin/main;
+        )
+        .unwrap();
+
+        let commits = Info::new(&dir)
+            .with_ref("main")
+            .commit_info()
+            .unwrap()
+            .commits
+            .unwrap();
+
+        assert!(commits[0].tags.is_empty());
+        assert_eq!(vec!["main", "origin/main"], commits[0].branches);
+        assert_eq!(vec!["v1.0", "v1.1"], commits[1].tags);
+        assert_eq!(vec!["feature"], commits[1].branches);
+        assert!(commits[2].tags.is_empty() && commits[2].branches.is_empty());
+    }
+
+    #[test]
+    fn notes_are_read() {
+        let dir = fixture("notes");
+        commit(&dir, "a.txt", "first");
+        commit(&dir, "b.txt", "second");
+        run_cmd!(cd ${dir}; git notes add -m "deployed to staging" HEAD~1).unwrap();
+
+        // notes are only read when asked for
+        let commits = Info::new(&dir).commit_info().unwrap().commits.unwrap();
+        assert_eq!(None, commits[1].notes);
+
+        let notes = Info::new(&dir).with_notes(true);
+        let commits = notes.commit_info().unwrap().commits.unwrap();
+        assert_eq!(None, commits[0].notes);
+        assert_eq!(Some("deployed to staging".to_string()), commits[1].notes);
+
+        run_cmd!(cd ${dir}; git notes --ref qa add -m "QA: passed" HEAD).unwrap();
+        for reference in ["qa", "notes/qa", "refs/notes/qa"] {
+            let info = notes.clone().with_notes_ref(reference);
+            let commits = info.commit_info().unwrap().commits.unwrap();
+            assert_eq!(Some("QA: passed".to_string()), commits[0].notes);
+            assert_eq!(None, commits[1].notes);
+            let streamed = info.commits_iter().unwrap().next().unwrap().unwrap();
+            assert_eq!(Some("QA: passed".to_string()), streamed.notes);
+        }
+        let configured = String::from("qa");
+        let info = notes.clone().with_notes_ref(configured);
+        assert_eq!(
+            Some("QA: passed".to_string()),
+            info.commit_info().unwrap().commits.unwrap()[0].notes
+        );
+        assert_eq!(
+            None,
+            Info::new(&dir)
+                .with_notes_ref("qa")
+                .commit_info()
+                .unwrap()
+                .commits
+                .unwrap()[0]
+                .notes
+        );
+        assert_eq!(None, notes.commit_info().unwrap().commits.unwrap()[0].notes);
+        assert_eq!(
+            Some("deployed to staging".to_string()),
+            notes.commits_iter().unwrap().nth(1).unwrap().unwrap().notes
+        );
+    }
+
+    #[test]
+    fn merge_base_finds_the_common_ancestor() {
+        let dir = fixture("merge_base");
+        commit(&dir, "a.txt", "first");
+        commit(&dir, "b.txt", "ancestor");
+        let info = Info::new(&dir);
+        let ancestor = info.commits_page(0, 1).unwrap().commits[0].hash.clone();
+
+        run_cmd!(cd ${dir}; git checkout -q -b one).unwrap();
+        commit(&dir, "c.txt", "on one");
+        run_cmd!(cd ${dir}; git checkout -q -b two main).unwrap();
+        commit(&dir, "d.txt", "on two");
+        run_cmd!(cd ${dir}; git checkout -q main).unwrap();
+        commit(&dir, "e.txt", "on main");
+
+        assert_eq!(ancestor, info.merge_base("one", "two").unwrap());
+        assert_eq!(
+            ancestor,
+            info.merge_base_all(&["one", "two", "main"]).unwrap()
+        );
+        assert!(info.merge_base("one", "nope").is_err());
+    }
+
+    #[test]
+    fn merge_base_of_unrelated_histories_is_none() {
+        let dir = fixture("merge_base_unrelated");
+        commit(&dir, "a.txt", "first");
+        run_cmd!(cd ${dir}; git checkout -q --orphan other).unwrap();
+        commit(&dir, "b.txt", "unrelated");
+
+        assert_eq!(None, Info::new(&dir).merge_base("main", "other").unwrap());
+    }
+
+    #[test]
+    fn messages_are_parsed_whatever_they_contain() {
+        let dir = fixture("awkward_messages");
+        let awkward = [
+            r#"say "hi" {"json": true}"#,
+            r"C:\path\to\file \n \u0041",
+            "100% done, %s %H %x00 %n",
+            "record\u{1e}separator and tab\t",
+        ];
+        for (i, message) in awkward.iter().enumerate() {
+            commit_as(
+                &dir,
+                &format!("{}.txt", i),
+                message,
+                "O'Brien \"OB\" Jr",
+                "ob@example.com",
+            );
+        }
+
+        let info = Info::new(&dir).commit_info().unwrap();
+        let mut expected: Vec<String> = awkward.iter().map(|m| m.trim().to_string()).collect();
+        expected.reverse();
+        assert_eq!(expected, messages(&info));
+
+        let commits = info.commits.unwrap();
+        assert!(commits.iter().all(|c| c.hash.is_some()));
+        assert_eq!(Some("O'Brien \"OB\" Jr"), commits[0].author_name.as_deref());
+
+        let streamed: Vec<_> = Info::new(&dir).commits_iter().unwrap().collect();
+        assert_eq!(4, streamed.len());
+        assert_eq!(
+            Some(r#"say "hi" {"json": true}"#),
+            streamed[3].as_ref().unwrap().commit_message.as_deref()
+        );
+    }
+
+    #[test]
+    fn crlf_log_output_is_parsed() {
+        let dir = fixture("crlf");
+        commit(
+            &dir,
+            "a.txt",
+            "first\n\nSee #7\n\nSigned-off-by: Jane Doe <jane@example.com>",
+        );
+        commit(&dir, "b.txt", "second");
+        run_cmd!(cd ${dir}; git notes add -m "a note" HEAD).unwrap();
+
+        let output = std::process::Command::new("git")
+            .current_dir(&dir)
+            .args([
+                "log",
+                "-z",
+                "--notes",
+                &format!("--format={}", super::LOG_FORMAT),
+            ])
+            .output()
+            .unwrap()
+            .stdout;
+        let output = String::from_utf8(output).unwrap();
+        assert!(!output.contains('\r'));
+
+        let expected = super::parse_log(&output).unwrap();
+        let parsed = super::parse_log(&output.replace('\n', "\r\n")).unwrap();
+
+        assert_eq!(2, parsed.len());
+        assert_eq!(expected, parsed);
+        assert_eq!(Some("second"), parsed[0].commit_message.as_deref());
+        assert_eq!(Some("a note".to_string()), parsed[0].notes);
+        assert_eq!(vec!["#7"], parsed[1].issue_refs);
+        assert_eq!("Jane Doe", parsed[1].signed_off_by[0].0);
+    }
+
+    #[test]
+    fn json_schema_field_names() {
+        let dir = fixture("json_fields");
+        commit(&dir, "a.txt", "first");
+
+        let json = Info::new(&dir).status_info().unwrap().to_json();
+        let keys =
+            |v: &serde_json::Value| v.as_object().unwrap().keys().cloned().collect::<Vec<_>>();
+
+        assert_eq!(
+            vec![
+                "branch",
+                "commits",
+                "default_branch",
+                "dir",
+                "head_hash",
+                "head_short_hash",
+                "is_bare",
+                "is_git",
+                "shallow",
+                "status",
+                "subpath",
+                "worktree"
+            ],
+            keys(&json)
+        );
+        assert_eq!(
+            vec![
+                "branch",
+                "entries",
+                "error",
+                "git_dirty",
+                "has_staged",
+                "has_unstaged",
+                "ignored",
+                "staged",
+                "summary",
+                "unstaged",
+                "untracked"
+            ],
+            keys(&json["status"])
+        );
+        assert_eq!(
+            vec!["is_dirty", "is_modified"],
+            keys(&json["status"]["summary"])
+        );
+        assert_eq!(serde_json::Value::Null, json["commits"]);
+    }
+
+    #[cfg(feature = "dates")]
+    #[test]
+    fn json_snapshot() {
+        let origin = fixture("json_snapshot_origin");
+        commit_at(&origin, "a.txt", "first", "2022-03-01 10:30:00 +0000");
+        let dir = clone(&origin, "json_snapshot");
+
+        let info = Info::new(&dir)
+            .status_info()
+            .unwrap()
+            .commit_info()
+            .unwrap();
+        let hash = info.commits.as_ref().unwrap()[0].hash.clone().unwrap();
+        let short_hash = info.commits.as_ref().unwrap()[0]
+            .short_hash
+            .clone()
+            .unwrap();
+        let json = info
+            .to_json_pretty()
+            .replace(&dir, "<dir>")
+            .replace(&hash, "HASH")
+            .replace(&short_hash, "SHORT_HASH");
+
+        let expected = r#"{
+  "branch": "origin/main",
+  "commits": [
+    {
+      "author_date": "2022-03-01T10:30:00+00:00",
+      "author_email": "test@example.com",
+      "author_name": "Test User",
+      "author_tz_offset_minutes": 0,
+      "branches": [
+        "main",
+        "origin/main"
+      ],
+      "commit_body": null,
+      "commit_date": "2022-03-01T10:30:00+00:00",
+      "commit_message": "first",
+      "commit_subject": "first",
+      "committer_email": "test@example.com",
+      "committer_name": "Test User",
+      "committer_tz_offset_minutes": 0,
+      "conventional": null,
+      "files": null,
+      "hash": "HASH",
+      "issue_refs": [],
+      "notes": null,
+      "parent_hashes": [],
+      "short_hash": "SHORT_HASH",
+      "signature": null,
+      "signed_by": null,
+      "signed_off_by": [],
+      "stats": null,
+      "tags": [],
+      "trailers": [],
+      "tree_hash": "2771eb9"
+    }
+  ],
+  "default_branch": "main",
+  "dir": "<dir>",
+  "head_hash": "HASH",
+  "head_short_hash": "SHORT_HASH",
+  "is_bare": false,
+  "is_git": true,
+  "shallow": null,
+  "status": {
+    "branch": {
+      "ahead": 0,
+      "behind": 0,
+      "head": "main",
+      "oid": "HASH",
+      "upstream": "origin/main"
+    },
+    "entries": [],
+    "error": null,
+    "git_dirty": false,
+    "has_staged": false,
+    "has_unstaged": false,
+    "ignored": [],
+    "staged": [],
+    "summary": {
+      "is_dirty": false,
+      "is_modified": false
+    },
+    "unstaged": [],
+    "untracked": []
+  },
+  "subpath": "",
+  "worktree": null
+}"#;
+        assert_eq!(expected, json);
+    }
+
+    #[cfg(feature = "dates")]
+    #[test]
+    fn display_timezone_changes_rendered_dates() {
+        let dir = fixture("display_timezone");
+        commit_at(&dir, "a.txt", "first", "2022-03-01 10:30:00 +0000");
+        let date = |info: Info| {
+            info.commit_info().unwrap().to_json()["commits"][0]["commit_date"]
+                .as_str()
+                .unwrap()
+                .to_string()
+        };
+
+        assert_eq!("2022-03-01T10:30:00+00:00", date(Info::new(&dir)));
+
+        let offset = chrono::FixedOffset::east_opt(3 * 3600).unwrap();
+        let info = Info::new(&dir).with_display_timezone(offset);
+        assert_eq!("2022-03-01 13:30:00 +03:00", date(info.clone()));
+        // the stored date keeps its own offset
+        let commits = info.clone().commit_info().unwrap().commits.unwrap();
+        assert_eq!(
+            "2022-03-01T10:30:00+00:00",
+            serde_json::to_value(&commits[0]).unwrap()["commit_date"]
+        );
+
+        let formatted = info.clone().with_date_format("%d/%m/%Y %H:%M");
+        assert_eq!("01/03/2022 13:30", date(formatted.clone()));
+        let json = formatted.commit_info().unwrap().to_json();
+        assert_eq!("01/03/2022 13:30", json["commits"][0]["author_date"]);
+        assert_eq!(
+            "2022-03-01 10:30",
+            date(Info::new(&dir).with_date_format("%Y-%m-%d %H:%M"))
+        );
+        // chrono can't render an unknown specifier
+        assert_eq!(
+            "2022-03-01T10:30:00+00:00",
+            date(Info::new(&dir).with_date_format("%Q"))
+        );
+
+        #[cfg(feature = "tz")]
+        assert_eq!(
+            "2022-03-01 03:30:00 MST",
+            date(Info::new(&dir).with_display_timezone(chrono_tz::America::Denver))
+        );
+    }
+
+    #[test]
+    fn status_paths_are_not_quoted() {
+        let dir = fixture("quoted_paths");
+        commit(&dir, "a.txt", "first");
+        fs::write(format!("{}/é.txt", dir), "accented").unwrap();
+        fs::write(format!("{}/a.txt", dir), "changed").unwrap();
+        run_cmd!(cd ${dir}; git mv a.txt "with space.txt").unwrap();
+
+        let status = Info::new(&dir).status_info().unwrap().status.unwrap();
+
+        assert_eq!(vec!["é.txt".to_string()], status.untracked);
+        assert_eq!(vec!["with space.txt".to_string()], status.staged);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn non_utf8_paths_are_lossy() {
+        use std::{ffi::OsStr, os::unix::ffi::OsStrExt, path::Path};
+
+        let dir = fixture("non_utf8_paths");
+        commit(&dir, "a.txt", "first");
+        fs::write(
+            Path::new(&dir).join(OsStr::from_bytes(b"caf\xe9.txt")),
+            "latin1",
+        )
+        .unwrap();
+
+        let status = Info::new(&dir).status_info().unwrap().status.unwrap();
+
+        assert_eq!(vec!["caf\u{FFFD}.txt".to_string()], status.untracked);
+    }
+
+    #[cfg(feature = "dates")]
+    #[test]
+    fn commit_dates_keep_their_offset() {
+        let dir = fixture("dates");
+        commit_at(&dir, "a.txt", "first", "2022-03-01 10:30:00 +0300");
+        // the author wrote it in Denver, the committer applied it in Nairobi a day later
+        run_cmd!(
+            cd ${dir};
+            GIT_COMMITTER_DATE="2022-03-02 09:00:00 +0300" git commit -q --allow-empty -m "applied" --date "2022-03-01 08:00:00 -0700";
+        )
+        .unwrap();
+
+        let commits = Info::new(&dir).commit_info().unwrap().commits.unwrap();
+        let first = &commits[1];
+        assert_eq!(
+            "2022-03-01T10:30:00+03:00",
+            first.commit_date.unwrap().to_rfc3339()
+        );
+        assert_eq!(first.commit_date, first.author_date);
+
+        let applied = &commits[0];
+        assert_eq!(
+            "2022-03-01T08:00:00-07:00",
+            applied.author_date.unwrap().to_rfc3339()
+        );
+        assert_eq!(
+            "2022-03-02T09:00:00+03:00",
+            applied.commit_date.unwrap().to_rfc3339()
+        );
+
+        let json = serde_json::to_value(applied).unwrap();
+        assert_eq!("2022-03-02T09:00:00+03:00", json["commit_date"]);
+        assert_eq!("2022-03-01T08:00:00-07:00", json["author_date"]);
+        assert_eq!(*applied, serde_json::from_value::<Commit>(json).unwrap());
+
+        // missing dates are written as null and read back
+        let undated = Commit::default();
+        let json = serde_json::to_value(&undated).unwrap();
+        assert_eq!(serde_json::Value::Null, json["commit_date"]);
+        assert_eq!(undated, serde_json::from_value::<Commit>(json).unwrap());
+        assert_eq!(undated, serde_json::from_str::<Commit>("{}").unwrap());
+
+        // dates serialized by earlier versions are still read: as git reports them, in UTC, or a "null" string
+        let old = serde_json::json!({
+            "commit_date": "2022-03-01 07:30:00 UTC",
+            "author_date": "2022-03-01 08:00:00 -0700",
+        });
+        let old: Commit = serde_json::from_value(old).unwrap();
+        assert_eq!(first.commit_date, old.commit_date);
+        assert_eq!(applied.author_date, old.author_date);
+        let old = serde_json::json!({ "commit_date": "null" });
+        assert_eq!(undated, serde_json::from_value::<Commit>(old).unwrap());
+    }
+
+    #[cfg(not(feature = "dates"))]
+    #[test]
+    fn commit_dates_are_kept_raw() {
+        let origin = fixture("raw_dates_origin");
+        commit_at(&origin, "a.txt", "first", "2022-03-01 10:30:00 +0300");
+        let dir = clone(&origin, "raw_dates");
+
+        let commits = Info::new(&dir).commit_info().unwrap().commits.unwrap();
+
+        assert_eq!(
+            Some("2022-03-01 10:30:00 +0300".to_string()),
+            commits[0].commit_date
+        );
+        let json = serde_json::to_value(&commits[0]).unwrap();
+        assert_eq!("2022-03-01 10:30:00 +0300", json["commit_date"]);
+    }
+
+    #[test]
+    fn contributors_are_counted_and_sorted() {
+        let dir = fixture("contributors");
+        commit_as(&dir, "a.txt", "first", "Ann", "ann@example.com");
+        commit_as(&dir, "b.txt", "second", "Bob", "bob@example.com");
+        commit_as(&dir, "c.txt", "third", "ann", "ANN@example.com");
+        commit_as(&dir, "d.txt", "fourth", "Ann", "ann@example.com");
+
+        let contributors = Info::new(&dir).contributors().unwrap();
+
+        assert_eq!(2, contributors.len());
+        assert_eq!("Ann", contributors[0].name);
+        assert_eq!(3, contributors[0].commit_count);
+        assert_eq!("bob@example.com", contributors[1].email);
+        assert_eq!(1, contributors[1].commit_count);
+
+        assert!(Info::new(fixture("contributors_empty"))
+            .contributors()
+            .unwrap()
+            .is_empty());
+    }
+
+    // Checks that `backend` reports what the git binary does on a clone with local commits and changes
+    #[cfg(any(feature = "backend-git2", feature = "backend-gix"))]
+    fn assert_matches_git_cli(name: &str, backend: impl super::GitBackend + Copy + 'static) {
+        use super::{GitBackend, GitCli, LogQuery};
+
+        let origin = fixture(&format!("{}_origin", name));
+        commit(&origin, "a.txt", "first");
+        let dir = clone(&origin, &format!("{}_clone", name));
+        let repo = std::path::Path::new(&dir);
+        fs::write(format!("{}/c.bin", dir), [0u8, 159, 146, 150]).unwrap();
+        commit(
+            &dir,
+            "b.txt",
+            "second\n\nFixes #12\n\nSigned-off-by: Ann <ann@example.com>",
+        );
+        run_cmd!(cd ${dir}; git notes add -m "reviewed" HEAD).unwrap();
+        run_cmd!(cd ${dir}; git tag v0.1; git tag -a -m release v0.2; git branch feature HEAD~1)
+            .unwrap();
+        // commit() writes the message into the file
+        commit(&dir, ".gitignore", "*.log");
+        fs::write(format!("{}/a.txt", dir), "changed").unwrap();
+        fs::write(format!("{}/d.txt", dir), "new").unwrap();
+        fs::write(format!("{}/e.txt", dir), "untracked").unwrap();
+        fs::write(format!("{}/debug.log", dir), "ignored").unwrap();
+        fs::create_dir_all(format!("{}/new/nested", dir)).unwrap();
+        fs::write(format!("{}/new/nested/f.txt", dir), "untracked").unwrap();
+        run_cmd!(cd ${dir}; git add d.txt).unwrap();
+
+        let cli = Info::new(&dir).with_backend(GitCli).gather().unwrap();
+        let other = Info::new(&dir).with_backend(backend).gather().unwrap();
+
+        assert_eq!(cli.commits, other.commits);
+        assert_eq!(cli.branch, other.branch);
+        assert_eq!(cli.head_hash, other.head_hash);
+        assert_eq!(cli.head_short_hash, other.head_short_hash);
+        assert_eq!(cli.default_branch, other.default_branch);
+
+        let (cli, other) = (cli.status.unwrap(), other.status.unwrap());
+        assert_eq!(vec!["d.txt"], other.staged);
+        assert_eq!(vec!["a.txt"], other.unstaged);
+        assert_eq!(vec!["e.txt", "new/"], other.untracked);
+        assert_eq!(vec!["debug.log"], other.ignored);
+        assert_eq!(cli.staged, other.staged);
+        assert_eq!(cli.unstaged, other.unstaged);
+        assert_eq!(cli.untracked, other.untracked);
+        assert_eq!(cli.ignored, other.ignored);
+        assert_eq!(cli.entries, other.entries);
+        assert_eq!(cli.summary, other.summary);
+        assert_eq!(cli.branch, other.branch);
+        assert_eq!(Some(2), other.branch.unwrap().ahead);
+
+        let query = LogQuery {
+            revision: "HEAD".into(),
+            ..Default::default()
+        };
+        let commits = backend.log(repo, &query).unwrap();
+        assert_eq!(GitCli.log(repo, &query).unwrap(), commits);
+        assert_eq!(None, commits[1].notes);
+
+        let query = LogQuery {
+            notes: true,
+            ..query
+        };
+        let commits = backend.log(repo, &query).unwrap();
+        assert_eq!(GitCli.log(repo, &query).unwrap(), commits);
+        assert_eq!(3, commits.len());
+        assert_eq!(Some("reviewed".to_string()), commits[1].notes);
+        assert_eq!(vec!["#12"], commits[1].issue_refs);
+
+        run_cmd!(cd ${dir}; git notes --ref qa add -m "passed" HEAD~2).unwrap();
+        let qa = LogQuery {
+            notes_ref: Some("refs/notes/qa".into()),
+            ..query.clone()
+        };
+        let commits = backend.log(repo, &qa).unwrap();
+        assert_eq!(GitCli.log(repo, &qa).unwrap(), commits);
+        assert_eq!(Some("passed".to_string()), commits[2].notes);
+        assert_eq!(None, commits[1].notes);
+
+        let signatures = LogQuery {
+            signatures: true,
+            ..query.clone()
+        };
+        let commits = backend.log(repo, &signatures).unwrap();
+        assert_eq!(GitCli.log(repo, &signatures).unwrap(), commits);
+        assert_eq!(Some(SignatureStatus::Unsigned), commits[0].signature);
+
+        let query = LogQuery {
+            pathspecs: vec!["b.txt".into()],
+            ..query
+        };
+        let commits = backend.log(repo, &query).unwrap();
+        assert_eq!(GitCli.log(repo, &query).unwrap(), commits);
+        assert_eq!(1, commits.len());
+
+        let query = LogQuery {
+            revision: "origin/main..HEAD".into(),
+            pathspecs: vec![],
+            ..query
+        };
+        let commits = backend.log(repo, &query).unwrap();
+        assert_eq!(GitCli.log(repo, &query).unwrap(), commits);
+        assert_eq!(2, commits.len());
+
+        let query = LogQuery {
+            revision: "HEAD".into(),
+            author: Some("^Test User <test@".into()),
+            committer: Some("@example\\.com>$".into()),
+            since: Some(0),
+            ..query
+        };
+        let commits = backend.log(repo, &query).unwrap();
+        assert_eq!(GitCli.log(repo, &query).unwrap(), commits);
+        assert_eq!(3, commits.len());
+
+        let stats = LogQuery {
+            stats: true,
+            ..query.clone()
+        };
+        let commits = backend.log(repo, &stats).unwrap();
+        assert_eq!(GitCli.log(repo, &stats).unwrap(), commits);
+        assert_eq!(Some(2), commits[1].stats.map(|s| s.files_changed));
+        let stats = LogQuery {
+            pathspecs: vec!["c.bin".into()],
+            ..stats
+        };
+        assert_eq!(
+            GitCli.log(repo, &stats).unwrap(),
+            backend.log(repo, &stats).unwrap()
+        );
+        let files = LogQuery {
+            stats: false,
+            files: true,
+            ..stats
+        };
+        assert_eq!(
+            GitCli.log(repo, &files).unwrap(),
+            backend.log(repo, &files).unwrap()
+        );
+
+        let grep = LogQuery {
+            grep: Some(CommitSearch::new("^A").with_ignore_case(true)),
+            author: Some("^test".into()),
+            ..query.clone()
+        };
+        assert_eq!(
+            GitCli.log(repo, &grep).unwrap(),
+            backend.log(repo, &grep).unwrap()
+        );
+
+        for query in [
+            LogQuery {
+                until: Some(0),
+                ..query.clone()
+            },
+            LogQuery {
+                author: Some("nobody".into()),
+                ..query.clone()
+            },
+            LogQuery {
+                committer: Some("nobody".into()),
+                ..query.clone()
+            },
+            LogQuery {
+                grep: Some(CommitSearch::new("(").with_fixed_string(true)),
+                ..query
+            },
+        ] {
+            assert_eq!(Vec::<Commit>::new(), backend.log(repo, &query).unwrap());
+            assert!(GitCli.log(repo, &query).unwrap().is_empty());
+        }
+
+        run_cmd!(cd ${dir}; git checkout -q --detach HEAD~1).unwrap();
+        assert_eq!(GitCli.head_name(repo), backend.head_name(repo));
+        run_cmd!(cd ${dir}; git tag v1.0).unwrap();
+        assert_eq!(GitCli.head_name(repo), backend.head_name(repo));
+    }
+
+    #[cfg(feature = "backend-git2")]
+    #[test]
+    fn git2_backend_matches_the_git_cli() {
+        assert_matches_git_cli("git2", super::Git2Backend);
+    }
+
+    #[cfg(feature = "backend-gix")]
+    #[test]
+    fn gix_backend_matches_the_git_cli() {
+        assert_matches_git_cli("gix", super::GixBackend);
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn async_versions_match_the_sync_ones() {
+        let origin = fixture("async_origin");
+        commit(&origin, "a.txt", "first");
+        let dir = clone(&origin, "async_clone");
+        commit(&dir, "b.txt", "second");
+        fs::write(format!("{}/a.txt", dir), "changed").unwrap();
+        fs::write(format!("{}/c.txt", dir), "new").unwrap();
+        let info = Info::new(&dir);
+
+        let (sync, not_blocking) = (
+            info.commit_info().unwrap(),
+            info.commit_info_async().await.unwrap(),
+        );
+        assert_eq!(sync.commits, not_blocking.commits);
+        assert_eq!(sync.branch, not_blocking.branch);
+        assert_eq!(sync.default_branch, not_blocking.default_branch);
+        assert_eq!(sync.head_hash, not_blocking.head_hash);
+        assert_eq!(sync.head_short_hash, not_blocking.head_short_hash);
+
+        let local = info.clone().with_ref("HEAD").with_commit_limit(1);
+        assert_eq!(
+            vec!["second"],
+            messages(&local.commit_info_async().await.unwrap())
+        );
+
+        let sync = info.status_info().unwrap().status.unwrap();
+        let not_blocking = info.status_info_async().await.unwrap().status.unwrap();
+        assert_eq!(vec!["a.txt"], not_blocking.unstaged);
+        assert_eq!(sync.unstaged, not_blocking.unstaged);
+        assert_eq!(sync.untracked, not_blocking.untracked);
+        assert_eq!(sync.summary, not_blocking.summary);
+        assert_eq!(sync.git_dirty, not_blocking.git_dirty);
+        assert_eq!(sync.upstream(), not_blocking.upstream());
+
+        let plain = env::temp_dir();
+        assert!(matches!(
+            Info::new(&plain).status_info_async().await,
+            Err(CommitInfoError::NotARepository(_))
+        ));
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn async_versions_are_stopped_too() {
+        let dir = slow_status("slow_async");
+        let timeout = Duration::from_millis(300);
+
+        let started = Instant::now();
+        let info = Info::new(&dir).with_timeout(timeout);
+        assert!(matches!(
+            info.status_info_async().await,
+            Err(CommitInfoError::Timeout(_))
+        ));
+        assert!(started.elapsed() < Duration::from_secs(5));
+
+        let token = CancellationToken::new();
+        let info = Info::new(&dir).with_cancellation(token.clone());
+        let started = Instant::now();
+        let canceller = tokio::spawn(async move {
+            tokio::time::sleep(timeout).await;
+            token.cancel();
+        });
+        assert!(matches!(
+            info.status_info_async().await,
+            Err(CommitInfoError::Cancelled)
+        ));
+        assert!(started.elapsed() < Duration::from_secs(5));
+        canceller.await.unwrap();
+    }
+
+    #[test]
+    fn auto_backend_prefers_the_git_binary() {
+        use super::Backend;
+
+        assert_eq!(Backend::Cli, Backend::detect());
+        assert_eq!(Backend::Cli, Backend::fallback(true));
+        #[cfg(feature = "backend-git2")]
+        assert_eq!(Backend::Git2, Backend::fallback(false));
+        #[cfg(all(feature = "backend-gix", not(feature = "backend-git2")))]
+        assert_eq!(Backend::Gix, Backend::fallback(false));
     }
 }
@@ -0,0 +1,325 @@
+// Copyright 2022 Anthony Mugendi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::{
+    add_files, add_stats, command_output, files_args, log_args, parse_log, parse_name_status,
+    parse_numstat, spawn_error, stats_args, CancellationToken, Commit, CommitInfoError, Info,
+    LogQuery, RunOptions, Status, NO_PROMPT_ENV, POLL_INTERVAL, STATUS_ARGS,
+};
+use anyhow::anyhow;
+use std::{
+    future::{poll_fn, Future},
+    path::Path,
+    pin::pin,
+    process::Stdio,
+    task::Poll,
+};
+use tokio::process::Command;
+
+impl Info {
+    /// Same as [Info::commit_info], but runs git with ```tokio::process``` so it doesn't block the async runtime.
+    /// Enabled by the ```tokio``` feature. The ```git``` binary is always used, whichever backend is set with [Info::with_backend]
+    /// ## Example
+    /// ```
+    ///  # use commit_info::Info;
+    ///  # use std::env;
+    ///  # #[tokio::main(flavor = "current_thread")]
+    ///  # async fn main() -> anyhow::Result<()> {
+    ///  # let mut path = env::current_dir().unwrap();
+    ///  # path.push("test_project");
+    ///  # let dir = path.to_string_lossy().to_string();
+    ///  // let dir = "/path/to/repo"; <- Point to the location of t=your repo
+    ///  let commits_info = Info::new(&dir).commit_info_async().await?;
+    ///  println!("{:#?}", commits_info);
+    ///  # Ok(())
+    ///  # }
+    /// ```
+    pub async fn commit_info_async(&self) -> Result<Info, CommitInfoError> {
+        self.limited_async(async {
+            let mut git_info = self.clone();
+            git_info.require_repo()?;
+            let git = self.async_git();
+
+            if let Some(reference) = &self.reference {
+                if !git.verify_ref(reference).await {
+                    return Err(match git.head_hash().await {
+                        None => CommitInfoError::NoCommits,
+                        Some(_) => anyhow!("Unknown git reference: {}", reference).into(),
+                    });
+                }
+                git_info.branch = Some(reference.clone());
+                git_info.commits = self.top_commits_async(reference).await?;
+                return Ok(git_info);
+            }
+
+            git_info.default_branch = git.default_branch().await;
+            git_info.head_hash = git.head_hash().await;
+            git_info.head_short_hash = git
+                .output(&["rev-parse", "-q", "--verify", "--short", "HEAD"])
+                .await
+                .ok();
+
+            // prefer the remote's default branch, then any remote branch
+            let branch = match &git_info.default_branch {
+                Some(default_branch) => Some(format!("origin/{}", default_branch)),
+                None => git.remote_branch().await,
+            };
+            // bare clones know the remote's default branch without tracking it
+            let branch = match branch {
+                Some(branch) if git.verify_ref(&branch).await => Some(branch),
+                _ => None,
+            };
+
+            match branch {
+                Some(branch) => {
+                    git_info.commits = self.top_commits_async(&branch).await?;
+                    git_info.branch = Some(branch);
+                }
+                None => {
+                    // no remote, so read the local history instead
+                    git_info.branch = git.head_name().await;
+                    // a fresh repo has no HEAD to read yet
+                    if git_info.head_hash.is_some() {
+                        git_info.commits = self.top_commits_async("HEAD").await?;
+                    }
+                }
+            }
+            Ok(git_info)
+        })
+        .await
+    }
+
+    /// Same as [Info::status_info], but runs git with ```tokio::process``` so it doesn't block the async runtime.
+    /// Enabled by the ```tokio``` feature. The ```git``` binary is always used, whichever backend is set with [Info::with_backend]
+    /// ## Example
+    /// ```
+    ///  # use commit_info::Info;
+    ///  # use std::env;
+    ///  # #[tokio::main(flavor = "current_thread")]
+    ///  # async fn main() -> anyhow::Result<()> {
+    ///  # let mut path = env::current_dir().unwrap();
+    ///  # path.push("test_project");
+    ///  # let dir = path.to_string_lossy().to_string();
+    ///  // let dir = "/path/to/repo"; <- Point to the location of t=your repo
+    ///  let status_info = Info::new(&dir).status_info_async().await?;
+    ///  println!("{:#?}", status_info);
+    ///  # Ok(())
+    ///  # }
+    /// ```
+    pub async fn status_info_async(&self) -> Result<Info, CommitInfoError> {
+        self.limited_async(async {
+            let mut git_info = self.clone();
+            git_info.require_repo()?;
+            // a bare repo has no working tree to compare against
+            if git_info.is_bare {
+                git_info.status = Some(Status::default());
+                return Ok(git_info);
+            }
+
+            // started first so the diff runs alongside the status
+            let git = self.async_git();
+            let diff = git
+                .command(&["diff", "--stat", "--"])
+                .stdout(Stdio::piped())
+                .stderr(Stdio::piped())
+                .spawn();
+            let porcelain = git.output(&STATUS_ARGS).await?;
+            let diff = match diff {
+                Ok(diff) => diff.wait_with_output().await,
+                Err(e) => Err(e),
+            }
+            .map_err(spawn_error);
+            let diff = command_output(&["diff", "--stat"], diff);
+
+            let mut status = Status::default();
+            status.summarize(&porcelain, diff);
+            git_info.status = Some(status);
+            Ok(git_info)
+        })
+        .await
+    }
+
+    // Runs work until it is done, the timeout of this Info passes or its cancellation token is cancelled.
+    // Dropping work kills the git command it is running
+    async fn limited_async<T>(
+        &self,
+        work: impl Future<Output = Result<T, CommitInfoError>>,
+    ) -> Result<T, CommitInfoError> {
+        let mut work = pin!(work);
+        // wakes the task up now and then to look at the token
+        let mut tick = tokio::time::interval(POLL_INTERVAL);
+        let guarded = poll_fn(|cx| {
+            let cancelled = self
+                .cancellation
+                .as_ref()
+                .map(CancellationToken::is_cancelled);
+            if cancelled == Some(true) {
+                return Poll::Ready(Err(CommitInfoError::Cancelled));
+            }
+            if let Poll::Ready(result) = work.as_mut().poll(cx) {
+                return Poll::Ready(result);
+            }
+            if cancelled.is_some() {
+                while tick.poll_tick(cx).is_ready() {}
+            }
+            Poll::Pending
+        });
+
+        match self.timeout {
+            Some(timeout) => tokio::time::timeout(timeout, guarded)
+                .await
+                .unwrap_or(Err(CommitInfoError::Timeout(timeout))),
+            None => guarded.await,
+        }
+    }
+
+    // Runs git for this Info without blocking
+    fn async_git(&self) -> AsyncGit<'_> {
+        AsyncGit {
+            dir: &self.dir,
+            options: self.run_options(),
+        }
+    }
+
+    // The latest commits of reference, up to the commit limit, or None if there are none
+    async fn top_commits_async(
+        &self,
+        reference: &str,
+    ) -> Result<Option<Vec<Commit>>, CommitInfoError> {
+        let query = LogQuery {
+            max_count: self.commit_limit,
+            ..self.log_query(reference)
+        };
+        let args = log_args(&query);
+        let args: Vec<&str> = args.iter().map(String::as_str).collect();
+        let mut commits = parse_log(&self.async_git().output(&args).await?)?;
+
+        if query.stats {
+            let args = stats_args(&query);
+            let args: Vec<&str> = args.iter().map(String::as_str).collect();
+            let numstat = self.async_git().output(&args).await?;
+            add_stats(&mut commits, parse_numstat(&numstat));
+        }
+        if query.files {
+            let args = files_args(&query);
+            let args: Vec<&str> = args.iter().map(String::as_str).collect();
+            let name_status = self.async_git().output(&args).await?;
+            add_files(&mut commits, parse_name_status(&name_status));
+        }
+
+        Ok(Some(commits).filter(|commits| !commits.is_empty()))
+    }
+}
+
+// Runs the git commands of an Info without blocking
+struct AsyncGit<'a> {
+    dir: &'a Path,
+    options: RunOptions,
+}
+
+impl AsyncGit<'_> {
+    // A git command running args in the repo, killed when dropped
+    fn command(&self, args: &[&str]) -> Command {
+        let mut command = Command::from(self.options.command(self.dir));
+        command.args(args).kill_on_drop(true);
+        command
+    }
+
+    // See RunOptions::output
+    async fn output(&self, args: &[&str]) -> Result<String, CommitInfoError> {
+        let output = self.command(args).output().await.map_err(spawn_error);
+        command_output(args, output)
+    }
+
+    // See RunOptions::remote_output
+    async fn remote_output(&self, args: &[&str]) -> Result<String, CommitInfoError> {
+        let mut command = self.command(args);
+        command.stdin(Stdio::null()).envs(NO_PROMPT_ENV);
+        command_output(args, command.output().await.map_err(spawn_error))
+    }
+
+    // Like output, but None when git fails or prints nothing
+    async fn output_ok(&self, args: &[&str]) -> Option<String> {
+        self.output(args)
+            .await
+            .ok()
+            .map(|output| output.trim().to_string())
+            .filter(|output| !output.is_empty())
+    }
+
+    async fn head_hash(&self) -> Option<String> {
+        self.output_ok(&["rev-parse", "-q", "--verify", "HEAD"])
+            .await
+    }
+
+    async fn verify_ref(&self, reference: &str) -> bool {
+        let commit = format!("{}^{{commit}}", reference);
+        self.output(&["rev-parse", "--verify", "-q", &commit])
+            .await
+            .is_ok()
+    }
+
+    // See GitCli::default_branch
+    async fn default_branch(&self) -> Option<String> {
+        let head = self
+            .output_ok(&["symbolic-ref", "--short", "-q", "refs/remotes/origin/HEAD"])
+            .await;
+        if let Some(branch) = head
+            .as_deref()
+            .and_then(|head| head.strip_prefix("origin/"))
+        {
+            return Some(branch.into());
+        }
+
+        // there is no point asking a remote that doesn't exist
+        self.output(&["remote", "get-url", "origin"]).await.ok()?;
+
+        let remote = self
+            .remote_output(&["remote", "show", "origin"])
+            .await
+            .ok()?;
+        remote
+            .lines()
+            .find_map(|line| line.trim().strip_prefix("HEAD branch: "))
+            .filter(|branch| *branch != "(unknown)")
+            .map(|branch| branch.into())
+    }
+
+    // See GitCli::remote_branch
+    async fn remote_branch(&self) -> Option<String> {
+        let branches = self.output(&["branch", "-r"]).await.ok()?;
+        branches
+            .lines()
+            .find(|branch| !branch.contains("HEAD"))
+            .map(|branch| branch.trim().to_string())
+            .filter(|branch| !branch.is_empty())
+    }
+
+    // See GitCli::head_name
+    async fn head_name(&self) -> Option<String> {
+        self.head_hash().await?;
+        for args in [
+            &["symbolic-ref", "--short", "-q", "HEAD"][..],
+            &["rev-parse", "--abbrev-ref", "@{upstream}"],
+            &["describe", "--tags"],
+        ] {
+            if let Some(name) = self.output_ok(args).await {
+                return Some(name);
+            }
+        }
+        self.output_ok(&["rev-parse", "--short", "HEAD"])
+            .await
+            .map(|hash| format!("(detached: {})", hash))
+    }
+}